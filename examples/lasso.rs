@@ -0,0 +1,34 @@
+use ark_test_curves::bls12_381::{Fr, G1Projective};
+use pazk::hyrax::Hyrax;
+use pazk::lasso::{operands_index, And32, DecomposableTable, Lasso, Lt32, Xor32};
+
+// every table splits the 32-bit operands into bytes and reads the result of
+// the operation on every pair of bytes in a subtable of 2^16 entries
+fn prove_and_verify<T: DecomposableTable<Fr>>(name: &str, table: T, op: impl Fn(u32, u32) -> u32) {
+    let operands = [
+        (0xdead_beef, 0x1234_5678),
+        (0xffff_0000, 0x00ff_ff00),
+        (7, 9),
+        (0x8000_0000, 0x7fff_ffff),
+    ];
+    let indices: Vec<u64> = operands
+        .iter()
+        .map(|(x, y)| operands_index(*x, *y))
+        .collect();
+    let lasso = Lasso::<Fr, Hyrax<G1Projective>, T>::new(table, 2, &mut ark_std::test_rng());
+    let (outputs, proof) = lasso.prove(&indices);
+    for ((x, y), output) in operands.iter().zip(&outputs) {
+        assert_eq!(*output, Fr::from(op(*x, *y)));
+        println!("{}({:#010x}, {:#010x}) = {:#x}", name, x, y, op(*x, *y));
+    }
+    match lasso.verify(&outputs, &proof) {
+        Ok(()) => println!("{}: {} lookups verified", name, indices.len()),
+        Err(err) => panic!("{}: {}", name, err),
+    }
+}
+
+fn main() {
+    prove_and_verify("and", And32, |x, y| x & y);
+    prove_and_verify("xor", Xor32, |x, y| x ^ y);
+    prove_and_verify("lt", Lt32, |x, y| (x < y) as u32);
+}
@@ -1,26 +1,23 @@
-use ark_ff::fields::{Fp64, MontBackend, MontConfig};
-use ark_poly::multivariate::Term;
-use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
-use ark_poly::DenseMVPolynomial;
+use field::F17;
+use pazk::parser::parse_polynomial;
 use pazk::sumcheck::{Prover, ProverMessage, Verifier};
 use trpl::{self, Receiver, Sender};
 
-#[derive(MontConfig)]
-#[modulus = "17"]
-#[generator = "3"]
-pub struct F17Config;
-pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+// `#[derive(MontConfig)]` puts its impls inside a function
+#[allow(non_local_definitions)]
+mod field {
+    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+
+    #[derive(MontConfig)]
+    #[modulus = "17"]
+    #[generator = "3"]
+    pub struct F17Config;
+    pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+}
 
 fn main() {
     // examples taken from SumCheck example in Thaler's book chp 4
-    let example_polynomial = SparsePolynomial::from_coefficients_vec(
-        3,
-        vec![
-            (F17::from(2), SparseTerm::new(vec![(0, 3)])),
-            (F17::from(1), SparseTerm::new(vec![(0, 1), (1, 1)])),
-            (F17::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
-        ],
-    );
+    let example_polynomial = parse_polynomial::<F17>("2*x0^3 + x0*x1 + x1*x2").unwrap();
 
     let (prover_tx, verifier_rx): (Sender<ProverMessage<F17>>, Receiver<ProverMessage<F17>>) =
         trpl::channel();
@@ -0,0 +1,476 @@
+//! FRI low degree test (Ben-Sasson, Bentov, Horesh, Riabzev 2018).
+//!
+//! The prover commits to the Reed-Solomon codeword of f over a coset of a
+//! radix-2 domain L_0. In every round, with folding factor k, the verifier
+//! sends β and the prover commits to the codeword of
+//!
+//!   f'(Y) = sum_{j < k} β^j f_j(Y),  where f(X) = sum_{j < k} X^j f_j(X^k)
+//!
+//! over L_{i+1} = {x^k : x in L_i}. Since f'(x^k) only depends on f at the k
+//! points of the coset x * <ζ>, every Merkle leaf stores a whole coset. Once
+//! the degree bound is small enough the prover sends the last polynomial in
+//! the clear, and the verifier checks the folding at random positions.
+//! The verifier challenges come from a Fiat-Shamir transcript.
+pub mod pcs;
+mod prover;
+mod verifier;
+
+pub use prover::{prove, prove_codeword};
+pub use verifier::{verify, FriQueries};
+
+use crate::merkle::MultiProof;
+use crate::univariate::{barycentric_evaluate, barycentric_weights};
+use ark_ff::{FftField, Field, PrimeField};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriParameters {
+    /// size of the evaluation domain over the degree bound, 1 / rate
+    pub blowup_factor: usize,
+    /// number of points folded into one per round
+    pub folding_factor: usize,
+    pub num_queries: usize,
+    /// the prover stops folding once the degree bound is at most this one
+    pub final_degree_bound: usize,
+}
+
+impl FriParameters {
+    pub fn new(
+        blowup_factor: usize,
+        folding_factor: usize,
+        num_queries: usize,
+        final_degree_bound: usize,
+    ) -> Self {
+        assert!(
+            blowup_factor.is_power_of_two() && blowup_factor >= 2,
+            "blowup factor must be a power of 2 greater than 1"
+        );
+        assert!(
+            folding_factor.is_power_of_two() && folding_factor >= 2,
+            "folding factor must be a power of 2 greater than 1"
+        );
+        assert!(num_queries > 0, "at least one query is needed");
+        assert!(
+            final_degree_bound > 0,
+            "final degree bound must be positive"
+        );
+        FriParameters {
+            blowup_factor,
+            folding_factor,
+            num_queries,
+            final_degree_bound,
+        }
+    }
+
+    /// Number of committed layers for polynomials of degree < degree_bound.
+    /// The first layer is always committed, so that even a polynomial already
+    /// below the final bound is checked against a codeword.
+    pub fn num_layers(&self, degree_bound: usize) -> usize {
+        self.fold_bound(degree_bound).0
+    }
+
+    /// Degree bound of the polynomial sent in the clear
+    pub fn final_bound(&self, degree_bound: usize) -> usize {
+        self.fold_bound(degree_bound).1
+    }
+
+    /// Size of the domain of the first layer, degree_bound * blowup_factor but
+    /// at least one coset of folding_factor points
+    pub fn domain_size(&self, degree_bound: usize) -> usize {
+        (degree_bound * self.blowup_factor).max(self.folding_factor)
+    }
+
+    // number of layers and degree bound after folding them, rounding up so
+    // that a bound below the folding factor does not vanish. A layer is only
+    // committed if its codeword has at least one coset to fold.
+    fn fold_bound(&self, degree_bound: usize) -> (usize, usize) {
+        let k = self.folding_factor;
+        let mut layers = 0;
+        let mut bound = degree_bound;
+        let mut size = self.domain_size(degree_bound);
+        while (layers == 0 || bound > self.final_degree_bound) && size >= k {
+            bound = bound.div_ceil(k);
+            size /= k;
+            layers += 1;
+        }
+        (layers, bound)
+    }
+
+    /// Estimates the bits of security of the test for the given field size
+    pub fn soundness<F: Field>(&self, degree_bound: usize) -> SoundnessEstimate {
+        let field_bits = F::BasePrimeField::MODULUS_BIT_SIZE as f64 * F::extension_degree() as f64;
+        let rate = 1.0 / self.blowup_factor as f64;
+        let queries = self.num_queries as f64;
+        let domain_size = self.domain_size(degree_bound) as f64;
+
+        // union bound over the folding rounds, as in the ethSTARK analysis
+        let layers = self.num_layers(degree_bound).max(1) as f64;
+        let commit_bits = field_bits - (domain_size * self.folding_factor as f64 * layers).log2();
+
+        SoundnessEstimate {
+            // unique decoding radius: every query catches a far word with
+            // probability at least (1 - rate) / 2
+            query_bits_proven: -queries * ((1.0 + rate) / 2.0).log2(),
+            // the rate to the power of the number of queries
+            query_bits_conjectured: -queries * rate.log2(),
+            commit_bits,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoundnessEstimate {
+    pub query_bits_proven: f64,
+    pub query_bits_conjectured: f64,
+    pub commit_bits: f64,
+}
+
+impl SoundnessEstimate {
+    pub fn proven_bits(&self) -> f64 {
+        self.query_bits_proven.min(self.commit_bits)
+    }
+
+    pub fn conjectured_bits(&self) -> f64 {
+        self.query_bits_conjectured.min(self.commit_bits)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FriProof<F: Field, D: CanonicalSerialize + CanonicalDeserialize> {
+    layer_roots: Vec<D>,
+    final_poly: Vec<F>,
+    openings: Vec<MultiProof<F, D>>,
+}
+
+impl<F: Field, D: CanonicalSerialize + CanonicalDeserialize> FriProof<F, D> {
+    /// Merkle root of the codeword of the tested polynomial
+    pub fn commitment(&self) -> Option<&D> {
+        self.layer_roots.first()
+    }
+
+    /// Coefficients of the polynomial of the last layer
+    pub fn final_poly(&self) -> &[F] {
+        &self.final_poly
+    }
+}
+
+/// Domain of the first layer: a coset of the subgroup of the given size, so
+/// the domain never contains 0 nor the points outside of it we may want to
+/// open polynomials at
+pub fn evaluation_domain<F: FftField>(size: usize) -> Radix2EvaluationDomain<F> {
+    Radix2EvaluationDomain::new_coset(size, F::GENERATOR)
+        .expect("field does not have a domain of this size")
+}
+
+// domain of the next layer, {x^k : x in domain}
+fn fold_domain<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    folding_factor: usize,
+) -> Radix2EvaluationDomain<F> {
+    let offset = domain.coset_offset().pow([folding_factor as u64]);
+    Radix2EvaluationDomain::new_coset(domain.size() / folding_factor, offset).unwrap()
+}
+
+// indexes in the domain of the coset stored in the given leaf
+fn coset_indices(leaf: usize, domain_size: usize, folding_factor: usize) -> Vec<usize> {
+    let num_leaves = domain_size / folding_factor;
+    (0..folding_factor).map(|s| leaf + s * num_leaves).collect()
+}
+
+// Merkle leaves of a layer, leaf t holding the values over the coset of
+// domain element t
+fn coset_leaves<F: Copy>(codeword: &[F], folding_factor: usize) -> Vec<Vec<F>> {
+    (0..codeword.len() / folding_factor)
+        .map(|leaf| {
+            coset_indices(leaf, codeword.len(), folding_factor)
+                .into_iter()
+                .map(|i| codeword[i])
+                .collect()
+        })
+        .collect()
+}
+
+// f'(x^k), interpolating f over the coset of x and evaluating at β
+fn fold_coset<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    leaf: usize,
+    values: &[F],
+    beta: F,
+) -> F {
+    let points: Vec<F> = coset_indices(leaf, domain.size(), values.len())
+        .into_iter()
+        .map(|i| domain.element(i))
+        .collect();
+    let weights = barycentric_weights(&points);
+    barycentric_evaluate(&points, values, &weights, beta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::{Blake3Hasher, Poseidon, Sha256Hasher};
+    use crate::transcript::Transcript;
+    use ark_ff::UniformRand;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn setup(degree_bound: usize) -> DensePolynomial<Fr> {
+        DensePolynomial::rand(degree_bound - 1, &mut test_rng())
+    }
+
+    #[test]
+    fn test_fold_matches_split() {
+        // f(X) = f_0(X^2) + X f_1(X^2) => f'(Y) = f_0(Y) + β f_1(Y)
+        let poly = setup(16);
+        let even = DensePolynomial::from_coefficients_vec(
+            poly.coeffs.iter().step_by(2).copied().collect(),
+        );
+        let odd = DensePolynomial::from_coefficients_vec(
+            poly.coeffs.iter().skip(1).step_by(2).copied().collect(),
+        );
+        let beta = Fr::rand(&mut test_rng());
+        let folded = &even + &(&odd * beta);
+
+        let domain = evaluation_domain::<Fr>(64);
+        let codeword = domain.fft(&poly.coeffs);
+        let next = fold_domain(&domain, 2);
+        for leaf in 0..32 {
+            let values: Vec<Fr> = coset_indices(leaf, 64, 2)
+                .iter()
+                .map(|i| codeword[*i])
+                .collect();
+            assert_eq!(
+                fold_coset(&domain, leaf, &values, beta),
+                folded.evaluate(&next.element(leaf))
+            );
+        }
+    }
+
+    #[test]
+    fn test_fri() {
+        for folding_factor in [2, 4, 8] {
+            let params = FriParameters::new(4, folding_factor, 20, 2);
+            let poly = setup(64);
+            let proof = prove(
+                &params,
+                &Sha256Hasher,
+                &poly,
+                64,
+                &mut Transcript::new(b"fri"),
+            );
+            assert_eq!(proof.layer_roots.len(), params.num_layers(64));
+            assert!(proof.final_poly.len() <= params.final_bound(64));
+            let queries = verify(
+                &params,
+                &Sha256Hasher,
+                64,
+                &proof,
+                &mut Transcript::new(b"fri"),
+            );
+            assert!(queries.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_fri_small_degree_bound() {
+        // degree bound below the folding factor, and below the final bound
+        for (degree_bound, final_degree_bound) in [(2, 1), (4, 8)] {
+            let params = FriParameters::new(4, 4, 10, final_degree_bound);
+            assert_eq!(params.num_layers(degree_bound), 1);
+            assert_eq!(params.final_bound(degree_bound), 1);
+            let poly = setup(degree_bound);
+            let proof = prove(
+                &params,
+                &Sha256Hasher,
+                &poly,
+                degree_bound,
+                &mut Transcript::new(b"fri"),
+            );
+            let queries = verify(
+                &params,
+                &Sha256Hasher,
+                degree_bound,
+                &proof,
+                &mut Transcript::new(b"fri"),
+            )
+            .unwrap();
+            assert!(!queries.indices.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_fri_codeword_smaller_than_folding_factor() {
+        // folding 32 points by 8 leaves 4, too few for another layer, and a
+        // degree bound of 1 with blowup 2 would give a domain of 2 < 4 points
+        for (params, degree_bound, layers, final_bound) in [
+            (FriParameters::new(2, 8, 10, 1), 16, 1, 2),
+            (FriParameters::new(2, 4, 10, 1), 1, 1, 1),
+        ] {
+            assert_eq!(params.num_layers(degree_bound), layers);
+            assert_eq!(params.final_bound(degree_bound), final_bound);
+            let poly = setup(degree_bound);
+            let proof = prove(
+                &params,
+                &Sha256Hasher,
+                &poly,
+                degree_bound,
+                &mut Transcript::new(b"fri"),
+            );
+            let result = verify(
+                &params,
+                &Sha256Hasher,
+                degree_bound,
+                &proof,
+                &mut Transcript::new(b"fri"),
+            );
+            assert!(result.is_ok(), "{:?}", result.err());
+        }
+    }
+
+    #[test]
+    fn test_fri_other_hashers() {
+        let params = FriParameters::new(2, 2, 10, 4);
+        let poly = setup(32);
+        let proof = prove(
+            &params,
+            &Blake3Hasher,
+            &poly,
+            32,
+            &mut Transcript::new(b"fri"),
+        );
+        assert!(verify(
+            &params,
+            &Blake3Hasher,
+            32,
+            &proof,
+            &mut Transcript::new(b"fri")
+        )
+        .is_ok());
+
+        let poseidon = Poseidon::<Fr>::new();
+        let proof = prove(&params, &poseidon, &poly, 32, &mut Transcript::new(b"fri"));
+        assert!(verify(&params, &poseidon, 32, &proof, &mut Transcript::new(b"fri")).is_ok());
+    }
+
+    #[test]
+    fn test_fri_queries_open_first_layer() {
+        let params = FriParameters::new(4, 2, 8, 1);
+        let poly = setup(16);
+        let proof = prove(
+            &params,
+            &Sha256Hasher,
+            &poly,
+            16,
+            &mut Transcript::new(b"fri"),
+        );
+        let queries = verify(
+            &params,
+            &Sha256Hasher,
+            16,
+            &proof,
+            &mut Transcript::new(b"fri"),
+        )
+        .unwrap();
+        let domain = evaluation_domain::<Fr>(64);
+        for (index, value) in queries.indices.iter().zip(queries.values.iter()) {
+            assert_eq!(*value, poly.evaluate(&domain.element(*index)));
+        }
+    }
+
+    #[test]
+    fn test_fri_degree_too_high() {
+        // the prover claims degree < 16 for a polynomial of degree 31
+        let params = FriParameters::new(4, 2, 20, 1);
+        let poly = setup(32);
+        let domain = evaluation_domain::<Fr>(64);
+        let codeword = domain.fft(&poly.coeffs);
+        let proof = prove_codeword(
+            &params,
+            &Sha256Hasher,
+            codeword,
+            16,
+            &mut Transcript::new(b"fri"),
+        );
+        assert!(verify(
+            &params,
+            &Sha256Hasher,
+            16,
+            &proof,
+            &mut Transcript::new(b"fri")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_fri_tampered_proof() {
+        let params = FriParameters::new(4, 2, 10, 2);
+        let poly = setup(32);
+        let proof = prove(
+            &params,
+            &Sha256Hasher,
+            &poly,
+            32,
+            &mut Transcript::new(b"fri"),
+        );
+
+        let mut wrong_final = proof.clone();
+        wrong_final.final_poly[0] += Fr::ONE;
+        assert!(verify(
+            &params,
+            &Sha256Hasher,
+            32,
+            &wrong_final,
+            &mut Transcript::new(b"fri")
+        )
+        .is_err());
+
+        let mut wrong_leaf = proof.clone();
+        wrong_leaf.openings[1].leaves[0][0] += Fr::ONE;
+        assert!(verify(
+            &params,
+            &Sha256Hasher,
+            32,
+            &wrong_leaf,
+            &mut Transcript::new(b"fri")
+        )
+        .is_err());
+
+        assert!(verify(
+            &params,
+            &Sha256Hasher,
+            32,
+            &proof,
+            &mut Transcript::new(b"other")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let params = FriParameters::new(4, 4, 10, 4);
+        let poly = setup(64);
+        let proof = prove(
+            &params,
+            &Sha256Hasher,
+            &poly,
+            64,
+            &mut Transcript::new(b"fri"),
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = FriProof::<Fr, [u8; 32]>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+    }
+
+    #[test]
+    fn test_soundness_estimate() {
+        let params = FriParameters::new(8, 2, 40, 1);
+        let estimate = params.soundness::<Fr>(1 << 20);
+        assert!((estimate.query_bits_conjectured - 120.0).abs() < 1e-9);
+        assert!(estimate.proven_bits() < estimate.conjectured_bits());
+        assert!(estimate.commit_bits > 200.0);
+    }
+}
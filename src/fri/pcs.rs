@@ -0,0 +1,459 @@
+//! Polynomial commitment from FRI. A polynomial is committed with the Merkle
+//! root of its codeword, and opened at a point z outside of the evaluation
+//! domain with a low degree test on the quotient (f(X) - f(z)) / (X - z),
+//! which is a polynomial exactly when the claimed evaluation is right. The
+//! verifier checks the quotient against the committed codeword at the
+//! positions queried by FRI (DEEP-FRI, Ben-Sasson, Goldberg, Kopparty, Saraf 2019).
+//!
+//! Several polynomials and points are opened with a single test on a random
+//! linear combination of all the quotients.
+use super::{
+    coset_leaves, evaluation_domain, prove, verify as verify_fri, FriParameters, FriProof,
+};
+use crate::merkle::{MerkleHasher, MerkleTree, MultiProof};
+use crate::pcs::PolynomialCommitment;
+use crate::transcript::Transcript;
+use crate::univariate::divide_by_linear;
+use ark_ff::FftField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+/// Commitment scheme for polynomials of degree < degree_bound
+#[derive(Clone, Debug)]
+pub struct FriPcs<H> {
+    params: FriParameters,
+    hasher: H,
+    degree_bound: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FriCommitment<D: CanonicalSerialize + CanonicalDeserialize> {
+    pub root: D,
+}
+
+/// Prover data of a commitment
+pub struct CommittedPolynomial<F: FftField, H: MerkleHasher<F>> {
+    poly: DensePolynomial<F>,
+    tree: MerkleTree<F, H>,
+}
+
+impl<F: FftField, H: MerkleHasher<F>> CommittedPolynomial<F, H> {
+    pub fn commitment(&self) -> FriCommitment<H::Digest> {
+        FriCommitment {
+            root: self.tree.root(),
+        }
+    }
+
+    pub fn polynomial(&self) -> &DensePolynomial<F> {
+        &self.poly
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FriOpeningProof<F: FftField, D: CanonicalSerialize + CanonicalDeserialize> {
+    fri: FriProof<F, D>,
+    /// openings of every committed codeword at the positions queried by FRI
+    openings: Vec<MultiProof<F, D>>,
+}
+
+impl<H: Clone> FriPcs<H> {
+    pub fn new(params: FriParameters, hasher: H, degree_bound: usize) -> Self {
+        assert!(
+            degree_bound.is_power_of_two(),
+            "degree bound must be a power of 2"
+        );
+        FriPcs {
+            params,
+            hasher,
+            degree_bound,
+        }
+    }
+
+    pub fn degree_bound(&self) -> usize {
+        self.degree_bound
+    }
+
+    pub fn commit<F: FftField>(&self, poly: &DensePolynomial<F>) -> CommittedPolynomial<F, H>
+    where
+        H: MerkleHasher<F>,
+    {
+        assert!(
+            poly.coeffs.len() <= self.degree_bound,
+            "polynomial exceeds the degree bound"
+        );
+        let domain = evaluation_domain::<F>(self.params.domain_size(self.degree_bound));
+        let codeword = domain.fft(&poly.coeffs);
+        CommittedPolynomial {
+            poly: poly.clone(),
+            tree: MerkleTree::new(
+                self.hasher.clone(),
+                coset_leaves(&codeword, self.params.folding_factor),
+            ),
+        }
+    }
+
+    /// Opens the polynomial at z, returning f(z) and the proof
+    pub fn open<F: FftField>(
+        &self,
+        committed: &CommittedPolynomial<F, H>,
+        z: F,
+        transcript: &mut Transcript,
+    ) -> (F, FriOpeningProof<F, H::Digest>)
+    where
+        H: MerkleHasher<F>,
+    {
+        let (values, proof) = self.batch_open(&[committed], &[z], transcript);
+        (values[0][0], proof)
+    }
+
+    pub fn verify<F: FftField>(
+        &self,
+        commitment: &FriCommitment<H::Digest>,
+        z: F,
+        value: F,
+        proof: &FriOpeningProof<F, H::Digest>,
+        transcript: &mut Transcript,
+    ) -> bool
+    where
+        H: MerkleHasher<F>,
+    {
+        self.batch_verify(
+            std::slice::from_ref(commitment),
+            &[z],
+            &[vec![value]],
+            proof,
+            transcript,
+        )
+    }
+
+    /// Opens every polynomial at every point, values[i][j] being f_i(z_j)
+    pub fn batch_open<F: FftField>(
+        &self,
+        committed: &[&CommittedPolynomial<F, H>],
+        points: &[F],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<F>>, FriOpeningProof<F, H::Digest>)
+    where
+        H: MerkleHasher<F>,
+    {
+        let values: Vec<Vec<F>> = committed
+            .iter()
+            .map(|c| points.iter().map(|z| c.poly.evaluate(z)).collect())
+            .collect();
+        let commitments: Vec<_> = committed.iter().map(|c| c.commitment()).collect();
+        let alpha = absorb_claims(&commitments, points, &values, transcript);
+
+        // sum_{i, j} alpha^{i * |points| + j} (f_i(X) - f_i(z_j)) / (X - z_j)
+        let mut quotient = DensePolynomial::from_coefficients_vec(vec![]);
+        let mut power = F::ONE;
+        for c in committed {
+            for z in points {
+                let (q, _) = divide_by_linear(&c.poly, *z);
+                quotient += (power, &q);
+                power *= alpha;
+            }
+        }
+
+        let fri = prove(
+            &self.params,
+            &self.hasher,
+            &quotient,
+            self.degree_bound,
+            transcript,
+        );
+        let leaves = &fri.openings[0].indices;
+        let openings = committed
+            .iter()
+            .map(|c| c.tree.open_batch(leaves))
+            .collect();
+        (values, FriOpeningProof { fri, openings })
+    }
+
+    pub fn batch_verify<F: FftField>(
+        &self,
+        commitments: &[FriCommitment<H::Digest>],
+        points: &[F],
+        values: &[Vec<F>],
+        proof: &FriOpeningProof<F, H::Digest>,
+        transcript: &mut Transcript,
+    ) -> bool
+    where
+        H: MerkleHasher<F>,
+    {
+        if values.len() != commitments.len()
+            || values.iter().any(|v| v.len() != points.len())
+            || proof.openings.len() != commitments.len()
+        {
+            return false;
+        }
+        let alpha = absorb_claims(commitments, points, values, transcript);
+        let queries = match verify_fri(
+            &self.params,
+            &self.hasher,
+            self.degree_bound,
+            &proof.fri,
+            transcript,
+        ) {
+            Ok(queries) => queries,
+            Err(_) => return false,
+        };
+
+        let domain = evaluation_domain::<F>(self.params.domain_size(self.degree_bound));
+        let k = self.params.folding_factor;
+        let num_leaves = domain.size() / k;
+        let leaves: Vec<usize> = queries.indices.iter().map(|i| i % num_leaves).collect();
+        for (commitment, opening) in commitments.iter().zip(proof.openings.iter()) {
+            if !MerkleTree::verify(&self.hasher, &commitment.root, num_leaves, &leaves, opening) {
+                return false;
+            }
+        }
+
+        for (index, quotient) in queries.indices.iter().zip(queries.values) {
+            let x = domain.element(*index);
+            // the leaf holds the coset of index % num_leaves
+            let slot = index / num_leaves;
+            let mut expected = F::ZERO;
+            let mut power = F::ONE;
+            for (opening, evals) in proof.openings.iter().zip(values) {
+                let f_x = opening.leaf(index % num_leaves).unwrap()[slot];
+                for (z, y) in points.iter().zip(evals) {
+                    let denominator = match (x - z).inverse() {
+                        Some(inverse) => inverse,
+                        // the point is in the evaluation domain
+                        None => return false,
+                    };
+                    expected += power * (f_x - y) * denominator;
+                    power *= alpha;
+                }
+            }
+            if expected != quotient {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// binds the claims to the transcript and draws the batching challenge
+fn absorb_claims<F: FftField, D: CanonicalSerialize + CanonicalDeserialize>(
+    commitments: &[FriCommitment<D>],
+    points: &[F],
+    values: &[Vec<F>],
+    transcript: &mut Transcript,
+) -> F {
+    transcript.append_serializable(b"fri_pcs_commitments", commitments);
+    transcript.append_serializable(b"fri_pcs_points", points);
+    transcript.append_serializable(b"fri_pcs_values", values);
+    transcript.challenge_field(b"fri_pcs_alpha")
+}
+
+impl<F: FftField, H: MerkleHasher<F> + Clone + Default> PolynomialCommitment<F> for FriPcs<H> {
+    type Polynomial = DensePolynomial<F>;
+    type Point = F;
+    type Commitment = FriCommitment<H::Digest>;
+    type ProverData = CommittedPolynomial<F, H>;
+    type Proof = FriOpeningProof<F, H::Digest>;
+    type BatchProof = FriOpeningProof<F, H::Digest>;
+
+    /// Rate 1/4, folding by 2 down to constants, and 32 queries
+    fn setup<R: RngCore>(size: usize, _rng: &mut R) -> Self {
+        FriPcs::new(FriParameters::new(4, 2, 32, 1), H::default(), size)
+    }
+
+    fn commit(&self, poly: &DensePolynomial<F>) -> (Self::Commitment, Self::ProverData) {
+        let committed = FriPcs::commit(self, poly);
+        (committed.commitment(), committed)
+    }
+
+    fn open(
+        &self,
+        _poly: &DensePolynomial<F>,
+        data: &Self::ProverData,
+        point: &F,
+        transcript: &mut Transcript,
+    ) -> (F, Self::Proof) {
+        FriPcs::open(self, data, *point, transcript)
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        point: &F,
+        value: F,
+        proof: &Self::Proof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        FriPcs::verify(self, commitment, *point, value, proof, transcript)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&DensePolynomial<F>, &Self::ProverData)],
+        points: &[&F],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<F>>, Self::BatchProof) {
+        let committed: Vec<_> = polys.iter().map(|(_, data)| *data).collect();
+        let points: Vec<F> = points.iter().map(|z| **z).collect();
+        FriPcs::batch_open(self, &committed, &points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[Self::Commitment],
+        points: &[&F],
+        values: &[Vec<F>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        let points: Vec<F> = points.iter().map(|z| **z).collect();
+        FriPcs::batch_verify(self, commitments, &points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::{Poseidon, Sha256Hasher};
+    use ark_ff::{Field, UniformRand};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn setup() -> FriPcs<Sha256Hasher> {
+        FriPcs::new(FriParameters::new(4, 2, 16, 2), Sha256Hasher, 32)
+    }
+
+    #[test]
+    fn test_open() {
+        let mut rng = test_rng();
+        let pcs = setup();
+        let poly = DensePolynomial::rand(31, &mut rng);
+        let committed = pcs.commit(&poly);
+        let z = Fr::rand(&mut rng);
+
+        let (value, proof) = pcs.open(&committed, z, &mut Transcript::new(b"pcs"));
+        assert_eq!(value, poly.evaluate(&z));
+        let commitment = committed.commitment();
+        assert!(pcs.verify(&commitment, z, value, &proof, &mut Transcript::new(b"pcs")));
+        assert!(!pcs.verify(
+            &commitment,
+            z,
+            value + Fr::ONE,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+        assert!(!pcs.verify(
+            &commitment,
+            z + Fr::ONE,
+            value,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+
+    #[test]
+    fn test_wrong_value() {
+        // a prover claiming y' != f(z) has to test (f - y') / (X - z), which
+        // is not a polynomial, so it runs FRI on a far codeword
+        let mut rng = test_rng();
+        let pcs = setup();
+        let poly = DensePolynomial::rand(31, &mut rng);
+        let committed = pcs.commit(&poly);
+        let z = Fr::rand(&mut rng);
+        let wrong = poly.evaluate(&z) + Fr::ONE;
+
+        let mut transcript = Transcript::new(b"pcs");
+        let alpha = absorb_claims(
+            &[committed.commitment()],
+            &[z],
+            &[vec![wrong]],
+            &mut transcript,
+        );
+        assert_ne!(alpha, Fr::ZERO);
+        let domain = evaluation_domain::<Fr>(128);
+        let codeword = (0..128)
+            .map(|i| {
+                let x = domain.element(i);
+                (poly.evaluate(&x) - wrong) / (x - z)
+            })
+            .collect();
+        let fri =
+            crate::fri::prove_codeword(&pcs.params, &Sha256Hasher, codeword, 32, &mut transcript);
+        let leaves = fri.openings[0].indices.clone();
+        let proof = FriOpeningProof {
+            fri,
+            openings: vec![committed.tree.open_batch(&leaves)],
+        };
+        assert!(!pcs.verify(
+            &committed.commitment(),
+            z,
+            wrong,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+
+    #[test]
+    fn test_batch_open() {
+        let mut rng = test_rng();
+        let pcs = FriPcs::new(FriParameters::new(4, 4, 16, 4), Poseidon::<Fr>::new(), 64);
+        let polys: Vec<DensePolynomial<Fr>> = (0..3)
+            .map(|d| DensePolynomial::rand(20 * d + 10, &mut rng))
+            .collect();
+        let committed: Vec<_> = polys.iter().map(|p| pcs.commit(p)).collect();
+        let refs: Vec<_> = committed.iter().collect();
+        let points: Vec<Fr> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (values, proof) = pcs.batch_open(&refs, &points, &mut Transcript::new(b"pcs"));
+        for (poly, evals) in polys.iter().zip(values.iter()) {
+            assert_eq!(
+                evals,
+                &vec![poly.evaluate(&points[0]), poly.evaluate(&points[1])]
+            );
+        }
+        let commitments: Vec<_> = committed.iter().map(|c| c.commitment()).collect();
+        assert!(pcs.batch_verify(
+            &commitments,
+            &points,
+            &values,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+
+        let mut wrong = values.clone();
+        wrong[2][1] += Fr::ONE;
+        assert!(!pcs.batch_verify(
+            &commitments,
+            &points,
+            &wrong,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+        // commitments in a different order
+        let swapped = vec![
+            commitments[1].clone(),
+            commitments[0].clone(),
+            commitments[2].clone(),
+        ];
+        assert!(!pcs.batch_verify(
+            &swapped,
+            &points,
+            &values,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let mut rng = test_rng();
+        let pcs = setup();
+        let committed = pcs.commit(&DensePolynomial::rand(10, &mut rng));
+        let (_, proof) = pcs.open(&committed, Fr::from(3), &mut Transcript::new(b"pcs"));
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = FriOpeningProof::<Fr, [u8; 32]>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+    }
+}
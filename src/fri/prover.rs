@@ -0,0 +1,88 @@
+use super::{coset_leaves, evaluation_domain, fold_coset, fold_domain, FriParameters, FriProof};
+use crate::merkle::{MerkleHasher, MerkleTree};
+use crate::solomon_reed_code::ReedSolomon;
+use crate::transcript::Transcript;
+use ark_ff::FftField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::EvaluationDomain;
+
+/// Proves that the polynomial has degree < degree_bound, which must be a power of 2
+pub fn prove<F: FftField, H: MerkleHasher<F> + Clone>(
+    params: &FriParameters,
+    hasher: &H,
+    poly: &DensePolynomial<F>,
+    degree_bound: usize,
+    transcript: &mut Transcript,
+) -> FriProof<F, H::Digest> {
+    assert!(
+        poly.coeffs.len() <= degree_bound,
+        "polynomial exceeds the degree bound"
+    );
+    let domain = evaluation_domain::<F>(params.domain_size(degree_bound));
+    let codeword = ReedSolomon::new(poly.coeffs.clone()).encode_over_domain(&domain);
+    prove_codeword(params, hasher, codeword, degree_bound, transcript)
+}
+
+/// Runs the test on the evaluations over
+/// `evaluation_domain(params.domain_size(degree_bound))`, whether they come from a low degree polynomial or not
+pub fn prove_codeword<F: FftField, H: MerkleHasher<F> + Clone>(
+    params: &FriParameters,
+    hasher: &H,
+    codeword: Vec<F>,
+    degree_bound: usize,
+    transcript: &mut Transcript,
+) -> FriProof<F, H::Digest> {
+    assert!(
+        degree_bound.is_power_of_two(),
+        "degree bound must be a power of 2"
+    );
+    let k = params.folding_factor;
+    let mut domain = evaluation_domain::<F>(params.domain_size(degree_bound));
+    assert_eq!(
+        codeword.len(),
+        domain.size(),
+        "codeword of the wrong length"
+    );
+    transcript.append_serializable(b"fri_degree_bound", &(degree_bound as u64));
+
+    // commit phase
+    let mut codeword = codeword;
+    let mut trees = vec![];
+    for _ in 0..params.num_layers(degree_bound) {
+        let tree = MerkleTree::new(hasher.clone(), coset_leaves(&codeword, k));
+        transcript.append_serializable(b"fri_root", &tree.root());
+        let beta = transcript.challenge_field::<F>(b"fri_beta");
+
+        codeword = tree
+            .leaves()
+            .iter()
+            .enumerate()
+            .map(|(leaf, values)| fold_coset(&domain, leaf, values, beta))
+            .collect();
+        domain = fold_domain(&domain, k);
+        trees.push(tree);
+    }
+
+    let mut final_poly = domain.ifft(&codeword);
+    final_poly.truncate(params.final_bound(degree_bound));
+    transcript.append_serializable(b"fri_final_poly", &final_poly);
+
+    // query phase
+    let num_leaves = params.domain_size(degree_bound) / k;
+    let mut positions: Vec<usize> = (0..params.num_queries)
+        .map(|_| transcript.challenge_index(b"fri_query", num_leaves))
+        .collect();
+    let openings = trees
+        .iter()
+        .map(|tree| {
+            positions.iter_mut().for_each(|p| *p %= tree.num_leaves());
+            tree.open_batch(&positions)
+        })
+        .collect();
+
+    FriProof {
+        layer_roots: trees.iter().map(|tree| tree.root()).collect(),
+        final_poly,
+        openings,
+    }
+}
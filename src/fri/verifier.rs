@@ -0,0 +1,110 @@
+use super::{coset_indices, evaluation_domain, fold_coset, fold_domain, FriParameters, FriProof};
+use crate::merkle::{MerkleHasher, MerkleTree};
+use crate::transcript::Transcript;
+use ark_ff::FftField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Polynomial};
+
+/// Values of the first layer read by the verifier, `values[i]` being the
+/// evaluation at the element `indices[i]` of the evaluation domain. Other
+/// protocols check them against the polynomials the tested one is built from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FriQueries<F> {
+    pub indices: Vec<usize>,
+    pub values: Vec<F>,
+}
+
+/// Verifies that the committed codeword is close to a polynomial of degree <
+/// degree_bound. The transcript must be in the same state as the prover's.
+pub fn verify<F: FftField, H: MerkleHasher<F>>(
+    params: &FriParameters,
+    hasher: &H,
+    degree_bound: usize,
+    proof: &FriProof<F, H::Digest>,
+    transcript: &mut Transcript,
+) -> Result<FriQueries<F>, String> {
+    if !degree_bound.is_power_of_two() {
+        return Err("degree bound must be a power of 2".to_string());
+    }
+    let num_layers = params.num_layers(degree_bound);
+    if proof.layer_roots.len() != num_layers || proof.openings.len() != num_layers {
+        return Err(format!("expected {} layers", num_layers));
+    }
+    if proof.final_poly.len() > params.final_bound(degree_bound) {
+        return Err("final polynomial exceeds its degree bound".to_string());
+    }
+
+    transcript.append_serializable(b"fri_degree_bound", &(degree_bound as u64));
+    let betas: Vec<F> = proof
+        .layer_roots
+        .iter()
+        .map(|root| {
+            transcript.append_serializable(b"fri_root", root);
+            transcript.challenge_field::<F>(b"fri_beta")
+        })
+        .collect();
+    transcript.append_serializable(b"fri_final_poly", &proof.final_poly);
+
+    let k = params.folding_factor;
+    let mut domain = evaluation_domain::<F>(params.domain_size(degree_bound));
+    let mut positions: Vec<usize> = (0..params.num_queries)
+        .map(|_| transcript.challenge_index(b"fri_query", domain.size() / k))
+        .collect();
+    // value of the next layer at the folded position of every query
+    let mut folded: Vec<Option<F>> = vec![None; positions.len()];
+    let mut queries = FriQueries {
+        indices: vec![],
+        values: vec![],
+    };
+
+    for (i, ((root, opening), beta)) in proof
+        .layer_roots
+        .iter()
+        .zip(proof.openings.iter())
+        .zip(betas)
+        .enumerate()
+    {
+        let num_leaves = domain.size() / k;
+        let previous = positions.clone();
+        positions.iter_mut().for_each(|p| *p %= num_leaves);
+        if !MerkleTree::verify(hasher, root, num_leaves, &positions, opening) {
+            return Err(format!("invalid Merkle opening in layer {}", i));
+        }
+
+        for (q, (leaf, index)) in positions.iter().zip(previous).enumerate() {
+            // the proof opens exactly the positions, so the leaf exists
+            let values = opening.leaf(*leaf).unwrap();
+            if values.len() != k {
+                return Err(format!("leaf of the wrong size in layer {}", i));
+            }
+            if let Some(expected) = folded[q] {
+                // index = leaf + slot * num_leaves
+                if values[index / num_leaves] != expected {
+                    return Err(format!("folding check failed in layer {}", i));
+                }
+            }
+            folded[q] = Some(fold_coset(&domain, *leaf, values, beta));
+        }
+        if i == 0 {
+            for leaf in opening.indices.iter() {
+                let values = opening.leaf(*leaf).unwrap();
+                queries
+                    .indices
+                    .extend(coset_indices(*leaf, domain.size(), k));
+                queries.values.extend_from_slice(values);
+            }
+        }
+        domain = fold_domain(&domain, k);
+    }
+
+    let final_poly = DensePolynomial::from_coefficients_slice(&proof.final_poly);
+    for (position, value) in positions.iter().zip(folded) {
+        if let Some(value) = value {
+            if final_poly.evaluate(&domain.element(*position)) != value {
+                return Err("final polynomial does not match the last layer".to_string());
+            }
+        }
+    }
+
+    Ok(queries)
+}
@@ -0,0 +1,156 @@
+use ark_ff::Field;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GateKind {
+    Add,
+    Mul,
+}
+
+/// Gate of a layer, reading the values at `left` and `right` of the layer
+/// below
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gate {
+    pub kind: GateKind,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Gate {
+    pub fn add(left: usize, right: usize) -> Self {
+        Gate {
+            kind: GateKind::Add,
+            left,
+            right,
+        }
+    }
+
+    pub fn mul(left: usize, right: usize) -> Self {
+        Gate {
+            kind: GateKind::Mul,
+            left,
+            right,
+        }
+    }
+
+    /// Value of the gate given the ones it reads
+    pub fn apply<F: Field>(&self, left: F, right: F) -> F {
+        match self.kind {
+            GateKind::Add => left + right,
+            GateKind::Mul => left * right,
+        }
+    }
+}
+
+/// Arithmetic circuit in layers of fan-in 2 gates over the inputs, layer 0
+/// being the inputs and the last layer the outputs. The values of a layer are
+/// padded with zeros to 2^k, k >= 1.
+#[derive(Clone, Debug)]
+pub struct LayeredCircuit {
+    num_inputs: usize,
+    /// gates of the layers 1, 2, ...
+    layers: Vec<Vec<Gate>>,
+}
+
+impl LayeredCircuit {
+    pub fn new(num_inputs: usize) -> Self {
+        assert!(num_inputs > 0, "circuit without inputs");
+        LayeredCircuit {
+            num_inputs,
+            layers: vec![],
+        }
+    }
+
+    /// Adds a layer of gates reading the last layer
+    pub fn add_layer(&mut self, gates: Vec<Gate>) {
+        assert!(!gates.is_empty(), "layer without gates");
+        let width = self.width(self.depth());
+        assert!(
+            gates.iter().all(|g| g.left < width && g.right < width),
+            "gate reading a value out of the layer below"
+        );
+        self.layers.push(gates);
+    }
+
+    /// Number of layers of gates
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn num_outputs(&self) -> usize {
+        self.width(self.depth())
+    }
+
+    /// Gates of layer i >= 1
+    pub fn gates(&self, i: usize) -> &[Gate] {
+        assert!(i > 0, "the inputs have no gates");
+        &self.layers[i - 1]
+    }
+
+    /// Number of variables of the multilinear extension of layer i
+    pub fn num_vars(&self, i: usize) -> usize {
+        (self.width(i).next_power_of_two().trailing_zeros() as usize).max(1)
+    }
+
+    /// Values of every layer padded with zeros, the inputs first
+    pub fn evaluate<F: Field>(&self, inputs: &[F]) -> Vec<Vec<F>> {
+        assert_eq!(inputs.len(), self.num_inputs, "wrong number of inputs");
+        let mut values = vec![self.pad(0, inputs.to_vec())];
+        for (i, gates) in self.layers.iter().enumerate() {
+            let below = &values[i];
+            let layer = gates
+                .iter()
+                .map(|g| g.apply(below[g.left], below[g.right]))
+                .collect();
+            values.push(self.pad(i + 1, layer));
+        }
+        values
+    }
+
+    /// Pads the values of layer i with zeros to 2^num_vars(i)
+    pub fn pad<F: Field>(&self, i: usize, mut values: Vec<F>) -> Vec<F> {
+        values.resize(1 << self.num_vars(i), F::ZERO);
+        values
+    }
+
+    fn width(&self, i: usize) -> usize {
+        match i {
+            0 => self.num_inputs,
+            _ => self.layers[i - 1].len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_evaluate() {
+        let mut circuit = LayeredCircuit::new(3);
+        circuit.add_layer(vec![Gate::mul(0, 1), Gate::add(1, 2), Gate::mul(2, 2)]);
+        circuit.add_layer(vec![Gate::add(0, 2)]);
+        assert_eq!(circuit.depth(), 2);
+        assert_eq!(circuit.num_outputs(), 1);
+        assert_eq!(
+            (0..3).map(|i| circuit.num_vars(i)).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+
+        let values = circuit.evaluate(&[Fr::from(2), Fr::from(3), Fr::from(4)]);
+        assert_eq!(values[0][3], Fr::from(0));
+        assert_eq!(values[1][..3], [Fr::from(6), Fr::from(7), Fr::from(16)]);
+        assert_eq!(values[2], vec![Fr::from(22), Fr::from(0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gate_out_of_layer() {
+        let mut circuit = LayeredCircuit::new(2);
+        circuit.add_layer(vec![Gate::add(0, 2)]);
+    }
+}
@@ -0,0 +1,441 @@
+//! GKR protocol for layered arithmetic circuits (Goldwasser, Kalai, Rothblum
+//! 2008; Thaler §4.6).
+//!
+//! Layer i has 2^k values, extended to the multilinear V_i, and every gate g
+//! reads the values l_g and r_g of the layer below, so
+//!
+//!   V_i(z) = sum_{x, y in {0,1}^k} sum_g eq(z, g) eq(x, l_g) eq(y, r_g) op_g(V_{i-1}(x), V_{i-1}(y))
+//!
+//! with op_g the addition or the product. Starting from the outputs at a
+//! random z, a sumcheck per layer reduces a claim about V_i to the claims
+//! V_{i-1}(r_x) and V_{i-1}(r_y). The verifier merges them with random α, β
+//! into the claim about α V_{i-1}(r_x) + β V_{i-1}(r_y), which the sum
+//! above handles by weighting every gate with α eq(r_x, g) + β eq(r_y, g).
+//! The last claims are checked against the inputs.
+//!
+//! The prover evaluates the summand point by point, which keeps the
+//! zero-knowledge mode in `zk` simple but is slow: a sum over the 2^{2k}
+//! points of {0,1}^{2k} evaluates V_{i-1} and the gates at each of them, in
+//! O(2^k + S) for S gates, so the prover is cubic in the width of the layers.
+mod circuit;
+pub mod zk;
+
+pub use circuit::{Gate, GateKind, LayeredCircuit};
+
+use crate::multilinear::{eq_table, mle_evaluate, number_to_domain};
+use crate::oracle::Oracle;
+use crate::sumcheck::{
+    prove_noninteractive, verify_noninteractive, SumcheckPolynomial, VerifierState,
+};
+use crate::transcript::Transcript;
+use crate::univariate::lagrange_interpolate;
+use ark_ff::Field;
+use ark_poly::univariate::{DensePolynomial, SparsePolynomial as UnivariatePolynomial};
+use ark_poly::Polynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LayerProof<F: Field> {
+    round_polys: Vec<UnivariatePolynomial<F>>,
+    /// V_{i-1}(r_x) and V_{i-1}(r_y)
+    left: F,
+    right: F,
+}
+
+/// Layers from the outputs down to the inputs
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GkrProof<F: Field> {
+    layers: Vec<LayerProof<F>>,
+}
+
+/// Extension of the values of a layer the prover makes claims about
+#[derive(Clone, Debug)]
+enum LayerMask<F: Field> {
+    /// the multilinear extension V
+    None,
+    /// V(x) + Z(x) (s_0 + s_1 x_0)
+    Linear(F, F),
+    /// M(x, Z(x)) for M the multilinear extension of the values followed by
+    /// the given blinding values, V(x) + Z(x) (B(x) - V(x))
+    Blinding(Vec<F>),
+}
+
+impl<F: Field> LayerMask<F> {
+    fn evaluate(&self, values: &[F], x: &[F]) -> F {
+        let v = mle_evaluate(values, x);
+        match self {
+            LayerMask::None => v,
+            LayerMask::Linear(s_0, s_1) => v + vanishing(x) * (*s_0 + *s_1 * x[0]),
+            LayerMask::Blinding(blinding) => v + vanishing(x) * (mle_evaluate(blinding, x) - v),
+        }
+    }
+
+    /// Highest degree of the extension in a variable
+    fn degree(&self) -> usize {
+        match self {
+            LayerMask::None => 1,
+            _ => 3,
+        }
+    }
+}
+
+/// Z(x) = prod_j x_j (1 - x_j), which vanishes on the hypercube
+fn vanishing<F: Field>(x: &[F]) -> F {
+    x.iter().map(|x_j| *x_j * (F::ONE - x_j)).product()
+}
+
+/// Weight of every gate in the claim sum_c weights_c V_i(points_c),
+/// sum_c weights_c eq(points_c, g)
+fn gate_weights<F: Field>(num_gates: usize, weights: &[F], points: &[Vec<F>]) -> Vec<F> {
+    let mut result = vec![F::ZERO; num_gates];
+    for (weight, point) in weights.iter().zip(points) {
+        for (g, eq) in eq_table(point).iter().take(num_gates).enumerate() {
+            result[g] += *weight * eq;
+        }
+    }
+    result
+}
+
+// summand of the sumcheck of a layer at (x, y), given the values v(x), v(y)
+// of the extension of the layer below. With a mask T(w) of the claims, the
+// point has a last variable w and the summand is
+// (1 - w) F(x, y) + eq(0, (x, y)) T(w).
+fn summand<F: Field>(
+    gates: &[Gate],
+    gate_weights: &[F],
+    point: &[F],
+    (left, right): (F, F),
+    mask: Option<F>,
+) -> F {
+    let k = point.len() / 2;
+    let eq_x = eq_table(&point[..k]);
+    let eq_y = eq_table(&point[k..2 * k]);
+    let sum: F = gates
+        .iter()
+        .zip(gate_weights)
+        .map(|(g, weight)| *weight * eq_x[g.left] * eq_y[g.right] * g.apply(left, right))
+        .sum();
+    match mask {
+        None => sum,
+        Some(t) => {
+            let w = point[2 * k];
+            (F::ONE - w) * sum + eq_x[0] * eq_y[0] * t
+        }
+    }
+}
+
+// summand of a layer as a sumcheck polynomial in x, y and possibly w
+struct LayerPolynomial<'a, F: Field> {
+    gates: &'a [Gate],
+    gate_weights: Vec<F>,
+    /// values of the layer below and their extension
+    below: &'a [F],
+    below_mask: &'a LayerMask<F>,
+    /// T(w)
+    mask: Option<DensePolynomial<F>>,
+    num_vars: usize,
+    fixed: Vec<F>,
+}
+
+impl<'a, F: Field> LayerPolynomial<'a, F> {
+    fn new(
+        gates: &'a [Gate],
+        gate_weights: Vec<F>,
+        (below, below_mask): (&'a [F], &'a LayerMask<F>),
+        mask: Option<DensePolynomial<F>>,
+    ) -> Self {
+        let k = below.len().trailing_zeros() as usize;
+        LayerPolynomial {
+            gates,
+            gate_weights,
+            below,
+            below_mask,
+            num_vars: 2 * k + mask.is_some() as usize,
+            mask,
+            fixed: vec![],
+        }
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        let k = point.len() / 2;
+        let left = self.below_mask.evaluate(self.below, &point[..k]);
+        let right = self.below_mask.evaluate(self.below, &point[k..2 * k]);
+        let mask = self.mask.as_ref().map(|t| t.evaluate(&point[2 * k]));
+        summand(self.gates, &self.gate_weights, point, (left, right), mask)
+    }
+
+    // sum over the free variables after the given prefix
+    fn sum_with_prefix(&self, prefix: &[F]) -> F {
+        let free = self.num_vars - prefix.len();
+        (0..1 << free)
+            .map(|b| {
+                let point: Vec<F> = prefix
+                    .iter()
+                    .copied()
+                    .chain(number_to_domain(b, free))
+                    .collect();
+                self.evaluate(&point)
+            })
+            .sum()
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for LayerPolynomial<'_, F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars - self.fixed.len()
+    }
+
+    fn hypercube_sum(&self) -> F {
+        self.sum_with_prefix(&self.fixed)
+    }
+
+    fn round_poly(&self) -> UnivariatePolynomial<F> {
+        let degree = self.below_mask.degree() + 1;
+        let points: Vec<(F, F)> = (0..=degree)
+            .map(|c| {
+                let c = F::from(c as u64);
+                let mut prefix = self.fixed.clone();
+                prefix.push(c);
+                (c, self.sum_with_prefix(&prefix))
+            })
+            .collect();
+        let coeffs = lagrange_interpolate(&points).coeffs;
+        UnivariatePolynomial::from_coefficients_vec(coeffs.into_iter().enumerate().collect())
+    }
+
+    fn fix_first_variable(&mut self, r: F) {
+        self.fixed.push(r);
+    }
+}
+
+// final check of the sumcheck of a layer, with the values of the layer below
+// and the mask claimed by the prover
+struct LayerOracle<'a, F: Field> {
+    gates: &'a [Gate],
+    gate_weights: Vec<F>,
+    left: F,
+    right: F,
+    mask: Option<F>,
+}
+
+impl<F: Field> Oracle<F> for LayerOracle<'_, F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        summand(
+            self.gates,
+            &self.gate_weights,
+            point,
+            (self.left, self.right),
+            self.mask,
+        )
+    }
+}
+
+fn new_transcript<F: Field>(inputs: &[F], outputs: &[F]) -> Transcript {
+    let mut transcript = Transcript::new(b"gkr");
+    transcript.append_serializable(b"inputs", inputs);
+    transcript.append_serializable(b"outputs", outputs);
+    transcript
+}
+
+/// Evaluates the circuit and proves its outputs
+pub fn prove<F: Field>(circuit: &LayeredCircuit, inputs: &[F]) -> (Vec<F>, GkrProof<F>) {
+    let depth = circuit.depth();
+    let values = circuit.evaluate(inputs);
+    let outputs = values[depth][..circuit.num_outputs()].to_vec();
+    let mut transcript = new_transcript(inputs, &outputs);
+
+    let mut points = vec![transcript.challenge_fields(b"z", circuit.num_vars(depth))];
+    let mut weights = vec![F::ONE];
+    let mut layers = Vec::with_capacity(depth);
+    for i in (1..=depth).rev() {
+        let gates = circuit.gates(i);
+        let poly = LayerPolynomial::new(
+            gates,
+            gate_weights(gates.len(), &weights, &points),
+            (&values[i - 1], &LayerMask::None),
+            None,
+        );
+        let (round_polys, prover) = prove_noninteractive(poly, &mut transcript);
+        let (r_x, r_y) = prover.get_random_vars().split_at(circuit.num_vars(i - 1));
+        let left = mle_evaluate(&values[i - 1], r_x);
+        let right = mle_evaluate(&values[i - 1], r_y);
+
+        transcript.append_field(b"left", &left);
+        transcript.append_field(b"right", &right);
+        weights = vec![
+            transcript.challenge_field(b"alpha"),
+            transcript.challenge_field(b"beta"),
+        ];
+        points = vec![r_x.to_vec(), r_y.to_vec()];
+        layers.push(LayerProof {
+            round_polys,
+            left,
+            right,
+        });
+    }
+
+    (outputs, GkrProof { layers })
+}
+
+/// Checks that the circuit maps the inputs to the outputs
+pub fn verify<F: Field>(
+    circuit: &LayeredCircuit,
+    inputs: &[F],
+    outputs: &[F],
+    proof: &GkrProof<F>,
+) -> Result<(), String> {
+    let depth = circuit.depth();
+    if inputs.len() != circuit.num_inputs() || outputs.len() != circuit.num_outputs() {
+        return Err("wrong number of inputs or outputs".to_string());
+    }
+    if proof.layers.len() != depth {
+        return Err("invalid number of layers".to_string());
+    }
+    let mut transcript = new_transcript(inputs, outputs);
+
+    let z = transcript.challenge_fields(b"z", circuit.num_vars(depth));
+    let mut claim = mle_evaluate(&circuit.pad(depth, outputs.to_vec()), &z);
+    let mut points = vec![z];
+    let mut weights = vec![F::ONE];
+    for (layer, i) in proof.layers.iter().zip((1..=depth).rev()) {
+        let gates = circuit.gates(i);
+        let oracle = LayerOracle {
+            gates,
+            gate_weights: gate_weights(gates.len(), &weights, &points),
+            left: layer.left,
+            right: layer.right,
+            mask: None,
+        };
+        let k = circuit.num_vars(i - 1);
+        let verifier = VerifierState::with_oracle(claim, 2 * k, oracle);
+        let r = verify_noninteractive(verifier, &layer.round_polys, 2, &mut transcript)
+            .map_err(|err| format!("layer {}: {}", i, err))?;
+
+        transcript.append_field(b"left", &layer.left);
+        transcript.append_field(b"right", &layer.right);
+        weights = vec![
+            transcript.challenge_field(b"alpha"),
+            transcript.challenge_field(b"beta"),
+        ];
+        claim = weights[0] * layer.left + weights[1] * layer.right;
+        points = vec![r[..k].to_vec(), r[k..].to_vec()];
+    }
+
+    let inputs = circuit.pad(0, inputs.to_vec());
+    let last = &proof.layers[depth - 1];
+    if mle_evaluate(&inputs, &points[0]) != last.left
+        || mle_evaluate(&inputs, &points[1]) != last.right
+    {
+        return Err("claims do not match the inputs".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    /// Circuit of depth 3 over 4 inputs, of widths 3, 2 and 1
+    pub(crate) fn sample_circuit() -> LayeredCircuit {
+        let mut circuit = LayeredCircuit::new(4);
+        circuit.add_layer(vec![Gate::mul(0, 1), Gate::add(2, 3), Gate::mul(1, 3)]);
+        circuit.add_layer(vec![Gate::add(0, 1), Gate::mul(1, 2)]);
+        circuit.add_layer(vec![Gate::mul(0, 1)]);
+        circuit
+    }
+
+    pub(crate) fn sample_inputs() -> Vec<Fr> {
+        let mut rng = test_rng();
+        (0..4).map(|_| Fr::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_layer_polynomial() {
+        let circuit = sample_circuit();
+        let values = circuit.evaluate(&sample_inputs());
+        let points = vec![vec![Fr::from(3), Fr::from(5)]];
+        let gates = circuit.gates(2);
+        let poly = LayerPolynomial::new(
+            gates,
+            gate_weights(gates.len(), &[Fr::ONE], &points),
+            (&values[1], &LayerMask::None),
+            None,
+        );
+        assert_eq!(poly.hypercube_sum(), mle_evaluate(&values[2], &points[0]));
+
+        // masking the layer below and the claims keeps the sum
+        let mask = LayerMask::Linear(Fr::from(7), Fr::from(11));
+        let t = DensePolynomial::from_coefficients_vec(vec![Fr::from(2), Fr::from(9)]);
+        let masked = LayerPolynomial::new(
+            gates,
+            gate_weights(gates.len(), &[Fr::ONE], &points),
+            (&values[1], &mask),
+            Some(t.clone()),
+        );
+        let sum = poly.hypercube_sum() + t.evaluate(&Fr::ZERO) + t.evaluate(&Fr::ONE);
+        assert_eq!(masked.hypercube_sum(), sum);
+    }
+
+    #[test]
+    fn test_layer_masks() {
+        let values: Vec<Fr> = (1..=4).map(Fr::from).collect();
+        let masks = [
+            LayerMask::None,
+            LayerMask::Linear(Fr::from(7), Fr::from(11)),
+            LayerMask::Blinding(vec![Fr::from(5); 4]),
+        ];
+        let x = [Fr::from(3), Fr::from(8)];
+        for mask in &masks {
+            for (b, value) in values.iter().enumerate() {
+                assert_eq!(mask.evaluate(&values, &number_to_domain(b, 2)), *value);
+            }
+        }
+        assert_ne!(masks[1].evaluate(&values, &x), mle_evaluate(&values, &x));
+        assert_ne!(masks[2].evaluate(&values, &x), mle_evaluate(&values, &x));
+    }
+
+    #[test]
+    fn test_gkr() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let (outputs, proof) = prove(&circuit, &inputs);
+        let values = circuit.evaluate(&inputs);
+        assert_eq!(outputs, vec![values[3][0]]);
+        assert_eq!(verify(&circuit, &inputs, &outputs, &proof), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_output() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let (outputs, proof) = prove(&circuit, &inputs);
+        let wrong = vec![outputs[0] + Fr::ONE];
+        assert!(verify(&circuit, &inputs, &wrong, &proof).is_err());
+    }
+
+    #[test]
+    fn test_wrong_inputs() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let (outputs, proof) = prove(&circuit, &inputs);
+        let mut wrong = inputs;
+        wrong[2] += Fr::ONE;
+        assert!(verify(&circuit, &wrong, &outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_tampered_layer() {
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let (outputs, mut proof) = prove(&circuit, &inputs);
+        proof.layers[1].left += Fr::ONE;
+        let result = verify(&circuit, &inputs, &outputs, &proof);
+        assert!(result.unwrap_err().starts_with("layer 2"));
+    }
+}
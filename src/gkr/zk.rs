@@ -0,0 +1,606 @@
+//! Zero-knowledge GKR with masked layer polynomials (Xie et al. 2019
+//! "Libra", following Chiesa, Forbes, Spooner 2017 and Wahby et al. 2018
+//! "Hyrax").
+//!
+//! Plain GKR leaks the inputs to the verifier and, through the round
+//! polynomials and the claims V_{i-1}(r_x), V_{i-1}(r_y), evaluations of
+//! every intermediate layer. Here:
+//!
+//! - the prover commits to the multilinear M of the inputs followed by 2^k
+//!   random blinding values, and the extension of the inputs is
+//!   M(x, Z(x)) for Z(x) = prod_j x_j (1 - x_j), which agrees with them on the
+//!   hypercube. The last claims are openings of M.
+//! - every intermediate layer i is extended to V_i(x) + Z(x) (s_0 + s_1 x_0).
+//!   The prover commits to random linear R_0(w), R_1(w) with
+//!   s_b = R_b(0) + R_b(1), so a claim sum_c w_c V_i(z_c) about the masked
+//!   extension is the sum over x, y and w in {0,1} of
+//!
+//!     (1 - w) F(x, y) + eq(0, (x, y)) sum_c w_c Z(z_c) (R_0(w) + z_c0 R_1(w))
+//!
+//!   for F the summand of plain GKR over the masked layer below. The verifier
+//!   gets R_0(r_w), R_1(r_w) from openings.
+//! - the sumcheck of every layer is the zero-knowledge one of
+//!   `sumcheck::zk`, with a mask of the degree of the summand in every
+//!   variable.
+//!
+//! What is hidden: everything but the circuit and the outputs. For an honest
+//! verifier, the round polynomials are masked, the claims about a masked
+//! layer at r_x and r_y are uniformly random since Z(r_x), Z(r_y) are nonzero
+//! with overwhelming probability, and R_b(r_w) says nothing about s_b. The
+//! whole view is then simulated from the outputs, as in the tests. As for the
+//! zero-knowledge sumcheck, this assumes hiding commitments whose openings
+//! reveal only the evaluations, which the schemes of this crate are not.
+use super::{gate_weights, vanishing, LayerMask, LayerOracle, LayerPolynomial, LayeredCircuit};
+use crate::multilinear::mle_evaluate;
+use crate::pcs::{MultilinearPcs, UnivariatePcs};
+use crate::sumcheck::zk::{check_rounds, ZkSumcheck, ZkSumcheckProof};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseMultilinearExtension, DenseUVPolynomial, Polynomial};
+use ark_std::rand::RngCore;
+
+/// Zero-knowledge argument that a circuit maps committed inputs to public
+/// outputs. The masks of the sumchecks and the layers are committed through
+/// U, the inputs through M.
+pub struct ZkGkr<F: Field, U: UnivariatePcs<F>, M: MultilinearPcs<F>> {
+    circuit: LayeredCircuit,
+    sumcheck: ZkSumcheck<F, U>,
+    input_pcs: M,
+}
+
+pub struct ZkLayerProof<F: Field, U: UnivariatePcs<F>> {
+    sumcheck: ZkSumcheckProof<F, U>,
+    /// masked extension of the layer below at r_x and r_y
+    left: F,
+    right: F,
+    /// R_0(r_w) and R_1(r_w), empty for the output layer
+    mask_evals: Vec<F>,
+    mask_openings: Vec<U::Proof>,
+}
+
+pub struct ZkGkrProof<F: Field, U: UnivariatePcs<F>, M: MultilinearPcs<F>> {
+    input_commitment: M::Commitment,
+    /// R_0 and R_1 of the layers 1, ..., depth - 1
+    mask_commitments: Vec<U::Commitment>,
+    /// from the outputs down to the inputs
+    layers: Vec<ZkLayerProof<F, U>>,
+    /// M at the last r_x and r_y
+    input_openings: Vec<M::Proof>,
+}
+
+// challenges of the verifier, the point z of the outputs and for every layer
+// ρ of the sumcheck, its point and α, β
+struct Challenges<F: Field> {
+    z: Vec<F>,
+    layers: Vec<LayerChallenges<F>>,
+}
+
+struct LayerChallenges<F: Field> {
+    rho: F,
+    r: Vec<F>,
+    alpha: F,
+    beta: F,
+}
+
+/// Degree of the extension of layer i in every variable
+fn extension_degrees(circuit: &LayeredCircuit, i: usize) -> Vec<usize> {
+    let k = circuit.num_vars(i);
+    match i {
+        0 => vec![3; k],
+        _ => (0..k).map(|j| if j == 0 { 3 } else { 2 }).collect(),
+    }
+}
+
+/// Degrees of the summand of layer i in x, y and w
+fn summand_degrees(circuit: &LayeredCircuit, i: usize) -> Vec<usize> {
+    let below = extension_degrees(circuit, i - 1);
+    let masked = i < circuit.depth();
+    below
+        .iter()
+        .chain(&below)
+        .map(|d| d + 1)
+        .chain(masked.then_some(1))
+        .collect()
+}
+
+/// T(w) = a R_0(w) + b R_1(w) for the claims sum_c weights_c V_i(points_c),
+/// with a = sum_c weights_c Z(points_c) and b = sum_c weights_c Z(points_c) points_c0
+fn mask_coefficients<F: Field>(weights: &[F], points: &[Vec<F>]) -> (F, F) {
+    weights
+        .iter()
+        .zip(points)
+        .fold((F::ZERO, F::ZERO), |(a, b), (weight, point)| {
+            let c = *weight * vanishing(point);
+            (a + c, b + c * point[0])
+        })
+}
+
+/// Point where M is opened for a claim about the inputs at x, (x, Z(x))
+fn input_point<F: Field>(x: &[F]) -> Vec<F> {
+    let mut point = x.to_vec();
+    point.push(vanishing(x));
+    point
+}
+
+fn layer_oracle<'a, F: Field>(
+    circuit: &'a LayeredCircuit,
+    i: usize,
+    (weights, points): (&[F], &[Vec<F>]),
+    (left, right): (F, F),
+    mask_evals: &[F],
+) -> LayerOracle<'a, F> {
+    let gates = circuit.gates(i);
+    let mask = (i < circuit.depth()).then(|| {
+        let (a, b) = mask_coefficients(weights, points);
+        a * mask_evals[0] + b * mask_evals[1]
+    });
+    LayerOracle {
+        gates,
+        gate_weights: gate_weights(gates.len(), weights, points),
+        left,
+        right,
+        mask,
+    }
+}
+
+fn new_transcript<F: Field, U: UnivariatePcs<F>, M: MultilinearPcs<F>>(
+    input_commitment: &M::Commitment,
+    mask_commitments: &[U::Commitment],
+    outputs: &[F],
+) -> Transcript {
+    let mut transcript = Transcript::new(b"zk-gkr");
+    transcript.append_serializable(b"input_commitment", input_commitment);
+    transcript.append_serializable(b"mask_commitments", mask_commitments);
+    transcript.append_serializable(b"outputs", outputs);
+    transcript
+}
+
+impl<F: Field, U: UnivariatePcs<F>, M: MultilinearPcs<F>> ZkGkr<F, U, M> {
+    pub fn new<R: RngCore>(circuit: LayeredCircuit, rng: &mut R) -> Self {
+        assert!(circuit.depth() > 0, "circuit without gates");
+        let input_vars = circuit.num_vars(0) + 1;
+        ZkGkr {
+            circuit,
+            sumcheck: ZkSumcheck::new(4, rng),
+            input_pcs: M::setup(input_vars, rng),
+        }
+    }
+
+    /// Evaluates the circuit and proves its outputs without revealing the
+    /// inputs
+    pub fn prove<R: RngCore>(&self, inputs: &[F], rng: &mut R) -> (Vec<F>, ZkGkrProof<F, U, M>) {
+        let circuit = &self.circuit;
+        let depth = circuit.depth();
+        let values = circuit.evaluate(inputs);
+        let outputs = values[depth][..circuit.num_outputs()].to_vec();
+
+        let blinding: Vec<F> = (0..values[0].len()).map(|_| F::rand(rng)).collect();
+        let input_poly = DenseMultilinearExtension::from_evaluations_vec(
+            circuit.num_vars(0) + 1,
+            values[0].iter().chain(&blinding).copied().collect(),
+        );
+        let (input_commitment, input_data) = self.input_pcs.commit(&input_poly);
+        // R_0 and R_1 of every intermediate layer
+        let layer_masks: Vec<DensePolynomial<F>> = (0..2 * (depth - 1))
+            .map(|_| DensePolynomial::rand(1, rng))
+            .collect();
+        let (mask_commitments, mask_data): (Vec<_>, Vec<_>) = layer_masks
+            .iter()
+            .map(|r| self.sumcheck.pcs().commit(r))
+            .unzip();
+        let hypercube_sum = |r: &DensePolynomial<F>| r.evaluate(&F::ZERO) + r.evaluate(&F::ONE);
+        let masks: Vec<LayerMask<F>> = std::iter::once(LayerMask::Blinding(blinding))
+            .chain(
+                layer_masks
+                    .chunks(2)
+                    .map(|r| LayerMask::Linear(hypercube_sum(&r[0]), hypercube_sum(&r[1]))),
+            )
+            .collect();
+
+        let mut transcript =
+            new_transcript::<F, U, M>(&input_commitment, &mask_commitments, &outputs);
+        let mut points = vec![transcript.challenge_fields(b"z", circuit.num_vars(depth))];
+        let mut weights = vec![F::ONE];
+        let mut layers = Vec::with_capacity(depth);
+        for i in (1..=depth).rev() {
+            let gates = circuit.gates(i);
+            let own_masks = (i < depth).then(|| &layer_masks[2 * (i - 1)..2 * i]);
+            let mask = own_masks.map(|r| {
+                let (a, b) = mask_coefficients(&weights, &points);
+                &(&r[0] * a) + &(&r[1] * b)
+            });
+            let poly = LayerPolynomial::new(
+                gates,
+                gate_weights(gates.len(), &weights, &points),
+                (&values[i - 1], &masks[i - 1]),
+                mask,
+            );
+            let degrees = summand_degrees(circuit, i);
+            let (_, sumcheck, r) = self
+                .sumcheck
+                .prove_masked(poly, &degrees, rng, &mut transcript);
+            let k = circuit.num_vars(i - 1);
+            let (r_x, r_y) = (&r[..k], &r[k..2 * k]);
+            let left = masks[i - 1].evaluate(&values[i - 1], r_x);
+            let right = masks[i - 1].evaluate(&values[i - 1], r_y);
+            transcript.append_field(b"left", &left);
+            transcript.append_field(b"right", &right);
+
+            let (mask_evals, mask_openings) = match own_masks {
+                None => (vec![], vec![]),
+                Some(r_masks) => {
+                    let mask_evals: Vec<F> =
+                        r_masks.iter().map(|p| p.evaluate(&r[2 * k])).collect();
+                    transcript.append_serializable(b"mask_evals", &mask_evals);
+                    let openings = r_masks
+                        .iter()
+                        .zip(&mask_data[2 * (i - 1)..2 * i])
+                        .map(|(p, data)| {
+                            self.sumcheck
+                                .pcs()
+                                .open(p, data, &r[2 * k], &mut transcript)
+                                .1
+                        })
+                        .collect();
+                    (mask_evals, openings)
+                }
+            };
+
+            weights = vec![
+                transcript.challenge_field(b"alpha"),
+                transcript.challenge_field(b"beta"),
+            ];
+            points = vec![r_x.to_vec(), r_y.to_vec()];
+            layers.push(ZkLayerProof {
+                sumcheck,
+                left,
+                right,
+                mask_evals,
+                mask_openings,
+            });
+        }
+
+        let input_openings = points
+            .iter()
+            .map(|x| {
+                self.input_pcs
+                    .open(&input_poly, &input_data, &input_point(x), &mut transcript)
+                    .1
+            })
+            .collect();
+        let proof = ZkGkrProof {
+            input_commitment,
+            mask_commitments,
+            layers,
+            input_openings,
+        };
+        (outputs, proof)
+    }
+
+    /// Checks that the circuit maps the committed inputs to the outputs
+    pub fn verify(&self, outputs: &[F], proof: &ZkGkrProof<F, U, M>) -> Result<(), String> {
+        let challenges = self.challenges(outputs, proof)?;
+        check_layers(&self.circuit, outputs, proof, &challenges)
+    }
+
+    // replays the transcript and checks the openings, which are all of
+    // commitments made before the challenges
+    fn challenges(
+        &self,
+        outputs: &[F],
+        proof: &ZkGkrProof<F, U, M>,
+    ) -> Result<Challenges<F>, String> {
+        let circuit = &self.circuit;
+        let depth = circuit.depth();
+        if outputs.len() != circuit.num_outputs() {
+            return Err("wrong number of outputs".to_string());
+        }
+        let malformed = proof.layers.len() != depth
+            || proof.mask_commitments.len() != 2 * (depth - 1)
+            || proof.input_openings.len() != 2
+            || proof.layers.iter().enumerate().any(|(j, layer)| {
+                let count = if j == 0 { 0 } else { 2 };
+                layer.mask_evals.len() != count || layer.mask_openings.len() != count
+            });
+        if malformed {
+            return Err("malformed proof".to_string());
+        }
+        let mut transcript =
+            new_transcript::<F, U, M>(&proof.input_commitment, &proof.mask_commitments, outputs);
+
+        let z = transcript.challenge_fields(b"z", circuit.num_vars(depth));
+        let mut claim = mle_evaluate(&circuit.pad(depth, outputs.to_vec()), &z);
+        let mut layers = Vec::with_capacity(depth);
+        for (layer, i) in proof.layers.iter().zip((1..=depth).rev()) {
+            let n = summand_degrees(circuit, i).len();
+            let (rho, r) = self
+                .sumcheck
+                .challenges(n, claim, &layer.sumcheck, &mut transcript)
+                .map_err(|err| format!("layer {}: {}", i, err))?;
+            transcript.append_field(b"left", &layer.left);
+            transcript.append_field(b"right", &layer.right);
+
+            if i < depth {
+                transcript.append_serializable(b"mask_evals", &layer.mask_evals);
+                let commitments = &proof.mask_commitments[2 * (i - 1)..2 * i];
+                let openings = commitments
+                    .iter()
+                    .zip(&layer.mask_evals)
+                    .zip(&layer.mask_openings);
+                for (b, ((commitment, value), opening)) in openings.enumerate() {
+                    let valid = self.sumcheck.pcs().verify(
+                        commitment,
+                        &r[n - 1],
+                        *value,
+                        opening,
+                        &mut transcript,
+                    );
+                    if !valid {
+                        return Err(format!("layer {}: invalid opening of R_{}", i, b));
+                    }
+                }
+            }
+
+            let alpha = transcript.challenge_field(b"alpha");
+            let beta = transcript.challenge_field(b"beta");
+            claim = alpha * layer.left + beta * layer.right;
+            layers.push(LayerChallenges {
+                rho,
+                r,
+                alpha,
+                beta,
+            });
+        }
+
+        let k = circuit.num_vars(0);
+        let (last, r) = (&proof.layers[depth - 1], &layers[depth - 1].r);
+        let claims = [(&r[..k], last.left), (&r[k..2 * k], last.right)];
+        for ((x, value), opening) in claims.iter().zip(&proof.input_openings) {
+            let valid = self.input_pcs.verify(
+                &proof.input_commitment,
+                &input_point(x),
+                *value,
+                opening,
+                &mut transcript,
+            );
+            if !valid {
+                return Err("invalid opening of the inputs".to_string());
+            }
+        }
+        Ok(Challenges { z, layers })
+    }
+}
+
+// checks the sumchecks of the layers and the claims they reduce to against
+// the given challenges
+fn check_layers<F: Field, U: UnivariatePcs<F>, M: MultilinearPcs<F>>(
+    circuit: &LayeredCircuit,
+    outputs: &[F],
+    proof: &ZkGkrProof<F, U, M>,
+    challenges: &Challenges<F>,
+) -> Result<(), String> {
+    let depth = circuit.depth();
+    let mut claim = mle_evaluate(&circuit.pad(depth, outputs.to_vec()), &challenges.z);
+    let mut points = vec![challenges.z.clone()];
+    let mut weights = vec![F::ONE];
+    let layers = proof.layers.iter().zip(&challenges.layers);
+    for ((layer, c), i) in layers.zip((1..=depth).rev()) {
+        let oracle = layer_oracle(
+            circuit,
+            i,
+            (&weights, &points),
+            (layer.left, layer.right),
+            &layer.mask_evals,
+        );
+        let degrees = summand_degrees(circuit, i);
+        check_rounds(&oracle, &degrees, claim, &layer.sumcheck, c.rho, &c.r)
+            .map_err(|err| format!("layer {}: {}", i, err))?;
+
+        let k = circuit.num_vars(i - 1);
+        claim = c.alpha * layer.left + c.beta * layer.right;
+        weights = vec![c.alpha, c.beta];
+        points = vec![c.r[..k].to_vec(), c.r[k..2 * k].to_vec()];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{sample_circuit, sample_inputs};
+    use super::*;
+    use crate::hyrax::Hyrax;
+    use crate::kzg::Srs;
+    use crate::oracle::Oracle;
+    use crate::pcs::{IdealOracle, PolynomialCommitment};
+    use crate::sumcheck::zk::tests::simulate_proof as simulate_sumcheck;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Projective};
+
+    type Ideal =
+        ZkGkr<Fr, IdealOracle<DensePolynomial<Fr>>, IdealOracle<DenseMultilinearExtension<Fr>>>;
+
+    fn random_challenges(circuit: &LayeredCircuit) -> Challenges<Fr> {
+        let mut rng = test_rng();
+        let mut rand = || Fr::rand(&mut rng);
+        let z = (0..circuit.num_vars(circuit.depth()))
+            .map(|_| rand())
+            .collect();
+        let layers = (1..=circuit.depth())
+            .rev()
+            .map(|i| LayerChallenges {
+                rho: rand(),
+                r: summand_degrees(circuit, i).iter().map(|_| rand()).collect(),
+                alpha: rand(),
+                beta: rand(),
+            })
+            .collect();
+        Challenges { z, layers }
+    }
+
+    // Honest-verifier simulator: knows the circuit, the outputs and the
+    // challenges, but not the inputs. It commits to random inputs and masks,
+    // picks the claims about the intermediate layers at random, and simulates
+    // every sumcheck from its claim and the value of the summand at its point.
+    fn simulate(
+        gkr: &Ideal,
+        outputs: &[Fr],
+        challenges: &Challenges<Fr>,
+    ) -> ZkGkrProof<Fr, IdealOracle<DensePolynomial<Fr>>, IdealOracle<DenseMultilinearExtension<Fr>>>
+    {
+        let mut rng = test_rng();
+        let circuit = &gkr.circuit;
+        let depth = circuit.depth();
+        let pcs = gkr.sumcheck.pcs();
+        let mut transcript = Transcript::new(b"simulator");
+
+        let inputs: Vec<Fr> = (0..2 << circuit.num_vars(0))
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let input_poly = DenseMultilinearExtension::from_evaluations_vec(
+            circuit.num_vars(0) + 1,
+            inputs.clone(),
+        );
+        let (input_commitment, input_data) = gkr.input_pcs.commit(&input_poly);
+        let layer_masks: Vec<DensePolynomial<Fr>> = (0..2 * (depth - 1))
+            .map(|_| DensePolynomial::rand(1, &mut rng))
+            .collect();
+        let mask_commitments = layer_masks.iter().map(|r| pcs.commit(r).0).collect();
+
+        let mut claim = mle_evaluate(&circuit.pad(depth, outputs.to_vec()), &challenges.z);
+        let mut points = vec![challenges.z.clone()];
+        let mut weights = vec![Fr::ONE];
+        let mut layers = vec![];
+        for (c, i) in challenges.layers.iter().zip((1..=depth).rev()) {
+            let k = circuit.num_vars(i - 1);
+            let (r_x, r_y) = (&c.r[..k], &c.r[k..2 * k]);
+            let (left, right) = if i == 1 {
+                let half = inputs.len() / 2;
+                let mask = LayerMask::Blinding(inputs[half..].to_vec());
+                (
+                    mask.evaluate(&inputs[..half], r_x),
+                    mask.evaluate(&inputs[..half], r_y),
+                )
+            } else {
+                (Fr::rand(&mut rng), Fr::rand(&mut rng))
+            };
+            let own_masks = if i < depth {
+                &layer_masks[2 * (i - 1)..2 * i]
+            } else {
+                &[]
+            };
+            let mask_evals: Vec<Fr> = own_masks.iter().map(|r| r.evaluate(&c.r[2 * k])).collect();
+            let mask_openings = own_masks
+                .iter()
+                .map(|r| pcs.open(r, &(), &c.r[2 * k], &mut transcript).1)
+                .collect();
+
+            let oracle = layer_oracle(circuit, i, (&weights, &points), (left, right), &mask_evals);
+            let degrees = summand_degrees(circuit, i);
+            let g_at_r = oracle.query(&c.r);
+            let sumcheck = simulate_sumcheck(pcs, claim, g_at_r, &degrees, (c.rho, &c.r), &mut rng);
+
+            claim = c.alpha * left + c.beta * right;
+            weights = vec![c.alpha, c.beta];
+            points = vec![r_x.to_vec(), r_y.to_vec()];
+            layers.push(ZkLayerProof {
+                sumcheck,
+                left,
+                right,
+                mask_evals,
+                mask_openings,
+            });
+        }
+
+        let input_openings = points
+            .iter()
+            .map(|x| {
+                gkr.input_pcs
+                    .open(&input_poly, &input_data, &input_point(x), &mut transcript)
+                    .1
+            })
+            .collect();
+        ZkGkrProof {
+            input_commitment,
+            mask_commitments,
+            layers,
+            input_openings,
+        }
+    }
+
+    #[test]
+    fn test_zk_gkr() {
+        let mut rng = test_rng();
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let gkr = Ideal::new(circuit.clone(), &mut rng);
+        let (outputs, proof) = gkr.prove(&inputs, &mut rng);
+        assert_eq!(outputs, vec![circuit.evaluate(&inputs)[3][0]]);
+        assert_eq!(gkr.verify(&outputs, &proof), Ok(()));
+    }
+
+    #[test]
+    fn test_zk_gkr_commitments() {
+        let mut rng = test_rng();
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let gkr = ZkGkr::<Fr, Srs<Bls12_381>, Hyrax<G1Projective>>::new(circuit, &mut rng);
+        let (outputs, proof) = gkr.prove(&inputs, &mut rng);
+        assert_eq!(gkr.verify(&outputs, &proof), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_output() {
+        let mut rng = test_rng();
+        let gkr = Ideal::new(sample_circuit(), &mut rng);
+        let (outputs, proof) = gkr.prove(&sample_inputs(), &mut rng);
+        assert!(gkr.verify(&[outputs[0] + Fr::ONE], &proof).is_err());
+    }
+
+    #[test]
+    fn test_tampered_claims() {
+        let mut rng = test_rng();
+        let gkr = Ideal::new(sample_circuit(), &mut rng);
+        let (outputs, mut proof) = gkr.prove(&sample_inputs(), &mut rng);
+        proof.layers[1].mask_evals[0] += Fr::ONE;
+        assert!(gkr.verify(&outputs, &proof).is_err());
+
+        let (outputs, mut proof) = gkr.prove(&sample_inputs(), &mut rng);
+        proof.layers[2].right += Fr::ONE;
+        assert!(gkr.verify(&outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_masked_claims() {
+        // the claims about an intermediate layer are not the evaluations of
+        // its multilinear extension
+        let mut rng = test_rng();
+        let circuit = sample_circuit();
+        let inputs = sample_inputs();
+        let gkr = Ideal::new(circuit.clone(), &mut rng);
+        let (outputs, proof) = gkr.prove(&inputs, &mut rng);
+        let challenges = gkr.challenges(&outputs, &proof).unwrap();
+        let values = circuit.evaluate(&inputs);
+        let r_x = &challenges.layers[0].r[..circuit.num_vars(2)];
+        assert_ne!(proof.layers[0].left, mle_evaluate(&values[2], r_x));
+    }
+
+    #[test]
+    fn test_simulator_accepted() {
+        let mut rng = test_rng();
+        let circuit = sample_circuit();
+        let gkr = Ideal::new(circuit.clone(), &mut rng);
+        let (outputs, _) = gkr.prove(&sample_inputs(), &mut rng);
+        let challenges = random_challenges(&circuit);
+
+        let proof = simulate(&gkr, &outputs, &challenges);
+        assert_eq!(
+            check_layers(&circuit, &outputs, &proof, &challenges),
+            Ok(())
+        );
+        // the proof is bound to the outputs
+        let wrong = vec![outputs[0] + Fr::ONE];
+        assert!(check_layers(&circuit, &wrong, &proof, &challenges).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Sumcheck based grand product (Thaler §15, Lasso, Spice).
+//!
+//! The values are the input layer of a binary tree of multiplication gates.
+//! Layer i has 2^i nodes and its multilinear extension satisfies
+//!
+//!   V_i(z) = sum_{x in {0,1}^i} eq(z, x) * V_{i+1}(0, x) * V_{i+1}(1, x)
+//!
+//! so one sumcheck per layer reduces a claim about V_i to claims about
+//! V_{i+1}(0, r) and V_{i+1}(1, r), which the verifier merges into a single
+//! claim V_{i+1}(c, r) for a random c. The last claim is about the multilinear
+//! extension of the values and is checked against an oracle to them.
+use crate::multilinear::{eq_evaluate, eq_table};
+use crate::oracle::Oracle;
+use crate::sumcheck::{
+    prove_noninteractive, verify_noninteractive, ProductPolynomial, VerifierState,
+};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LayerProof<F: Field> {
+    round_polys: Vec<UnivariatePolynomial<F>>,
+    left: F,
+    right: F,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProductProof<F: Field> {
+    layers: Vec<LayerProof<F>>,
+}
+
+/// Claim the proof reduces to: the multilinear extension of the values
+/// evaluates to `value` at `point`
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputClaim<F: Field> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+// final check of the sumcheck of a layer, eq(z, r) * V_{i+1}(0, r) * V_{i+1}(1, r),
+// where the last two factors are the values claimed by the prover
+struct LayerOracle<'a, F: Field> {
+    z: &'a [F],
+    left: F,
+    right: F,
+}
+
+impl<F: Field> Oracle<F> for LayerOracle<'_, F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        eq_evaluate(self.z, point) * self.left * self.right
+    }
+}
+
+// layers[0] = [product], ..., layers[k] = values
+fn build_layers<F: Field>(values: &[F]) -> Vec<Vec<F>> {
+    assert!(
+        values.len().is_power_of_two(),
+        "number of values must be a power of 2"
+    );
+    let mut layers = vec![values.to_vec()];
+    while layers[0].len() > 1 {
+        let layer = layers[0].chunks(2).map(|c| c[0] * c[1]).collect();
+        layers.insert(0, layer);
+    }
+    layers
+}
+
+fn new_transcript<F: Field>(num_vars: usize, product: &F) -> Transcript {
+    let mut transcript = Transcript::new(b"gkr-grand-product");
+    transcript.append_message(b"num_vars", &(num_vars as u64).to_le_bytes());
+    transcript.append_field(b"product", product);
+    transcript
+}
+
+/// Calculates the product of the values and proves it in time O(n)
+pub fn prove_product<F: Field>(values: &[F]) -> (F, ProductProof<F>) {
+    let layers = build_layers(values);
+    let product = layers[0][0];
+    let mut transcript = new_transcript(layers.len() - 1, &product);
+
+    let mut z: Vec<F> = vec![];
+    let mut proofs = Vec::with_capacity(layers.len() - 1);
+    for (i, next) in layers.iter().skip(1).enumerate() {
+        let left: Vec<F> = next.iter().step_by(2).copied().collect();
+        let right: Vec<F> = next.iter().skip(1).step_by(2).copied().collect();
+
+        let (round_polys, left, right, rs) = if i == 0 {
+            (vec![], left[0], right[0], vec![])
+        } else {
+            let poly = ProductPolynomial::new(i, vec![eq_table(&z), left, right]);
+            let (round_polys, prover) = prove_noninteractive(poly, &mut transcript);
+            let evals = prover.get_poly().final_evaluations();
+            (
+                round_polys,
+                evals[1],
+                evals[2],
+                prover.get_random_vars().to_vec(),
+            )
+        };
+
+        transcript.append_field(b"left", &left);
+        transcript.append_field(b"right", &right);
+        let c: F = transcript.challenge_field(b"c");
+        z = std::iter::once(c).chain(rs).collect();
+
+        proofs.push(LayerProof {
+            round_polys,
+            left,
+            right,
+        });
+    }
+
+    (product, ProductProof { layers: proofs })
+}
+
+/// Reduces the claimed product of 2^num_vars values to a claim about the
+/// multilinear extension of the values
+pub fn reduce_product_claim<F: Field>(
+    num_vars: usize,
+    product: F,
+    proof: &ProductProof<F>,
+) -> Result<InputClaim<F>, String> {
+    if proof.layers.len() != num_vars {
+        return Err("invalid number of layers".to_string());
+    }
+    let mut transcript = new_transcript(num_vars, &product);
+
+    let mut z: Vec<F> = vec![];
+    let mut claim = product;
+    for (i, layer) in proof.layers.iter().enumerate() {
+        if layer.round_polys.len() != i {
+            return Err(format!("layer {}: invalid number of rounds", i));
+        }
+
+        let rs = if i == 0 {
+            if layer.left * layer.right != claim {
+                return Err("product of the children does not match the root".to_string());
+            }
+            vec![]
+        } else {
+            let oracle = LayerOracle {
+                z: &z,
+                left: layer.left,
+                right: layer.right,
+            };
+            let verifier = VerifierState::with_oracle(claim, i, oracle);
+            verify_noninteractive(verifier, &layer.round_polys, 3, &mut transcript)
+                .map_err(|err| format!("layer {}: {}", i, err))?
+        };
+
+        transcript.append_field(b"left", &layer.left);
+        transcript.append_field(b"right", &layer.right);
+        let c: F = transcript.challenge_field(b"c");
+        claim = layer.left + c * (layer.right - layer.left);
+        z = std::iter::once(c).chain(rs).collect();
+    }
+
+    Ok(InputClaim {
+        point: z,
+        value: claim,
+    })
+}
+
+/// Verifies that the product of the 2^num_vars values behind the oracle is the
+/// given one. The oracle is queried once.
+pub fn verify_product<F: Field, O: Oracle<F, Point = [F]>>(
+    num_vars: usize,
+    product: F,
+    proof: &ProductProof<F>,
+    values: &O,
+) -> bool {
+    match reduce_product_claim(num_vars, product, proof) {
+        Ok(claim) => values.query(&claim.point) == claim.value,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn setup(num_vars: usize) -> (Vec<Fr>, DenseMultilinearExtension<Fr>) {
+        let mut rng = test_rng();
+        let values: Vec<Fr> = (0..(1 << num_vars)).map(|_| Fr::rand(&mut rng)).collect();
+        let mle = DenseMultilinearExtension::from_evaluations_slice(num_vars, &values);
+        (values, mle)
+    }
+
+    #[test]
+    fn test_build_layers() {
+        let values: Vec<Fr> = (1..=4).map(Fr::from).collect();
+        let layers = build_layers(&values);
+        assert_eq!(layers[0], vec![Fr::from(24)]);
+        assert_eq!(layers[1], vec![Fr::from(2), Fr::from(12)]);
+        assert_eq!(layers[2], values);
+    }
+
+    #[test]
+    fn test_prove_product() {
+        for num_vars in 0..6 {
+            let (values, mle) = setup(num_vars);
+            let (product, proof) = prove_product(&values);
+            assert_eq!(product, values.iter().product::<Fr>());
+            assert!(verify_product(num_vars, product, &proof, &mle));
+        }
+    }
+
+    #[test]
+    fn test_wrong_product() {
+        let (values, mle) = setup(4);
+        let (product, proof) = prove_product(&values);
+        assert!(!verify_product(4, product + Fr::ONE, &proof, &mle));
+    }
+
+    #[test]
+    fn test_wrong_values() {
+        let (values, _) = setup(4);
+        let (product, proof) = prove_product(&values);
+        let (_, other) = setup(3);
+        let other = DenseMultilinearExtension::from_evaluations_vec(
+            4,
+            other.iter().chain(other.iter()).copied().collect(),
+        );
+        assert!(!verify_product(4, product, &proof, &other));
+    }
+
+    #[test]
+    fn test_tampered_layer() {
+        let (values, mle) = setup(4);
+        let (product, mut proof) = prove_product(&values);
+        proof.layers[2].left += Fr::ONE;
+        assert!(reduce_product_claim(4, product, &proof).is_err());
+        assert!(!verify_product(4, product, &proof, &mle));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let (values, mle) = setup(3);
+        let (product, proof) = prove_product(&values);
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let proof = ProductProof::<Fr>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(verify_product(3, product, &proof, &mle));
+    }
+}
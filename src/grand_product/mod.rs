@@ -0,0 +1,245 @@
+//! Grand product argument, ported from `scripts/grand_product_argument.ipynb`.
+//!
+//! The prover arranges the values W in a binary tree where every parent node is
+//! the product of its children, so the root is y = prod_i W_i. The tree is
+//! stored as a vector: the leaves are at positions 0..n and the internal node i,
+//! n <= i <= 2n-2, has children l(i) = 2(i - n) and r(i) = l(i) + 1.
+//!
+//! The prover sends an oracle to the low degree extension g of the tree. The
+//! constraint h(x) = g(x) - g(l(x)) * g(r(x)) vanishes on the internal nodes H,
+//! so the prover also sends the quotient q = h / Z_H and the verifier checks
+//! h(r) = q(r) * Z_H(r) at a random point r, together with g(2n-2) = y.
+//!
+//! `gkr` proves the same tree layer by layer with the sumcheck protocol instead.
+pub mod gkr;
+mod prover;
+mod verifier;
+
+pub use gkr::{prove_product, reduce_product_claim, verify_product, InputClaim, ProductProof};
+pub use prover::Prover;
+pub use verifier::Verifier;
+
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::DenseUVPolynomial;
+
+// number is a power of 2
+fn is_power_of_2(n: usize) -> bool {
+    n != 0 && n & (n - 1) == 0
+}
+
+/// Returns the indexes of the children of an internal node of the tree
+pub fn children(index: usize, n: usize) -> (usize, usize) {
+    assert!(
+        n <= index && index <= 2 * n - 2,
+        "index is not an internal node"
+    );
+    let left_child = (index - n) * 2;
+    (left_child, left_child + 1)
+}
+
+/// Creates the binary product tree of the given values
+pub fn create_tree<F: Field>(values: &[F]) -> Vec<F> {
+    let n = values.len();
+    assert!(is_power_of_2(n), "number of values must be a power of 2");
+
+    let mut tree = values.to_vec();
+    for i in n..(2 * n - 1) {
+        let (left_i, right_i) = children(i, n);
+        let result = tree[left_i] * tree[right_i];
+        tree.push(result);
+    }
+    tree
+}
+
+/// Indexes of the internal nodes of a tree with n leaves, n..2n-1
+pub fn internal_nodes<F: Field>(n: usize) -> Vec<F> {
+    (n..(2 * n - 1)).map(|i| F::from(i as u64)).collect()
+}
+
+/// l(x) = 2(x - n), maps an internal node to its left child
+pub fn left_child_poly<F: Field>(n: usize) -> DensePolynomial<F> {
+    let two = F::from(2u64);
+    DensePolynomial::from_coefficients_vec(vec![-two * F::from(n as u64), two])
+}
+
+/// r(x) = 2(x - n) + 1, maps an internal node to its right child
+pub fn right_child_poly<F: Field>(n: usize) -> DensePolynomial<F> {
+    let two = F::from(2u64);
+    DensePolynomial::from_coefficients_vec(vec![F::ONE - two * F::from(n as u64), two])
+}
+
+/// Running products of the fractions num_i / den_i, acc_0 = 1 and
+/// acc_{i+1} = acc_i * num_i / den_i. The last of the n + 1 values is the
+/// whole product, which is 1 when the numerators are a permutation of the
+/// denominators. This is the accumulator Z of the PLONK permutation argument.
+pub fn running_products<F: Field>(numerators: &[F], denominators: &[F]) -> Vec<F> {
+    assert_eq!(
+        numerators.len(),
+        denominators.len(),
+        "one denominator per numerator"
+    );
+    let mut inverses = denominators.to_vec();
+    ark_ff::batch_inversion(&mut inverses);
+    assert!(
+        inverses.iter().all(|d| !d.is_zero()),
+        "zero denominator in the running product"
+    );
+    let mut products = Vec::with_capacity(numerators.len() + 1);
+    products.push(F::ONE);
+    for (num, inverse) in numerators.iter().zip(inverses) {
+        let last = *products.last().unwrap();
+        products.push(last * num * inverse);
+    }
+    products
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::PolynomialOracle;
+    use crate::test_fields::F127;
+    use crate::univariate::{lagrange_interpolate, quo_rem, vanishing_polynomial};
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::Polynomial;
+    use ark_std::test_rng;
+
+    fn setup() -> Vec<F127> {
+        let mut rng = test_rng();
+        (0..8).map(|_| F127::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_create_tree() {
+        let values: Vec<F127> = (1..=4).map(F127::from).collect();
+        let tree = create_tree(&values);
+        assert_eq!(tree.len(), 2 * values.len() - 1);
+        assert_eq!(tree[4..], [F127::from(2), F127::from(12), F127::from(24)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_create_tree_not_power_of_2() {
+        create_tree(&[F127::from(1), F127::from(2), F127::from(3)]);
+    }
+
+    #[test]
+    fn test_child_polys() {
+        let n = 8;
+        let tree_indexes = n..(2 * n - 1);
+        let points = |f: fn(usize, usize) -> usize| -> Vec<(F127, F127)> {
+            tree_indexes
+                .clone()
+                .map(|i| (F127::from(i as u64), F127::from(f(i, n) as u64)))
+                .collect()
+        };
+        let left_child_poly = lagrange_interpolate(&points(|i, n| children(i, n).0));
+        let right_child_poly = lagrange_interpolate(&points(|i, n| children(i, n).1));
+
+        assert_eq!(left_child_poly, super::left_child_poly(n));
+        assert_eq!(right_child_poly, super::right_child_poly(n));
+        assert_eq!(
+            &left_child_poly + &DensePolynomial::from_coefficients_vec(vec![F127::ONE]),
+            right_child_poly
+        );
+    }
+
+    #[test]
+    fn test_constraint_vanishes_on_internal_nodes() {
+        let values = setup();
+        let n = values.len();
+        let prover = Prover::new(values);
+        let lde = prover.lde();
+        let (l, r) = (left_child_poly::<F127>(n), right_child_poly::<F127>(n));
+        let h = |x: &F127| {
+            lde.evaluate(x) - lde.evaluate(&l.evaluate(x)) * lde.evaluate(&r.evaluate(x))
+        };
+
+        for i in internal_nodes::<F127>(n) {
+            assert!(h(&i).is_zero());
+        }
+
+        let z = vanishing_polynomial(&internal_nodes::<F127>(n));
+        let x = F127::rand(&mut test_rng());
+        assert_eq!(h(&x), prover.quotient().evaluate(&x) * z.evaluate(&x));
+        let (_, remainder) = quo_rem(prover.quotient().naive_mul(&z), &z);
+        assert!(remainder.is_zero());
+    }
+
+    #[test]
+    fn test_running_products() {
+        let values: Vec<F127> = (1..=8).map(F127::from).collect();
+        let mut permuted = values.clone();
+        permuted.rotate_left(3);
+        let products = running_products(&values, &permuted);
+        assert_eq!(products.len(), values.len() + 1);
+        assert_eq!(products[0], F127::ONE);
+        assert_eq!(
+            products[2],
+            values[0] * values[1] / (permuted[0] * permuted[1])
+        );
+        assert_eq!(*products.last().unwrap(), F127::ONE);
+
+        permuted[0] += F127::ONE;
+        assert_ne!(
+            *running_products(&values, &permuted).last().unwrap(),
+            F127::ONE
+        );
+    }
+
+    #[test]
+    fn test_grand_product_argument() {
+        let values = setup();
+        let n = values.len();
+        let prover = Prover::new(values.clone());
+        assert_eq!(prover.product(), values.iter().product::<F127>());
+
+        let verifier = Verifier::new(
+            n,
+            prover.product(),
+            PolynomialOracle::new(prover.lde().clone()),
+            PolynomialOracle::new(prover.quotient().clone()),
+        );
+        assert!(verifier.verify(&mut test_rng()));
+    }
+
+    #[test]
+    fn test_wrong_product() {
+        let values = setup();
+        let n = values.len();
+        let prover = Prover::new(values);
+
+        let verifier = Verifier::new(
+            n,
+            prover.product() + F127::ONE,
+            PolynomialOracle::new(prover.lde().clone()),
+            PolynomialOracle::new(prover.quotient().clone()),
+        );
+        assert!(!verifier.verify(&mut test_rng()));
+    }
+
+    #[test]
+    fn test_inconsistent_tree() {
+        let values = setup();
+        let n = values.len();
+        let mut tree = create_tree(&values);
+        tree[n] += F127::ONE;
+        let lde = lagrange_interpolate(
+            &tree
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (F127::from(i as u64), *v))
+                .collect::<Vec<_>>(),
+        );
+
+        // an honest quotient does not exist, reuse the one of the honest tree
+        let prover = Prover::new(values);
+        let verifier = Verifier::new(
+            n,
+            prover.product(),
+            PolynomialOracle::new(lde),
+            PolynomialOracle::new(prover.quotient().clone()),
+        );
+        assert!(!verifier.verify(&mut test_rng()));
+    }
+}
@@ -0,0 +1,69 @@
+use super::{create_tree, internal_nodes, left_child_poly, right_child_poly};
+use crate::univariate::{divide_by_vanishing, lagrange_interpolate};
+use ark_ff::{Field, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::DenseUVPolynomial;
+
+pub struct Prover<F: Field> {
+    tree: Vec<F>,
+    lde: DensePolynomial<F>,
+    quotient: DensePolynomial<F>,
+}
+
+impl<F: Field> Prover<F> {
+    pub fn new(values: Vec<F>) -> Self {
+        let n = values.len();
+        let tree = create_tree(&values);
+        let points: Vec<(F, F)> = tree
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (F::from(i as u64), *value))
+            .collect();
+        let lde = lagrange_interpolate(&points);
+
+        // h(x) = g(x) - g(l(x)) * g(r(x))
+        let left = compose(&lde, &left_child_poly(n));
+        let right = compose(&lde, &right_child_poly(n));
+        let h = &lde - &left.naive_mul(&right);
+
+        let (quotient, remainder) = divide_by_vanishing(&h, &internal_nodes(n));
+        assert!(
+            remainder.is_zero(),
+            "constraint does not vanish on the internal nodes"
+        );
+
+        Prover {
+            tree,
+            lde,
+            quotient,
+        }
+    }
+
+    pub fn product(&self) -> F {
+        *self.tree.last().unwrap()
+    }
+
+    pub fn tree(&self) -> &[F] {
+        &self.tree
+    }
+
+    /// low degree extension of the tree, g(i) = tree[i]
+    pub fn lde(&self) -> &DensePolynomial<F> {
+        &self.lde
+    }
+
+    /// q(x) = h(x) / Z_H(x)
+    pub fn quotient(&self) -> &DensePolynomial<F> {
+        &self.quotient
+    }
+}
+
+// calculates f(g(x)) using horner's method
+fn compose<F: Field>(f: &DensePolynomial<F>, g: &DensePolynomial<F>) -> DensePolynomial<F> {
+    f.coeffs
+        .iter()
+        .rev()
+        .fold(DensePolynomial::zero(), |acc, c| {
+            &acc.naive_mul(g) + &DensePolynomial::from_coefficients_vec(vec![*c])
+        })
+}
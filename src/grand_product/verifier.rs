@@ -0,0 +1,45 @@
+use super::{internal_nodes, left_child_poly, right_child_poly};
+use crate::oracle::Oracle;
+use crate::univariate::vanishing_polynomial;
+use ark_ff::Field;
+use ark_poly::Polynomial;
+use ark_std::rand::RngCore;
+
+pub struct Verifier<F: Field, O: Oracle<F, Point = F>> {
+    n: usize,
+    product: F,
+    lde: O,
+    quotient: O,
+}
+
+impl<F: Field, O: Oracle<F, Point = F>> Verifier<F, O> {
+    pub fn new(n: usize, product: F, lde: O, quotient: O) -> Self {
+        Verifier {
+            n,
+            product,
+            lde,
+            quotient,
+        }
+    }
+
+    /// Checks that the root of the tree is the claimed product and that
+    /// g(x) - g(l(x)) * g(r(x)) = q(x) * Z_H(x) at a random point
+    pub fn verify<R: RngCore>(&self, rng: &mut R) -> bool {
+        let root = F::from((2 * self.n - 2) as u64);
+        if self.lde.query(&root) != self.product {
+            return false;
+        }
+
+        let r = F::rand(rng);
+        self.check_at(r)
+    }
+
+    fn check_at(&self, r: F) -> bool {
+        let left = self.lde.query(&left_child_poly(self.n).evaluate(&r));
+        let right = self.lde.query(&right_child_poly(self.n).evaluate(&r));
+        let h = self.lde.query(&r) - left * right;
+
+        let z = vanishing_polynomial(&internal_nodes(self.n)).evaluate(&r);
+        h == self.quotient.query(&r) * z
+    }
+}
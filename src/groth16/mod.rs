@@ -0,0 +1,291 @@
+//! Groth16 (Groth 2016, Thaler Chapter 17.5): a pairing based SNARK with a
+//! circuit specific trusted setup and proofs of three group elements.
+//!
+//! The R1CS is reduced to a QAP, and the setup publishes the polynomials of
+//! the QAP evaluated at a secret τ, hidden in the exponent and separated by
+//! the secrets α, β, γ and δ. The prover sends
+//!
+//!   A = α + A(τ) + rδ,  B = β + B(τ) + sδ,
+//!   C = (sum_{i witness} z_i (β u_i(τ) + α v_i(τ) + w_i(τ)) + h(τ) Z_H(τ)) / δ
+//!       + sA + rB - rsδ
+//!
+//! and the verifier checks e(A, B) = e(α, β) e(L, γ) e(C, δ), where L is the
+//! same combination as in C over the constant and the public values.
+mod qap;
+
+pub use qap::Qap;
+
+use crate::r1cs::R1CS;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, UniformRand, Zero};
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    pub alpha_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g2: E::G2Affine,
+    /// (β u_i(τ) + α v_i(τ) + w_i(τ)) / γ for the constant and public values
+    pub public_query: Vec<E::G1Affine>,
+}
+
+/// Verifying key with e(α, β) computed once, leaving three pairings per proof
+#[derive(Clone, Debug)]
+pub struct PreparedVerifyingKey<E: Pairing> {
+    vk: VerifyingKey<E>,
+    alpha_beta: PairingOutput<E>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProvingKey<E: Pairing> {
+    pub vk: VerifyingKey<E>,
+    beta_g1: E::G1Affine,
+    delta_g1: E::G1Affine,
+    /// u_i(τ) for every variable
+    a_query: Vec<E::G1Affine>,
+    /// v_i(τ) for every variable, in both groups
+    b_g1_query: Vec<E::G1Affine>,
+    b_g2_query: Vec<E::G2Affine>,
+    /// τ^i Z_H(τ) / δ for i < n - 1
+    h_query: Vec<E::G1Affine>,
+    /// (β u_i(τ) + α v_i(τ) + w_i(τ)) / δ for the witness variables
+    witness_query: Vec<E::G1Affine>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Groth16Proof<E: Pairing> {
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+}
+
+/// QAP of the circuit together with its proving key
+pub struct Groth16<E: Pairing> {
+    qap: Qap<E::ScalarField>,
+    pk: ProvingKey<E>,
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    pub fn prepare(&self) -> PreparedVerifyingKey<E> {
+        PreparedVerifyingKey {
+            vk: self.clone(),
+            alpha_beta: E::pairing(self.alpha_g1, self.beta_g2),
+        }
+    }
+}
+
+impl<E: Pairing> Groth16<E> {
+    /// Trusted setup for the given R1CS. The secrets are dropped at the end,
+    /// whoever knows them can prove false statements.
+    pub fn setup<R: RngCore>(r1cs: &R1CS<E::ScalarField>, rng: &mut R) -> Self {
+        let qap = Qap::from_r1cs(r1cs);
+        let domain = *qap.domain();
+        let tau = loop {
+            // τ in H would make Z_H(τ) = 0
+            let tau = E::ScalarField::rand(rng);
+            if !domain.evaluate_vanishing_polynomial(tau).is_zero() {
+                break tau;
+            }
+        };
+        let [alpha, beta, gamma, delta] = [(); 4].map(|_| E::ScalarField::rand(rng));
+        let gamma_inverse = gamma.inverse().expect("γ is not zero");
+        let delta_inverse = delta.inverse().expect("δ is not zero");
+
+        let [u, v, w] = qap.evaluate_at(tau);
+        let combined: Vec<E::ScalarField> = (0..qap.num_variables())
+            .map(|i| beta * u[i] + alpha * v[i] + w[i])
+            .collect();
+        let (public, witness) = combined.split_at(qap.num_public() + 1);
+        let public: Vec<_> = public.iter().map(|x| *x * gamma_inverse).collect();
+        let witness: Vec<_> = witness.iter().map(|x| *x * delta_inverse).collect();
+        let vanishing = domain.evaluate_vanishing_polynomial(tau) * delta_inverse;
+        let h: Vec<_> = std::iter::successors(Some(vanishing), |p| Some(*p * tau))
+            .take(domain.size() - 1)
+            .collect();
+
+        let vk = VerifyingKey {
+            alpha_g1: g1::<E>(&[alpha])[0],
+            beta_g2: g2::<E>(&[beta])[0],
+            gamma_g2: g2::<E>(&[gamma])[0],
+            delta_g2: g2::<E>(&[delta])[0],
+            public_query: g1::<E>(&public),
+        };
+        let pk = ProvingKey {
+            vk,
+            beta_g1: g1::<E>(&[beta])[0],
+            delta_g1: g1::<E>(&[delta])[0],
+            a_query: g1::<E>(&u),
+            b_g1_query: g1::<E>(&v),
+            b_g2_query: g2::<E>(&v),
+            h_query: g1::<E>(&h),
+            witness_query: g1::<E>(&witness),
+        };
+        Groth16 { qap, pk }
+    }
+
+    pub fn proving_key(&self) -> &ProvingKey<E> {
+        &self.pk
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        &self.pk.vk
+    }
+
+    pub fn prove<R: RngCore>(
+        &self,
+        public: &[E::ScalarField],
+        witness: &[E::ScalarField],
+        rng: &mut R,
+    ) -> Groth16Proof<E> {
+        assert_eq!(
+            public.len(),
+            self.qap.num_public(),
+            "wrong number of public values"
+        );
+        let mut z = vec![E::ScalarField::ONE];
+        z.extend_from_slice(public);
+        z.extend_from_slice(witness);
+        assert_eq!(z.len(), self.qap.num_variables(), "wrong witness length");
+        let h = self.qap.quotient(&z);
+        let pk = &self.pk;
+        let (r, s) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+
+        let delta = pk.delta_g1.into_group();
+        let a = pk.vk.alpha_g1 + E::G1::msm_unchecked(&pk.a_query, &z) + delta * r;
+        let b_g1 = pk.beta_g1 + E::G1::msm_unchecked(&pk.b_g1_query, &z) + delta * s;
+        let b = pk.vk.beta_g2
+            + E::G2::msm_unchecked(&pk.b_g2_query, &z)
+            + pk.vk.delta_g2.into_group() * s;
+        let witness_part = E::G1::msm_unchecked(&pk.witness_query, &z[self.qap.num_public() + 1..]);
+        let h_part = E::G1::msm_unchecked(&pk.h_query, &h.coeffs);
+        let c = witness_part + h_part + a * s + b_g1 * r - delta * (r * s);
+
+        Groth16Proof {
+            a: a.into_affine(),
+            b: b.into_affine(),
+            c: c.into_affine(),
+        }
+    }
+}
+
+/// Checks e(A, B) = e(α, β) e(L, γ) e(C, δ)
+pub fn verify<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    public: &[E::ScalarField],
+    proof: &Groth16Proof<E>,
+) -> bool {
+    let vk = &pvk.vk;
+    if public.len() + 1 != vk.public_query.len() {
+        return false;
+    }
+    let public_part = vk.public_query[0] + E::G1::msm_unchecked(&vk.public_query[1..], public);
+    let product = E::multi_pairing(
+        [proof.a.into_group(), -public_part, -proof.c.into_group()],
+        [proof.b, vk.gamma_g2, vk.delta_g2],
+    );
+    product == pvk.alpha_beta
+}
+
+fn g1<E: Pairing>(scalars: &[E::ScalarField]) -> Vec<E::G1Affine> {
+    let g = E::G1::generator();
+    E::G1::normalize_batch(&scalars.iter().map(|x| g * x).collect::<Vec<_>>())
+}
+
+fn g2<E: Pairing>(scalars: &[E::ScalarField]) -> Vec<E::G2Affine> {
+    let h = E::G2::generator();
+    E::G2::normalize_batch(&scalars.iter().map(|x| h * x).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::fixtures::{cubic, repeated_squaring};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    #[test]
+    fn test_cubic() {
+        let mut rng = test_rng();
+        let (r1cs, public, witness) = cubic(3, 35);
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let pvk = groth16.verifying_key().prepare();
+        let proof = groth16.prove(&public, &witness, &mut rng);
+        assert!(verify(&pvk, &public, &proof));
+        assert!(!verify(&pvk, &[Fr::from(36)], &proof));
+        assert!(!verify(&pvk, &[], &proof));
+    }
+
+    #[test]
+    fn test_wrong_witness() {
+        let mut rng = test_rng();
+        let (r1cs, public, mut witness) = cubic(3, 35);
+        witness[2] = Fr::from(28);
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let proof = groth16.prove(&public, &witness, &mut rng);
+        assert!(!verify(&groth16.verifying_key().prepare(), &public, &proof));
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let mut rng = test_rng();
+        let (r1cs, public, witness) = cubic(3, 35);
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let pvk = groth16.verifying_key().prepare();
+        let proof = groth16.prove(&public, &witness, &mut rng);
+
+        let mut tampered = proof;
+        tampered.c = (proof.c + groth16.verifying_key().alpha_g1).into_affine();
+        assert!(!verify(&pvk, &public, &tampered));
+        let mut tampered = proof;
+        std::mem::swap(&mut tampered.a, &mut tampered.c);
+        assert!(!verify(&pvk, &public, &tampered));
+    }
+
+    #[test]
+    fn test_proofs_are_randomized() {
+        let mut rng = test_rng();
+        let (r1cs, public, witness) = cubic(3, 35);
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let pvk = groth16.verifying_key().prepare();
+        let first = groth16.prove(&public, &witness, &mut rng);
+        let second = groth16.prove(&public, &witness, &mut rng);
+        assert_ne!(first, second);
+        assert!(verify(&pvk, &public, &first) && verify(&pvk, &public, &second));
+    }
+
+    #[test]
+    fn test_larger_instance() {
+        let (r1cs, public, witness) = repeated_squaring();
+
+        let mut rng = test_rng();
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let proof = groth16.prove(&public, &witness, &mut rng);
+        assert!(verify(&groth16.verifying_key().prepare(), &public, &proof));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = test_rng();
+        let (r1cs, public, witness) = cubic(3, 35);
+        let groth16 = Groth16::<Bls12_381>::setup(&r1cs, &mut rng);
+        let proof = groth16.prove(&public, &witness, &mut rng);
+
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 48 + 96 + 48);
+        let proof = Groth16Proof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+
+        let mut bytes = vec![];
+        groth16
+            .verifying_key()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        let vk = VerifyingKey::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(&vk, groth16.verifying_key());
+        assert!(verify(&vk.prepare(), &public, &proof));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::r1cs::{SparseMatrix, R1CS};
+use ark_ff::FftField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
+
+/// Quadratic arithmetic program of a R1CS. Constraint j is placed at the j-th
+/// element ω^j of a FFT domain H, and variable i gets the polynomials u_i, v_i
+/// and w_i interpolating its columns of A, B and C. z satisfies the R1CS iff
+///
+///   (sum_i z_i u_i(X)) (sum_i z_i v_i(X)) - sum_i z_i w_i(X)
+///
+/// is divisible by Z_H(X) = X^n - 1.
+///
+/// The constraints z_i * 0 = 0 are appended for the constant and the public
+/// values, which makes their u_i linearly independent as Groth16 requires.
+#[derive(Clone, Debug)]
+pub struct Qap<F: FftField> {
+    domain: GeneralEvaluationDomain<F>,
+    a: SparseMatrix<F>,
+    b: SparseMatrix<F>,
+    c: SparseMatrix<F>,
+    num_public: usize,
+}
+
+impl<F: FftField> Qap<F> {
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Self {
+        let num_cols = r1cs.num_variables();
+        let mut a = r1cs.a.rows().to_vec();
+        let mut b = r1cs.b.rows().to_vec();
+        let mut c = r1cs.c.rows().to_vec();
+        for i in 0..=r1cs.num_public() {
+            a.push(vec![(i, F::ONE)]);
+            b.push(vec![]);
+            c.push(vec![]);
+        }
+        let domain = GeneralEvaluationDomain::new(a.len()).expect("no FFT domain of this size");
+        Qap {
+            domain,
+            a: SparseMatrix::new(num_cols, a),
+            b: SparseMatrix::new(num_cols, b),
+            c: SparseMatrix::new(num_cols, c),
+            num_public: r1cs.num_public(),
+        }
+    }
+
+    pub fn domain(&self) -> &GeneralEvaluationDomain<F> {
+        &self.domain
+    }
+
+    /// Length of z, including the constant 1
+    pub fn num_variables(&self) -> usize {
+        self.a.num_cols()
+    }
+
+    pub fn num_public(&self) -> usize {
+        self.num_public
+    }
+
+    /// Evaluations u_i(x), v_i(x) and w_i(x) for every variable i
+    pub fn evaluate_at(&self, x: F) -> [Vec<F>; 3] {
+        let lagrange = self.domain.evaluate_all_lagrange_coefficients(x);
+        [&self.a, &self.b, &self.c].map(|m| {
+            let mut evals = vec![F::ZERO; m.num_cols()];
+            for (row, col, value) in m.entries() {
+                evals[col] += lagrange[row] * value;
+            }
+            evals
+        })
+    }
+
+    /// Quotient h(X) = (A(X) B(X) - C(X)) / Z_H(X) for the assignment z. The
+    /// product is evaluated over a coset of H, where Z_H is a non zero
+    /// constant. If z does not satisfy the constraints the result is not the
+    /// quotient of anything.
+    pub fn quotient(&self, z: &[F]) -> DensePolynomial<F> {
+        assert_eq!(z.len(), self.num_variables(), "wrong assignment length");
+        let coset = self
+            .domain
+            .get_coset(F::GENERATOR)
+            .expect("the generator is not in the domain");
+        let [a, b, c] = [&self.a, &self.b, &self.c].map(|m| {
+            let mut evals = m.mul_vector(z);
+            self.domain.ifft_in_place(&mut evals);
+            coset.fft_in_place(&mut evals);
+            evals
+        });
+        let vanishing_inverse = self
+            .domain
+            .evaluate_vanishing_polynomial(F::GENERATOR)
+            .inverse()
+            .expect("the generator is not in the domain");
+        let mut h: Vec<F> = a
+            .iter()
+            .zip(&b)
+            .zip(&c)
+            .map(|((a, b), c)| (*a * b - c) * vanishing_inverse)
+            .collect();
+        coset.ifft_in_place(&mut h);
+        DensePolynomial::from_coefficients_vec(h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::fixtures::cubic;
+    use crate::univariate::divide_by_domain_vanishing;
+    use ark_ff::Zero;
+    use ark_poly::Polynomial;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn combine(evals: &[Fr], z: &[Fr]) -> Fr {
+        evals.iter().zip(z).map(|(e, z)| *e * z).sum()
+    }
+
+    #[test]
+    fn test_quotient() {
+        let (r1cs, public, witness) = cubic::<Fr>(3, 35);
+        let qap = Qap::from_r1cs(&r1cs);
+        assert_eq!(qap.domain().size(), 8);
+        let z = r1cs.assignment(&public, &witness);
+
+        // A(x) B(x) - C(x) = h(x) Z_H(x) at a point outside of H
+        let x = Fr::from(12345);
+        let [u, v, w] = qap.evaluate_at(x);
+        let h = qap.quotient(&z);
+        assert!(h.degree() <= qap.domain().size() - 2);
+        let lhs = combine(&u, &z) * combine(&v, &z) - combine(&w, &z);
+        assert_eq!(
+            lhs,
+            h.evaluate(&x) * qap.domain().evaluate_vanishing_polynomial(x)
+        );
+    }
+
+    #[test]
+    fn test_unsatisfied_is_not_divisible() {
+        let (r1cs, public, mut witness) = cubic::<Fr>(3, 35);
+        witness[1] = Fr::from(10);
+        let qap = Qap::from_r1cs(&r1cs);
+        let z = r1cs.assignment(&public, &witness);
+
+        let [a, b, c] = [&qap.a, &qap.b, &qap.c]
+            .map(|m| DensePolynomial::from_coefficients_vec(qap.domain().ifft(&m.mul_vector(&z))));
+        let (_, remainder) = divide_by_domain_vanishing(&(&(&a * &b) - &c), qap.domain());
+        assert!(!remainder.is_zero());
+    }
+}
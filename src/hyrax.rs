@@ -0,0 +1,319 @@
+//! Hyrax commitments to multilinear polynomials (Wahby et al. 2018).
+//!
+//! The N = 2^v evaluations are arranged as a matrix M with 2^{v/2} columns,
+//! the low variables indexing the column and the high ones the row, and every
+//! row is committed with Pedersen. Since eq(r, x) splits into
+//! eq(r_lo, col) eq(r_hi, row), f(r) = L^T M R with L = eq(r_hi, ·) and
+//! R = eq(r_lo, ·). By homomorphism the verifier computes the commitment to
+//! L^T M from the row commitments, and the prover shows that its inner product
+//! with R is f(r), either by sending L^T M or with an inner product argument.
+//! Commitments and openings are of size O(√N). Rows are not blinded, so this
+//! version is not hiding.
+use crate::ipa::{self, InnerProductProof};
+use crate::multilinear::{column_vars, combine_rows, inner_product, matrix_tensor};
+use crate::pcs::{open_separately, verify_separately, PolynomialCommitment};
+use crate::pedersen::{hash_to_curve, PedersenGenerators};
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+#[derive(Clone, Debug)]
+pub struct Hyrax<C: CurveGroup> {
+    num_vars: usize,
+    generators: PedersenGenerators<C>,
+    u: C::Affine,
+}
+
+/// Commitments to the rows of the evaluation matrix
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyraxCommitment<C: CurveGroup> {
+    pub rows: Vec<C::Affine>,
+}
+
+/// Opening sending the combination of the rows L^T M in the clear
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyraxProof<F: Field> {
+    pub combined_row: Vec<F>,
+}
+
+impl<C: CurveGroup> Hyrax<C> {
+    pub fn new(num_vars: usize, label: &[u8]) -> Self {
+        let num_cols = 1 << column_vars(num_vars);
+        Hyrax {
+            num_vars,
+            generators: PedersenGenerators::new(num_cols, label),
+            u: hash_to_curve::<C>(label, u64::MAX),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn commit(&self, poly: &DenseMultilinearExtension<C::ScalarField>) -> HyraxCommitment<C> {
+        assert_eq!(
+            poly.num_vars, self.num_vars,
+            "polynomial with a different number of variables"
+        );
+        let rows: Vec<C> = poly
+            .evaluations
+            .chunks(self.generators.len())
+            .map(|row| self.generators.commit_unblinded(row))
+            .collect();
+        HyraxCommitment {
+            rows: C::normalize_batch(&rows),
+        }
+    }
+
+    /// Opens the polynomial at the point, returning f(point) and L^T M
+    pub fn open(
+        &self,
+        poly: &DenseMultilinearExtension<C::ScalarField>,
+        point: &[C::ScalarField],
+    ) -> (C::ScalarField, HyraxProof<C::ScalarField>) {
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        let combined_row = self.combine_rows(poly, &left);
+        (
+            inner_product(&combined_row, &right),
+            HyraxProof { combined_row },
+        )
+    }
+
+    pub fn verify(
+        &self,
+        commitment: &HyraxCommitment<C>,
+        point: &[C::ScalarField],
+        value: C::ScalarField,
+        proof: &HyraxProof<C::ScalarField>,
+    ) -> bool {
+        if point.len() != self.num_vars
+            || commitment.rows.len() != 1 << (self.num_vars - column_vars(self.num_vars))
+            || proof.combined_row.len() != self.generators.len()
+        {
+            return false;
+        }
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        self.generators.commit_unblinded(&proof.combined_row)
+            == C::msm_unchecked(&commitment.rows, &left)
+            && inner_product(&proof.combined_row, &right) == value
+    }
+
+    /// Opening whose last step is an inner product argument, of size O(log N)
+    /// on top of the commitment
+    pub fn open_with_ipa(
+        &self,
+        poly: &DenseMultilinearExtension<C::ScalarField>,
+        point: &[C::ScalarField],
+        commitment: &HyraxCommitment<C>,
+        transcript: &mut Transcript,
+    ) -> (C::ScalarField, InnerProductProof<C>) {
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        let combined_row = self.combine_rows(poly, &left);
+        let value = inner_product(&combined_row, &right);
+        let u = self.bind_claim(commitment, point, value, transcript);
+        let proof = ipa::prove(
+            self.generators.generators(),
+            u,
+            &combined_row,
+            &right,
+            transcript,
+        );
+        (value, proof)
+    }
+
+    pub fn verify_with_ipa(
+        &self,
+        commitment: &HyraxCommitment<C>,
+        point: &[C::ScalarField],
+        value: C::ScalarField,
+        proof: &InnerProductProof<C>,
+        transcript: &mut Transcript,
+    ) -> bool {
+        if point.len() != self.num_vars
+            || commitment.rows.len() != 1 << (self.num_vars - column_vars(self.num_vars))
+        {
+            return false;
+        }
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        let u = self.bind_claim(commitment, point, value, transcript);
+        // commitment to L^T M, plus the claimed inner product
+        let p = C::msm_unchecked(&commitment.rows, &left) + u * value;
+        ipa::verify(
+            self.generators.generators(),
+            u,
+            p,
+            &right,
+            proof,
+            transcript,
+        )
+    }
+
+    // L^T M
+    fn combine_rows(
+        &self,
+        poly: &DenseMultilinearExtension<C::ScalarField>,
+        left: &[C::ScalarField],
+    ) -> Vec<C::ScalarField> {
+        let n = self.generators.len();
+        combine_rows(poly.evaluations.chunks(n), left, n)
+    }
+
+    fn bind_claim(
+        &self,
+        commitment: &HyraxCommitment<C>,
+        point: &[C::ScalarField],
+        value: C::ScalarField,
+        transcript: &mut Transcript,
+    ) -> C::Affine {
+        transcript.append_serializable(b"hyrax_commitment", commitment);
+        transcript.append_serializable(b"hyrax_point", point);
+        transcript.append_field(b"hyrax_value", &value);
+        let xi: C::ScalarField = transcript.challenge_field(b"hyrax_xi");
+        (self.u * xi).into_affine()
+    }
+}
+
+impl<C: CurveGroup> PolynomialCommitment<C::ScalarField> for Hyrax<C> {
+    type Polynomial = DenseMultilinearExtension<C::ScalarField>;
+    type Point = [C::ScalarField];
+    type Commitment = HyraxCommitment<C>;
+    type ProverData = HyraxCommitment<C>;
+    type Proof = InnerProductProof<C>;
+    type BatchProof = Vec<InnerProductProof<C>>;
+
+    fn setup<R: RngCore>(size: usize, _rng: &mut R) -> Self {
+        Hyrax::new(size, b"hyrax")
+    }
+
+    fn commit(&self, poly: &Self::Polynomial) -> (HyraxCommitment<C>, HyraxCommitment<C>) {
+        let commitment = Hyrax::commit(self, poly);
+        (commitment.clone(), commitment)
+    }
+
+    fn open(
+        &self,
+        poly: &Self::Polynomial,
+        data: &HyraxCommitment<C>,
+        point: &[C::ScalarField],
+        transcript: &mut Transcript,
+    ) -> (C::ScalarField, Self::Proof) {
+        self.open_with_ipa(poly, point, data, transcript)
+    }
+
+    fn verify(
+        &self,
+        commitment: &HyraxCommitment<C>,
+        point: &[C::ScalarField],
+        value: C::ScalarField,
+        proof: &Self::Proof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        self.verify_with_ipa(commitment, point, value, proof, transcript)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &HyraxCommitment<C>)],
+        points: &[&[C::ScalarField]],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<C::ScalarField>>, Self::BatchProof) {
+        open_separately(self, polys, points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[HyraxCommitment<C>],
+        points: &[&[C::ScalarField]],
+        values: &[Vec<C::ScalarField>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        verify_separately(self, commitments, points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_poly::MultilinearExtension;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    fn setup(num_vars: usize) -> (Hyrax<G1Projective>, DenseMultilinearExtension<Fr>, Vec<Fr>) {
+        let mut rng = test_rng();
+        let poly = DenseMultilinearExtension::rand(num_vars, &mut rng);
+        let point = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        (Hyrax::new(num_vars, b"hyrax-test"), poly, point)
+    }
+
+    #[test]
+    fn test_open() {
+        for num_vars in [1, 4, 5] {
+            let (hyrax, poly, point) = setup(num_vars);
+            let commitment = hyrax.commit(&poly);
+            assert_eq!(commitment.rows.len(), 1 << (num_vars / 2));
+
+            let (value, proof) = hyrax.open(&poly, &point);
+            assert_eq!(Some(value), poly.evaluate(&point));
+            assert!(hyrax.verify(&commitment, &point, value, &proof));
+            assert!(!hyrax.verify(&commitment, &point, value + Fr::ONE, &proof));
+            let mut wrong = proof.clone();
+            wrong.combined_row[0] += Fr::ONE;
+            assert!(!hyrax.verify(&commitment, &point, value, &wrong));
+        }
+    }
+
+    #[test]
+    fn test_open_with_ipa() {
+        let (hyrax, poly, point) = setup(6);
+        let commitment = hyrax.commit(&poly);
+        let (value, proof) =
+            hyrax.open_with_ipa(&poly, &point, &commitment, &mut Transcript::new(b"hyrax"));
+        assert_eq!(Some(value), poly.evaluate(&point));
+        assert!(hyrax.verify_with_ipa(
+            &commitment,
+            &point,
+            value,
+            &proof,
+            &mut Transcript::new(b"hyrax")
+        ));
+        assert!(!hyrax.verify_with_ipa(
+            &commitment,
+            &point,
+            value + Fr::ONE,
+            &proof,
+            &mut Transcript::new(b"hyrax")
+        ));
+
+        let mut changed = poly.clone();
+        changed.evaluations[7] += Fr::ONE;
+        let other = hyrax.commit(&changed);
+        assert!(!hyrax.verify_with_ipa(
+            &other,
+            &point,
+            value,
+            &proof,
+            &mut Transcript::new(b"hyrax")
+        ));
+    }
+
+    #[test]
+    fn test_commitment_is_homomorphic() {
+        let (hyrax, f, point) = setup(4);
+        let g = DenseMultilinearExtension::from_evaluations_vec(
+            4,
+            f.evaluations.iter().map(|e| e.square()).collect(),
+        );
+        let (cf, cg) = (hyrax.commit(&f), hyrax.commit(&g));
+        let sum = hyrax.commit(&(&f + &g));
+        for ((a, b), c) in cf.rows.iter().zip(&cg.rows).zip(&sum.rows) {
+            assert_eq!((*a + b).into_affine(), *c);
+        }
+        let (value, proof) = hyrax.open(&(&f + &g), &point);
+        assert!(hyrax.verify(&sum, &point, value, &proof));
+    }
+}
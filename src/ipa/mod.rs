@@ -0,0 +1,287 @@
+//! Inner product argument of Bulletproofs (Bünz et al. 2018), in the version
+//! of Thaler's Chapter 14.4 with a public vector b.
+//!
+//! Given P = <a, G> + <a, b> U, the prover shows it knows a. Every round
+//! halves the vectors: the prover sends L = <a_lo, G_hi> + <a_lo, b_hi> U and
+//! R = <a_hi, G_lo> + <a_hi, b_lo> U, and for the challenge x both parties fold
+//!
+//!   a' = x a_lo + x^-1 a_hi,  b' = x^-1 b_lo + x b_hi,  G' = x^-1 G_lo + x G_hi
+//!   P' = x^2 L + P + x^-2 R
+//!
+//! until a single element is left, which the prover sends in the clear. The
+//! statement (P, b, G and U) is absorbed into the transcript before the first
+//! challenge, so the rounds are bound to it. The argument is not zero
+//! knowledge.
+pub mod pcs;
+
+pub use crate::multilinear::inner_product;
+pub use pcs::IpaPcs;
+
+use crate::transcript::Transcript;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct InnerProductProof<C: CurveGroup> {
+    lefts: Vec<C::Affine>,
+    rights: Vec<C::Affine>,
+    a: C::ScalarField,
+}
+
+/// Proves knowledge of a such that the commitment is <a, G> + <a, b> U. The
+/// length of a must be a power of 2, at most the number of generators.
+pub fn prove<C: CurveGroup>(
+    generators: &[C::Affine],
+    u: C::Affine,
+    a: &[C::ScalarField],
+    b: &[C::ScalarField],
+    transcript: &mut Transcript,
+) -> InnerProductProof<C> {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "length must be a power of 2");
+    assert_eq!(n, b.len(), "vectors of different length");
+    assert!(n <= generators.len(), "more values than generators");
+
+    let commitment = C::msm_unchecked(&generators[..n], a) + u * inner_product(a, b);
+    bind_statement(&generators[..n], &u, &commitment, b, transcript);
+
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    let mut g: Vec<C> = generators[..n].iter().map(|g| g.into_group()).collect();
+    let mut lefts = vec![];
+    let mut rights = vec![];
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(half);
+        let (b_lo, b_hi) = b.split_at(half);
+        let (g_lo, g_hi) = g.split_at(half);
+
+        let left = msm::<C>(g_hi, a_lo) + u * inner_product(a_lo, b_hi);
+        let right = msm::<C>(g_lo, a_hi) + u * inner_product(a_hi, b_lo);
+        let (left, right) = (left.into_affine(), right.into_affine());
+        let x = round_challenge::<C>(&left, &right, transcript);
+        let x_inv = x.inverse().unwrap();
+
+        a = fold(a_lo, a_hi, x, x_inv);
+        b = fold(b_lo, b_hi, x_inv, x);
+        g = fold(g_lo, g_hi, x_inv, x);
+        lefts.push(left);
+        rights.push(right);
+    }
+
+    InnerProductProof {
+        lefts,
+        rights,
+        a: a[0],
+    }
+}
+
+/// Verifies the proof for the commitment P = <a, G> + <a, b> U
+pub fn verify<C: CurveGroup>(
+    generators: &[C::Affine],
+    u: C::Affine,
+    commitment: C,
+    b: &[C::ScalarField],
+    proof: &InnerProductProof<C>,
+    transcript: &mut Transcript,
+) -> bool {
+    let n = b.len();
+    if !n.is_power_of_two()
+        || n > generators.len()
+        || proof.lefts.len() != n.trailing_zeros() as usize
+        || proof.rights.len() != proof.lefts.len()
+    {
+        return false;
+    }
+    bind_statement(&generators[..n], &u, &commitment, b, transcript);
+
+    let mut b = b.to_vec();
+    let mut g: Vec<C> = generators[..n].iter().map(|g| g.into_group()).collect();
+    let mut p = commitment;
+    for (left, right) in proof.lefts.iter().zip(proof.rights.iter()) {
+        let x = round_challenge::<C>(left, right, transcript);
+        let x_inv = match x.inverse() {
+            Some(x_inv) => x_inv,
+            None => return false,
+        };
+        let half = b.len() / 2;
+        b = fold(&b[..half], &b[half..], x_inv, x);
+        g = fold(&g[..half], &g[half..], x_inv, x);
+        p += *left * x.square() + *right * x_inv.square();
+    }
+
+    p == g[0] * proof.a + u * (proof.a * b[0])
+}
+
+// x * lo + y * hi, entrywise
+fn fold<T, F>(lo: &[T], hi: &[T], x: F, y: F) -> Vec<T>
+where
+    T: Copy + std::ops::Mul<F, Output = T> + std::ops::Add<Output = T>,
+    F: Copy,
+{
+    lo.iter().zip(hi).map(|(l, h)| *l * x + *h * y).collect()
+}
+
+fn msm<C: CurveGroup>(bases: &[C], scalars: &[C::ScalarField]) -> C {
+    C::msm_unchecked(&C::normalize_batch(bases), scalars)
+}
+
+fn bind_statement<C: CurveGroup>(
+    generators: &[C::Affine],
+    u: &C::Affine,
+    commitment: &C,
+    b: &[C::ScalarField],
+    transcript: &mut Transcript,
+) {
+    transcript.append_serializable(b"ipa_generators", generators);
+    transcript.append_serializable(b"ipa_u", u);
+    transcript.append_serializable(b"ipa_commitment", &commitment.into_affine());
+    transcript.append_serializable(b"ipa_b", b);
+}
+
+fn round_challenge<C: CurveGroup>(
+    left: &C::Affine,
+    right: &C::Affine,
+    transcript: &mut Transcript,
+) -> C::ScalarField {
+    transcript.append_serializable(b"ipa_left", left);
+    transcript.append_serializable(b"ipa_right", right);
+    transcript.challenge_field(b"ipa_x")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::{hash_to_curve, PedersenGenerators};
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    fn setup(
+        n: usize,
+    ) -> (
+        PedersenGenerators<G1Projective>,
+        Vec<Fr>,
+        Vec<Fr>,
+        G1Projective,
+    ) {
+        let mut rng = test_rng();
+        let gens = PedersenGenerators::new(n, b"ipa-test");
+        let a: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let b: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let u = hash_to_curve::<G1Projective>(b"ipa-test-u", 0);
+        let commitment = gens.commit_unblinded(&a) + u * inner_product(&a, &b);
+        (gens, a, b, commitment)
+    }
+
+    #[test]
+    fn test_inner_product_argument() {
+        let u = hash_to_curve::<G1Projective>(b"ipa-test-u", 0);
+        for n in [1, 2, 8, 32] {
+            let (gens, a, b, commitment) = setup(n);
+            let proof =
+                prove::<G1Projective>(gens.generators(), u, &a, &b, &mut Transcript::new(b"ipa"));
+            assert_eq!(proof.lefts.len(), n.trailing_zeros() as usize);
+            assert!(verify(
+                gens.generators(),
+                u,
+                commitment,
+                &b,
+                &proof,
+                &mut Transcript::new(b"ipa")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_wrong_statement() {
+        let u = hash_to_curve::<G1Projective>(b"ipa-test-u", 0);
+        let (gens, a, b, commitment) = setup(16);
+        let proof =
+            prove::<G1Projective>(gens.generators(), u, &a, &b, &mut Transcript::new(b"ipa"));
+
+        // the commitment claims another inner product
+        let wrong = commitment + u;
+        assert!(!verify(
+            gens.generators(),
+            u,
+            wrong,
+            &b,
+            &proof,
+            &mut Transcript::new(b"ipa")
+        ));
+        let mut other_b = b.clone();
+        other_b[3] += Fr::from(1);
+        assert!(!verify(
+            gens.generators(),
+            u,
+            commitment,
+            &other_b,
+            &proof,
+            &mut Transcript::new(b"ipa")
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.a += Fr::from(1);
+        assert!(!verify(
+            gens.generators(),
+            u,
+            commitment,
+            &b,
+            &tampered,
+            &mut Transcript::new(b"ipa")
+        ));
+        let mut tampered = proof;
+        tampered.lefts.swap(0, 1);
+        assert!(!verify(
+            gens.generators(),
+            u,
+            commitment,
+            &b,
+            &tampered,
+            &mut Transcript::new(b"ipa")
+        ));
+    }
+
+    #[test]
+    fn test_statement_is_bound() {
+        // the challenges depend on the generators and U, not only on the rounds
+        let u = hash_to_curve::<G1Projective>(b"ipa-test-u", 0);
+        let (gens, a, b, commitment) = setup(8);
+        let proof =
+            prove::<G1Projective>(gens.generators(), u, &a, &b, &mut Transcript::new(b"ipa"));
+        let other = PedersenGenerators::<G1Projective>::new(8, b"ipa-other");
+        assert!(!verify(
+            other.generators(),
+            u,
+            commitment,
+            &b,
+            &proof,
+            &mut Transcript::new(b"ipa")
+        ));
+        let other_u = hash_to_curve::<G1Projective>(b"ipa-test-u", 1);
+        assert!(!verify(
+            gens.generators(),
+            other_u,
+            commitment,
+            &b,
+            &proof,
+            &mut Transcript::new(b"ipa")
+        ));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let u = hash_to_curve::<G1Projective>(b"ipa-test-u", 0);
+        let (gens, a, b, _) = setup(4);
+        let proof =
+            prove::<G1Projective>(gens.generators(), u, &a, &b, &mut Transcript::new(b"ipa"));
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            InnerProductProof::deserialize_compressed(&bytes[..]).unwrap(),
+            proof
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Polynomial commitment from the inner product argument (Thaler 14.4). The
+//! coefficients are committed with Pedersen, and since f(z) = <c, (1, z, z^2, ...)>,
+//! an evaluation is an inner product with a public vector. No trusted setup
+//! is needed, but the verifier works in linear time.
+use super::{prove, verify as verify_ipa, InnerProductProof};
+use crate::pcs::{open_separately, verify_separately, PolynomialCommitment};
+use crate::pedersen::{hash_to_curve, PedersenGenerators};
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Polynomial;
+use ark_std::rand::RngCore;
+
+/// Commitment scheme for polynomials of degree < degree_bound, a power of 2
+#[derive(Clone, Debug)]
+pub struct IpaPcs<C: CurveGroup> {
+    generators: PedersenGenerators<C>,
+    u: C::Affine,
+}
+
+impl<C: CurveGroup> IpaPcs<C> {
+    pub fn new(degree_bound: usize, label: &[u8]) -> Self {
+        assert!(
+            degree_bound.is_power_of_two(),
+            "degree bound must be a power of 2"
+        );
+        IpaPcs {
+            generators: PedersenGenerators::new(degree_bound, label),
+            u: hash_to_curve::<C>(label, u64::MAX),
+        }
+    }
+
+    pub fn degree_bound(&self) -> usize {
+        self.generators.len()
+    }
+
+    pub fn commit(&self, poly: &DensePolynomial<C::ScalarField>) -> C::Affine {
+        self.generators.commit_unblinded(&poly.coeffs).into_affine()
+    }
+
+    /// Opens the polynomial at z, returning f(z) and the proof
+    pub fn open(
+        &self,
+        poly: &DensePolynomial<C::ScalarField>,
+        z: C::ScalarField,
+        transcript: &mut Transcript,
+    ) -> (C::ScalarField, InnerProductProof<C>) {
+        let value = poly.evaluate(&z);
+        let u = self.bind_claim(&self.commit(poly), z, value, transcript);
+        let mut coeffs = poly.coeffs.clone();
+        coeffs.resize(self.degree_bound(), C::ScalarField::ZERO);
+        let proof = prove(
+            self.generators.generators(),
+            u,
+            &coeffs,
+            &self.powers(z),
+            transcript,
+        );
+        (value, proof)
+    }
+
+    pub fn verify(
+        &self,
+        commitment: &C::Affine,
+        z: C::ScalarField,
+        value: C::ScalarField,
+        proof: &InnerProductProof<C>,
+        transcript: &mut Transcript,
+    ) -> bool {
+        let u = self.bind_claim(commitment, z, value, transcript);
+        // P = <c, G> + f(z) U
+        let p = *commitment + u * value;
+        verify_ipa(
+            self.generators.generators(),
+            u,
+            p,
+            &self.powers(z),
+            proof,
+            transcript,
+        )
+    }
+
+    // (1, z, ..., z^{d-1})
+    fn powers(&self, z: C::ScalarField) -> Vec<C::ScalarField> {
+        std::iter::successors(Some(C::ScalarField::ONE), |p| Some(*p * z))
+            .take(self.degree_bound())
+            .collect()
+    }
+
+    // the claimed value is committed on a fresh multiple of U, so the prover
+    // cannot move part of it into the commitment
+    fn bind_claim(
+        &self,
+        commitment: &C::Affine,
+        z: C::ScalarField,
+        value: C::ScalarField,
+        transcript: &mut Transcript,
+    ) -> C::Affine {
+        transcript.append_serializable(b"ipa_pcs_commitment", commitment);
+        transcript.append_field(b"ipa_pcs_point", &z);
+        transcript.append_field(b"ipa_pcs_value", &value);
+        let xi: C::ScalarField = transcript.challenge_field(b"ipa_pcs_xi");
+        (self.u * xi).into_affine()
+    }
+}
+
+impl<C: CurveGroup> PolynomialCommitment<C::ScalarField> for IpaPcs<C> {
+    type Polynomial = DensePolynomial<C::ScalarField>;
+    type Point = C::ScalarField;
+    type Commitment = C::Affine;
+    type ProverData = ();
+    type Proof = InnerProductProof<C>;
+    type BatchProof = Vec<InnerProductProof<C>>;
+
+    fn setup<R: RngCore>(size: usize, _rng: &mut R) -> Self {
+        IpaPcs::new(size, b"ipa-pcs")
+    }
+
+    fn commit(&self, poly: &Self::Polynomial) -> (C::Affine, ()) {
+        (IpaPcs::commit(self, poly), ())
+    }
+
+    fn open(
+        &self,
+        poly: &Self::Polynomial,
+        _data: &(),
+        point: &C::ScalarField,
+        transcript: &mut Transcript,
+    ) -> (C::ScalarField, Self::Proof) {
+        IpaPcs::open(self, poly, *point, transcript)
+    }
+
+    fn verify(
+        &self,
+        commitment: &C::Affine,
+        point: &C::ScalarField,
+        value: C::ScalarField,
+        proof: &Self::Proof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        IpaPcs::verify(self, commitment, *point, value, proof, transcript)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &())],
+        points: &[&C::ScalarField],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<C::ScalarField>>, Self::BatchProof) {
+        open_separately(self, polys, points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[C::Affine],
+        points: &[&C::ScalarField],
+        values: &[Vec<C::ScalarField>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        verify_separately(self, commitments, points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_poly::DenseUVPolynomial;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    #[test]
+    fn test_open() {
+        let mut rng = test_rng();
+        let pcs = IpaPcs::<G1Projective>::new(16, b"ipa-pcs-test");
+        for degree in [0, 7, 15] {
+            let poly = DensePolynomial::rand(degree, &mut rng);
+            let commitment = pcs.commit(&poly);
+            let z = Fr::rand(&mut rng);
+
+            let (value, proof) = pcs.open(&poly, z, &mut Transcript::new(b"pcs"));
+            assert_eq!(value, poly.evaluate(&z));
+            assert!(pcs.verify(&commitment, z, value, &proof, &mut Transcript::new(b"pcs")));
+            assert!(!pcs.verify(
+                &commitment,
+                z,
+                value + Fr::ONE,
+                &proof,
+                &mut Transcript::new(b"pcs")
+            ));
+            assert!(!pcs.verify(
+                &commitment,
+                z + Fr::ONE,
+                value,
+                &proof,
+                &mut Transcript::new(b"pcs")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_wrong_commitment() {
+        let mut rng = test_rng();
+        let pcs = IpaPcs::<G1Projective>::new(8, b"ipa-pcs-test");
+        let poly = DensePolynomial::rand(7, &mut rng);
+        let other = pcs.commit(&DensePolynomial::rand(7, &mut rng));
+        let (value, proof) = pcs.open(&poly, Fr::from(2), &mut Transcript::new(b"pcs"));
+        assert!(!pcs.verify(
+            &other,
+            Fr::from(2),
+            value,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! KZG polynomial commitments (Kate, Zaverucha, Goldberg 2010).
+//!
+//! The structured reference string holds [τ^i]_1 and [τ^i]_2 for a secret τ.
+//! A polynomial f is committed as [f(τ)]_1, and f(z) = y is proven with the
+//! commitment to the quotient q(X) = (f(X) - y) / (X - z), checked by the
+//! pairing equation e(C - [y]_1, [1]_2) = e(π, [τ - z]_2).
+pub mod multilinear;
+
+pub use multilinear::{MultilinearKzgProof, MultilinearSrs};
+
+use crate::pcs::PolynomialCommitment;
+use crate::transcript::Transcript;
+use crate::univariate::{
+    divide_by_linear, divide_by_vanishing, lagrange_interpolate, vanishing_polynomial,
+};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{Field, UniformRand, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+/// Structured reference string, [τ^i]_1 for i <= max_degree and [τ^i]_2 for
+/// i <= max_points, the largest number of points opened at once
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Srs<E: Pairing> {
+    powers_of_g: Vec<E::G1Affine>,
+    powers_of_h: Vec<E::G2Affine>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgCommitment<E: Pairing>(pub E::G1Affine);
+
+/// Commitment to the quotient by the vanishing polynomial of the opened points
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KzgProof<E: Pairing>(pub E::G1Affine);
+
+impl<E: Pairing> Srs<E> {
+    /// Generates the SRS from a random τ, which is dropped at the end. Only
+    /// meant for tests: whoever knows τ can open commitments to any value.
+    pub fn setup<R: RngCore>(max_degree: usize, max_points: usize, rng: &mut R) -> Self {
+        let tau = E::ScalarField::rand(rng);
+        Self::from_secret(tau, max_degree, max_points)
+    }
+
+    fn from_secret(tau: E::ScalarField, max_degree: usize, max_points: usize) -> Self {
+        let powers: Vec<E::ScalarField> =
+            std::iter::successors(Some(E::ScalarField::ONE), |p| Some(*p * tau))
+                .take(max_degree.max(max_points) + 1)
+                .collect();
+        let g = E::G1::generator();
+        let h = E::G2::generator();
+        let powers_of_g: Vec<E::G1> = powers[..=max_degree].iter().map(|p| g * p).collect();
+        let powers_of_h: Vec<E::G2> = powers[..=max_points].iter().map(|p| h * p).collect();
+        Srs {
+            powers_of_g: E::G1::normalize_batch(&powers_of_g),
+            powers_of_h: E::G2::normalize_batch(&powers_of_h),
+        }
+    }
+
+    /// The SRS for polynomials of degree at most max_degree opened at up to
+    /// max_points points. A verifier of single point openings only needs
+    /// `trim(0, 1)`, that is [1]_1, [1]_2 and [τ]_2.
+    pub fn trim(&self, max_degree: usize, max_points: usize) -> Self {
+        assert!(
+            max_degree <= self.max_degree() && max_points < self.powers_of_h.len(),
+            "SRS too small"
+        );
+        Srs {
+            powers_of_g: self.powers_of_g[..=max_degree].to_vec(),
+            powers_of_h: self.powers_of_h[..=max_points].to_vec(),
+        }
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+
+    pub fn commit(&self, poly: &DensePolynomial<E::ScalarField>) -> KzgCommitment<E> {
+        KzgCommitment(self.commit_g1(&poly.coeffs).into_affine())
+    }
+
+    /// Opens the polynomial at z, returning f(z) and the proof
+    pub fn open(
+        &self,
+        poly: &DensePolynomial<E::ScalarField>,
+        z: E::ScalarField,
+    ) -> (E::ScalarField, KzgProof<E>) {
+        let (quotient, value) = divide_by_linear(poly, z);
+        (
+            value,
+            KzgProof(self.commit_g1(&quotient.coeffs).into_affine()),
+        )
+    }
+
+    pub fn verify(
+        &self,
+        commitment: &KzgCommitment<E>,
+        z: E::ScalarField,
+        value: E::ScalarField,
+        proof: &KzgProof<E>,
+    ) -> bool {
+        // e(C - [y]_1, [1]_2) = e(π, [τ]_2 - [z]_2)
+        let g = E::G1::generator();
+        let h = self.powers_of_h[0];
+        let lhs = E::pairing(commitment.0.into_group() - g * value, h);
+        let rhs = E::pairing(proof.0, self.powers_of_h[1].into_group() - h * z);
+        lhs == rhs
+    }
+
+    /// Opens the polynomial at several points with a single group element,
+    /// the commitment to (f(X) - I(X)) / Z_S(X), where I interpolates f over
+    /// the points S and Z_S vanishes on them
+    pub fn open_multi(
+        &self,
+        poly: &DensePolynomial<E::ScalarField>,
+        points: &[E::ScalarField],
+    ) -> (Vec<E::ScalarField>, KzgProof<E>) {
+        assert!(
+            points.len() < self.powers_of_h.len(),
+            "too many points for the SRS"
+        );
+        let values: Vec<E::ScalarField> = points.iter().map(|z| poly.evaluate(z)).collect();
+        let interpolation = interpolation(points, &values);
+        let (quotient, remainder) = divide_by_vanishing(&(poly - &interpolation), points);
+        debug_assert!(remainder.is_zero());
+        (
+            values,
+            KzgProof(self.commit_g1(&quotient.coeffs).into_affine()),
+        )
+    }
+
+    pub fn verify_multi(
+        &self,
+        commitment: &KzgCommitment<E>,
+        points: &[E::ScalarField],
+        values: &[E::ScalarField],
+        proof: &KzgProof<E>,
+    ) -> bool {
+        if points.len() != values.len() || points.len() >= self.powers_of_h.len() {
+            return false;
+        }
+        // e(C - [I(τ)]_1, [1]_2) = e(π, [Z_S(τ)]_2)
+        let interpolation = self.commit_g1(&interpolation(points, values).coeffs);
+        let vanishing = vanishing_polynomial(points);
+        let vanishing = E::G2::msm_unchecked(
+            &self.powers_of_h[..vanishing.coeffs.len()],
+            &vanishing.coeffs,
+        );
+        let lhs = E::pairing(
+            commitment.0.into_group() - interpolation,
+            self.powers_of_h[0],
+        );
+        let rhs = E::pairing(proof.0, vanishing);
+        lhs == rhs
+    }
+
+    /// Opens every polynomial at every point, values[i][j] being f_i(z_j), by
+    /// opening the random linear combination sum_i γ^i f_i
+    pub fn batch_open(
+        &self,
+        polys: &[&DensePolynomial<E::ScalarField>],
+        points: &[E::ScalarField],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<E::ScalarField>>, KzgProof<E>) {
+        let values: Vec<Vec<E::ScalarField>> = polys
+            .iter()
+            .map(|poly| points.iter().map(|z| poly.evaluate(z)).collect())
+            .collect();
+        let commitments: Vec<KzgCommitment<E>> =
+            polys.iter().map(|poly| self.commit(poly)).collect();
+        let gamma = absorb_claims(&commitments, points, &values, transcript);
+
+        let mut combined = DensePolynomial::from_coefficients_vec(vec![]);
+        let mut power = E::ScalarField::ONE;
+        for poly in polys {
+            combined += (power, *poly);
+            power *= gamma;
+        }
+        let (_, proof) = self.open_multi(&combined, points);
+        (values, proof)
+    }
+
+    pub fn batch_verify(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        points: &[E::ScalarField],
+        values: &[Vec<E::ScalarField>],
+        proof: &KzgProof<E>,
+        transcript: &mut Transcript,
+    ) -> bool {
+        if commitments.len() != values.len() || values.iter().any(|v| v.len() != points.len()) {
+            return false;
+        }
+        let gamma = absorb_claims(commitments, points, values, transcript);
+
+        // the commitment and the evaluations of the combination follow by linearity
+        let mut combined = E::G1::default();
+        let mut combined_values = vec![E::ScalarField::ZERO; points.len()];
+        let mut power = E::ScalarField::ONE;
+        for (commitment, evals) in commitments.iter().zip(values) {
+            combined += commitment.0 * power;
+            for (c, v) in combined_values.iter_mut().zip(evals) {
+                *c += power * v;
+            }
+            power *= gamma;
+        }
+        self.verify_multi(
+            &KzgCommitment(combined.into_affine()),
+            points,
+            &combined_values,
+            proof,
+        )
+    }
+
+    // [p(τ)]_1 for the polynomial with the given coefficients
+    fn commit_g1(&self, coeffs: &[E::ScalarField]) -> E::G1 {
+        assert!(
+            coeffs.len() <= self.powers_of_g.len(),
+            "polynomial exceeds the degree of the SRS"
+        );
+        E::G1::msm_unchecked(&self.powers_of_g[..coeffs.len()], coeffs)
+    }
+}
+
+fn interpolation<F: Field>(points: &[F], values: &[F]) -> DensePolynomial<F> {
+    let pairs: Vec<(F, F)> = points.iter().copied().zip(values.iter().copied()).collect();
+    lagrange_interpolate(&pairs)
+}
+
+// binds the claims to the transcript and draws the batching challenge
+fn absorb_claims<E: Pairing>(
+    commitments: &[KzgCommitment<E>],
+    points: &[E::ScalarField],
+    values: &[Vec<E::ScalarField>],
+    transcript: &mut Transcript,
+) -> E::ScalarField {
+    transcript.append_serializable(b"kzg_commitments", commitments);
+    transcript.append_serializable(b"kzg_points", points);
+    transcript.append_serializable(b"kzg_values", values);
+    transcript.challenge_field(b"kzg_gamma")
+}
+
+impl<E: Pairing> PolynomialCommitment<E::ScalarField> for Srs<E> {
+    type Polynomial = DensePolynomial<E::ScalarField>;
+    type Point = E::ScalarField;
+    type Commitment = KzgCommitment<E>;
+    type ProverData = ();
+    type Proof = KzgProof<E>;
+    type BatchProof = KzgProof<E>;
+
+    fn setup<R: RngCore>(size: usize, rng: &mut R) -> Self {
+        Srs::setup(size - 1, size, rng)
+    }
+
+    fn commit(&self, poly: &Self::Polynomial) -> (KzgCommitment<E>, ()) {
+        (Srs::commit(self, poly), ())
+    }
+
+    fn open(
+        &self,
+        poly: &Self::Polynomial,
+        _data: &(),
+        point: &E::ScalarField,
+        _transcript: &mut Transcript,
+    ) -> (E::ScalarField, KzgProof<E>) {
+        Srs::open(self, poly, *point)
+    }
+
+    fn verify(
+        &self,
+        commitment: &KzgCommitment<E>,
+        point: &E::ScalarField,
+        value: E::ScalarField,
+        proof: &KzgProof<E>,
+        _transcript: &mut Transcript,
+    ) -> bool {
+        Srs::verify(self, commitment, *point, value, proof)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &())],
+        points: &[&E::ScalarField],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<E::ScalarField>>, KzgProof<E>) {
+        let polys: Vec<_> = polys.iter().map(|(poly, _)| *poly).collect();
+        let points: Vec<_> = points.iter().map(|z| **z).collect();
+        Srs::batch_open(self, &polys, &points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        points: &[&E::ScalarField],
+        values: &[Vec<E::ScalarField>],
+        proof: &KzgProof<E>,
+        transcript: &mut Transcript,
+    ) -> bool {
+        let points: Vec<_> = points.iter().map(|z| **z).collect();
+        Srs::batch_verify(self, commitments, &points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    fn setup() -> Srs<Bls12_381> {
+        Srs::setup(31, 4, &mut test_rng())
+    }
+
+    #[test]
+    fn test_open() {
+        let mut rng = test_rng();
+        let srs = setup();
+        let poly = DensePolynomial::rand(31, &mut rng);
+        let commitment = srs.commit(&poly);
+        let z = Fr::rand(&mut rng);
+
+        let (value, proof) = srs.open(&poly, z);
+        assert_eq!(value, poly.evaluate(&z));
+        assert!(srs.verify(&commitment, z, value, &proof));
+        assert!(!srs.verify(&commitment, z, value + Fr::ONE, &proof));
+        assert!(!srs.verify(&commitment, z + Fr::ONE, value, &proof));
+    }
+
+    #[test]
+    fn test_commitment_is_homomorphic() {
+        let mut rng = test_rng();
+        let srs = setup();
+        let f = DensePolynomial::rand(10, &mut rng);
+        let g = DensePolynomial::rand(20, &mut rng);
+        let sum = srs.commit(&f).0 + srs.commit(&g).0;
+        assert_eq!(srs.commit(&(&f + &g)).0, sum.into_affine());
+    }
+
+    #[test]
+    fn test_setup_from_secret() {
+        let tau = Fr::from(5);
+        let srs = Srs::<Bls12_381>::from_secret(tau, 3, 1);
+        let poly =
+            DensePolynomial::from_coefficients_vec(vec![Fr::from(1), Fr::from(2), Fr::from(3)]);
+        let expected = <Bls12_381 as Pairing>::G1::generator() * poly.evaluate(&tau);
+        assert_eq!(srs.commit(&poly).0, expected.into_affine());
+        assert_eq!(srs.max_degree(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the degree")]
+    fn test_degree_too_high() {
+        let srs = setup();
+        srs.commit(&DensePolynomial::rand(32, &mut test_rng()));
+    }
+
+    #[test]
+    fn test_open_multi() {
+        let mut rng = test_rng();
+        let srs = setup();
+        let poly = DensePolynomial::rand(25, &mut rng);
+        let commitment = srs.commit(&poly);
+        let points: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (values, proof) = srs.open_multi(&poly, &points);
+        assert!(srs.verify_multi(&commitment, &points, &values, &proof));
+        let mut wrong = values.clone();
+        wrong[3] += Fr::ONE;
+        assert!(!srs.verify_multi(&commitment, &points, &wrong, &proof));
+        assert!(!srs.verify_multi(&commitment, &points[..3], &values[..3], &proof));
+
+        // a single point is the usual opening
+        let (value, proof) = srs.open(&poly, points[0]);
+        assert!(srs.verify_multi(&commitment, &points[..1], &[value], &proof));
+    }
+
+    #[test]
+    fn test_batch_open() {
+        let mut rng = test_rng();
+        let srs = setup();
+        let polys: Vec<DensePolynomial<Fr>> = (0..3)
+            .map(|d| DensePolynomial::rand(10 * d + 1, &mut rng))
+            .collect();
+        let refs: Vec<_> = polys.iter().collect();
+        let commitments: Vec<_> = polys.iter().map(|p| srs.commit(p)).collect();
+        let points: Vec<Fr> = (0..2).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (values, proof) = srs.batch_open(&refs, &points, &mut Transcript::new(b"kzg"));
+        assert_eq!(values[1][0], polys[1].evaluate(&points[0]));
+        assert!(srs.batch_verify(
+            &commitments,
+            &points,
+            &values,
+            &proof,
+            &mut Transcript::new(b"kzg")
+        ));
+
+        let mut wrong = values.clone();
+        wrong[0][1] += Fr::ONE;
+        assert!(!srs.batch_verify(
+            &commitments,
+            &points,
+            &wrong,
+            &proof,
+            &mut Transcript::new(b"kzg")
+        ));
+        assert!(!srs.batch_verify(
+            &commitments[..2],
+            &points,
+            &values[..2],
+            &proof,
+            &mut Transcript::new(b"kzg")
+        ));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = test_rng();
+        let srs = Srs::<Bls12_381>::setup(7, 2, &mut rng);
+        let mut bytes = vec![];
+        srs.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(Srs::deserialize_compressed(&bytes[..]).unwrap(), srs);
+
+        let (_, proof) = srs.open(&DensePolynomial::rand(7, &mut rng), Fr::from(2));
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            KzgProof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap(),
+            proof
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Multilinear KZG commitments (Papamanthou, Shi, Tamassia 2013).
+//!
+//! A multilinear polynomial f in v variables is committed as [f(τ)]_1 for a
+//! secret τ in F^v. Fixing the variables one at a time,
+//!
+//!   f(X) - f(r) = sum_i (X_i - r_i) q_i(X_{i+1}, ..., X_{v-1})
+//!
+//! with q_i(Y) = f(r_0, ..., r_{i-1}, 1, Y) - f(r_0, ..., r_{i-1}, 0, Y), so
+//! the opening at r is made of the v commitments [q_i(τ)]_1, checked by
+//! e(C - [f(r)]_1, [1]_2) = prod_i e([q_i(τ)]_1, [τ_i - r_i]_2).
+use super::KzgCommitment;
+use crate::multilinear::eq_table;
+use crate::pcs::{open_separately, verify_separately, PolynomialCommitment};
+use crate::transcript::Transcript;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::UniformRand;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+/// Structured reference string for polynomials in num_vars variables
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultilinearSrs<E: Pairing> {
+    /// lagrange[i] holds [eq((τ_i, ..., τ_{v-1}), x)]_1 for x in {0,1}^{v-i}
+    lagrange: Vec<Vec<E::G1Affine>>,
+    /// [τ_i]_2 for every variable
+    tau_h: Vec<E::G2Affine>,
+}
+
+/// Commitments to the quotients q_0, ..., q_{v-1}
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultilinearKzgProof<E: Pairing>(pub Vec<E::G1Affine>);
+
+impl<E: Pairing> MultilinearSrs<E> {
+    /// Generates the SRS from a random τ, which is dropped at the end. Only
+    /// meant for tests: whoever knows τ can open commitments to any value.
+    pub fn setup<R: RngCore>(num_vars: usize, rng: &mut R) -> Self {
+        let tau: Vec<E::ScalarField> = (0..num_vars).map(|_| E::ScalarField::rand(rng)).collect();
+        let g = E::G1::generator();
+        let h = E::G2::generator();
+        let lagrange = (0..=num_vars)
+            .map(|i| {
+                let table: Vec<E::G1> = eq_table(&tau[i..]).iter().map(|e| g * e).collect();
+                E::G1::normalize_batch(&table)
+            })
+            .collect();
+        let tau_h: Vec<E::G2> = tau.iter().map(|t| h * t).collect();
+        MultilinearSrs {
+            lagrange,
+            tau_h: E::G2::normalize_batch(&tau_h),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.tau_h.len()
+    }
+
+    pub fn commit(&self, poly: &DenseMultilinearExtension<E::ScalarField>) -> KzgCommitment<E> {
+        assert_eq!(
+            poly.num_vars,
+            self.num_vars(),
+            "polynomial with a different number of variables"
+        );
+        KzgCommitment(E::G1::msm_unchecked(&self.lagrange[0], &poly.evaluations).into_affine())
+    }
+
+    /// Opens the polynomial at the point, returning f(point) and the proof
+    pub fn open(
+        &self,
+        poly: &DenseMultilinearExtension<E::ScalarField>,
+        point: &[E::ScalarField],
+    ) -> (E::ScalarField, MultilinearKzgProof<E>) {
+        assert_eq!(
+            point.len(),
+            self.num_vars(),
+            "point of a different dimension"
+        );
+        let mut table = poly.evaluations.clone();
+        let mut quotients = Vec::with_capacity(point.len());
+        for (i, r) in point.iter().enumerate() {
+            // variable i is the lowest bit of the index of the remaining table
+            let quotient: Vec<E::ScalarField> =
+                table.chunks(2).map(|pair| pair[1] - pair[0]).collect();
+            quotients.push(E::G1::msm_unchecked(&self.lagrange[i + 1], &quotient));
+            table = table
+                .chunks(2)
+                .zip(quotient.iter())
+                .map(|(pair, q)| pair[0] + *r * q)
+                .collect();
+        }
+        (
+            table[0],
+            MultilinearKzgProof(E::G1::normalize_batch(&quotients)),
+        )
+    }
+
+    pub fn verify(
+        &self,
+        commitment: &KzgCommitment<E>,
+        point: &[E::ScalarField],
+        value: E::ScalarField,
+        proof: &MultilinearKzgProof<E>,
+    ) -> bool {
+        if point.len() != self.num_vars() || proof.0.len() != self.num_vars() {
+            return false;
+        }
+        let h = E::G2::generator();
+        let lhs = E::pairing(commitment.0.into_group() - E::G1::generator() * value, h);
+        let shifted: Vec<E::G2Affine> = self
+            .tau_h
+            .iter()
+            .zip(point)
+            .map(|(t, r)| (t.into_group() - h * r).into_affine())
+            .collect();
+        lhs == E::multi_pairing(&proof.0, shifted)
+    }
+}
+
+impl<E: Pairing> PolynomialCommitment<E::ScalarField> for MultilinearSrs<E> {
+    type Polynomial = DenseMultilinearExtension<E::ScalarField>;
+    type Point = [E::ScalarField];
+    type Commitment = KzgCommitment<E>;
+    type ProverData = ();
+    type Proof = MultilinearKzgProof<E>;
+    type BatchProof = Vec<MultilinearKzgProof<E>>;
+
+    fn setup<R: RngCore>(size: usize, rng: &mut R) -> Self {
+        MultilinearSrs::setup(size, rng)
+    }
+
+    fn commit(&self, poly: &Self::Polynomial) -> (KzgCommitment<E>, ()) {
+        (MultilinearSrs::commit(self, poly), ())
+    }
+
+    fn open(
+        &self,
+        poly: &Self::Polynomial,
+        _data: &(),
+        point: &[E::ScalarField],
+        _transcript: &mut Transcript,
+    ) -> (E::ScalarField, Self::Proof) {
+        MultilinearSrs::open(self, poly, point)
+    }
+
+    fn verify(
+        &self,
+        commitment: &KzgCommitment<E>,
+        point: &[E::ScalarField],
+        value: E::ScalarField,
+        proof: &Self::Proof,
+        _transcript: &mut Transcript,
+    ) -> bool {
+        MultilinearSrs::verify(self, commitment, point, value, proof)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &())],
+        points: &[&[E::ScalarField]],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<E::ScalarField>>, Self::BatchProof) {
+        open_separately(self, polys, points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[KzgCommitment<E>],
+        points: &[&[E::ScalarField]],
+        values: &[Vec<E::ScalarField>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        verify_separately(self, commitments, points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pcs::OpeningOracle;
+    use crate::sumcheck::{ProductPolynomial, ProverState, VerifierState};
+    use ark_ff::Field;
+    use ark_poly::MultilinearExtension;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    fn setup(num_vars: usize) -> (MultilinearSrs<Bls12_381>, DenseMultilinearExtension<Fr>) {
+        let mut rng = test_rng();
+        let srs = MultilinearSrs::setup(num_vars, &mut rng);
+        let poly = DenseMultilinearExtension::rand(num_vars, &mut rng);
+        (srs, poly)
+    }
+
+    #[test]
+    fn test_open() {
+        let (srs, poly) = setup(4);
+        let commitment = srs.commit(&poly);
+        let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut test_rng())).collect();
+
+        let (value, proof) = srs.open(&poly, &point);
+        assert_eq!(proof.0.len(), 4);
+        assert_eq!(Some(value), poly.evaluate(&point));
+        assert!(srs.verify(&commitment, &point, value, &proof));
+        assert!(!srs.verify(&commitment, &point, value + Fr::ONE, &proof));
+        let mut other = point.clone();
+        other[2] += Fr::ONE;
+        assert!(!srs.verify(&commitment, &other, value, &proof));
+        assert!(!srs.verify(&commitment, &point[..3], value, &proof));
+    }
+
+    #[test]
+    fn test_open_on_hypercube() {
+        let (srs, poly) = setup(3);
+        let commitment = srs.commit(&poly);
+        let point = crate::multilinear::number_to_domain::<Fr>(5, 3);
+        let (value, proof) = srs.open(&poly, &point);
+        assert_eq!(value, poly.evaluations[5]);
+        assert!(srs.verify(&commitment, &point, value, &proof));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let (srs, poly) = setup(2);
+        let (_, proof) = srs.open(&poly, &[Fr::from(3), Fr::from(4)]);
+        let mut bytes = vec![];
+        srs.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            MultilinearSrs::deserialize_compressed(&bytes[..]).unwrap(),
+            srs
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            MultilinearKzgProof::deserialize_compressed(&bytes[..]).unwrap(),
+            proof
+        );
+    }
+
+    #[test]
+    fn test_sumcheck_with_commitment() {
+        let (srs, poly) = setup(4);
+        // the final evaluation is replaced by an opening of the commitment
+        let oracle = OpeningOracle {
+            pcs: &srs,
+            commitment: srs.commit(&poly),
+            poly: &poly,
+            data: &(),
+        };
+
+        let mut prover =
+            ProverState::new(ProductPolynomial::new(4, vec![poly.evaluations.clone()]));
+        let mut verifier = VerifierState::with_oracle(prover.calculate_sum(), 4, oracle);
+        for _ in 0..4 {
+            let r = verifier.verify_round(prover.calculate_round_poly());
+            prover.update_random_vars(r);
+        }
+        assert_eq!(verifier.get_random_vars(), prover.get_random_vars());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid opening")]
+    fn test_sumcheck_with_wrong_polynomial() {
+        // the prover runs the sumcheck on a polynomial other than the committed one
+        let (srs, poly) = setup(3);
+        let other = DenseMultilinearExtension::rand(3, &mut test_rng());
+        let oracle = OpeningOracle {
+            pcs: &srs,
+            commitment: srs.commit(&poly),
+            poly: &other,
+            data: &(),
+        };
+
+        let mut prover =
+            ProverState::new(ProductPolynomial::new(3, vec![other.evaluations.clone()]));
+        let mut verifier = VerifierState::with_oracle(prover.calculate_sum(), 3, oracle);
+        for _ in 0..3 {
+            let r = verifier.verify_round(prover.calculate_round_poly());
+            prover.update_random_vars(r);
+        }
+    }
+}
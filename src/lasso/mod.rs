@@ -0,0 +1,435 @@
+//! Lasso (Setty, Thaler, Wahby 2023): lookups into huge decomposable tables.
+//!
+//! A lookup at index a returns T[a] = g(T_1[a_1], ..., T_k[a_k]). The prover
+//! commits to the chunks dim_i of the m indices and to E_k, the values read
+//! by every memory (a subtable read at one of the chunks). For a random r,
+//!
+//!   v(r) = sum_{j in {0,1}^log m} eq(r, j) g(E_1(j), ..., E_k(j)),
+//!
+//! where v are the results of the lookups, is proven with a sumcheck whose
+//! cost depends on m and not on the size of T.
+//!
+//! That E_k was actually read from the subtable at dim_i is shown with offline
+//! memory checking: every memory is a log of m reads of its subtable, proven
+//! with `MemoryChecking`, whose committed addresses and values read are dim_i
+//! and E_k. The verifier also checks that the values written back are the
+//! values read, so the subtable never changes, and that the memories reading
+//! the same chunk read at the same addresses. The commitments are
+//! deterministic, so both are equalities of commitments.
+mod tables;
+
+pub use tables::{operands_index, And32, DecomposableTable, Lt32, Xor32};
+
+use crate::memory_checking::{MemoryChecking, MemoryCheckingProof, MemoryTrace};
+use crate::multilinear::{eq_evaluate, eq_table, mle_evaluate};
+use crate::oracle::Oracle;
+use crate::pcs::MultilinearPcs;
+use crate::sumcheck::{prove_noninteractive, verify_noninteractive, SumOfProducts, VerifierState};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::RngCore;
+
+pub struct Lasso<F: Field, P: MultilinearPcs<F>, T: DecomposableTable<F>> {
+    table: T,
+    subtables: Vec<Vec<F>>,
+    memories: Vec<(usize, usize)>,
+    lookup_vars: usize,
+    /// memory checking of the reads of every memory
+    checkings: Vec<MemoryChecking<F, P>>,
+}
+
+pub struct LassoProof<F: Field, P: MultilinearPcs<F>> {
+    /// memory checking of every memory, with the commitments to dim and E
+    memories: Vec<MemoryCheckingProof<F, P>>,
+    round_polys: Vec<UnivariatePolynomial<F>>,
+    /// E_k at the end of the sumcheck
+    value_evals: Vec<F>,
+    openings: Vec<P::Proof>,
+}
+
+// final check of the sumcheck, eq(r, x) g(E(x)) with the values claimed by the
+// prover
+struct PrimaryOracle<'a, F: Field, T: DecomposableTable<F>> {
+    r: &'a [F],
+    table: &'a T,
+    value_evals: &'a [F],
+}
+
+impl<F: Field, T: DecomposableTable<F>> Oracle<F> for PrimaryOracle<'_, F, T> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        eq_evaluate(self.r, point) * self.table.combine(self.value_evals)
+    }
+}
+
+impl<F: Field, P: MultilinearPcs<F>, T: DecomposableTable<F>> Lasso<F, P, T> {
+    /// Lookups of up to 2^lookup_vars indices into the table
+    pub fn new<R: RngCore>(table: T, lookup_vars: usize, rng: &mut R) -> Self {
+        let subtables = table.subtables();
+        let memories = table.memories();
+        let checkings = memories
+            .iter()
+            .map(|(_, subtable)| {
+                MemoryChecking::new(subtables[*subtable].clone(), lookup_vars, rng)
+            })
+            .collect();
+        Lasso {
+            table,
+            subtables,
+            memories,
+            lookup_vars,
+            checkings,
+        }
+    }
+
+    pub fn num_lookups(&self) -> usize {
+        1 << self.lookup_vars
+    }
+
+    /// Returns T[index] for every index, padded with lookups at 0, and the
+    /// proof
+    pub fn prove(&self, indices: &[u64]) -> (Vec<F>, LassoProof<F, P>) {
+        assert!(indices.len() <= self.num_lookups(), "too many lookups");
+        let mut indices = indices.to_vec();
+        indices.resize(self.num_lookups(), 0);
+        let chunks: Vec<Vec<usize>> = indices.iter().map(|i| self.table.decompose(*i)).collect();
+
+        // every memory reads its subtable at its chunk of every index
+        let mut memories = vec![];
+        let mut value_polys = vec![];
+        let mut value_data = vec![];
+        for ((chunk, subtable), checking) in self.memories.iter().zip(&self.checkings) {
+            let mut trace = MemoryTrace::new(self.subtables[*subtable].clone());
+            for chunks in &chunks {
+                trace.read(chunks[*chunk]);
+            }
+            let (memory, values, data) = checking.prove_with_read_values(&trace);
+            memories.push(memory);
+            value_polys.push(values);
+            value_data.push(data);
+        }
+        let outputs: Vec<F> = (0..self.num_lookups())
+            .map(|j| {
+                let read: Vec<F> = value_polys.iter().map(|v| v.evaluations[j]).collect();
+                self.table.combine(&read)
+            })
+            .collect();
+        let mut transcript = new_transcript(&outputs, &memories);
+
+        // primary sumcheck over the tables [eq(r, .), E_1, ..., E_k]
+        let r = transcript.challenge_fields::<F>(b"lasso_r", self.lookup_vars);
+        let mut tables = vec![eq_table(&r)];
+        tables.extend(value_polys.iter().map(|v| v.evaluations.clone()));
+        let (round_polys, prover) = prove_noninteractive(
+            SumOfProducts::new(self.lookup_vars, tables, self.primary_terms()),
+            &mut transcript,
+        );
+        let rz = prover.get_random_vars().to_vec();
+        let value_evals = prover.get_poly().final_evaluations()[1..].to_vec();
+        transcript.append_serializable(b"lasso_value_evals", &value_evals);
+
+        let openings = self
+            .checkings
+            .iter()
+            .zip(value_polys.iter().zip(&value_data))
+            .map(|(checking, (poly, data))| {
+                checking.op_pcs().open(poly, data, &rz, &mut transcript).1
+            })
+            .collect();
+
+        let proof = LassoProof {
+            memories,
+            round_polys,
+            value_evals,
+            openings,
+        };
+        (outputs, proof)
+    }
+
+    pub fn verify(&self, outputs: &[F], proof: &LassoProof<F, P>) -> Result<(), String> {
+        let num_memories = self.memories.len();
+        if outputs.len() != self.num_lookups()
+            || proof.memories.len() != num_memories
+            || proof.value_evals.len() != num_memories
+            || proof.openings.len() != num_memories
+        {
+            return Err("malformed proof".to_string());
+        }
+        for (k, ((chunk, _), checking)) in self.memories.iter().zip(&self.checkings).enumerate() {
+            let memory = &proof.memories[k];
+            checking
+                .verify(memory)
+                .map_err(|err| format!("memory {}: {}", k, err))?;
+            if !same_commitment(
+                &memory.read_value_commitment,
+                &memory.write_value_commitment,
+            ) {
+                return Err(format!("memory {}: the subtable is written", k));
+            }
+            let first = self.memories.iter().position(|(c, _)| c == chunk).unwrap();
+            if !same_commitment(
+                &memory.address_commitment,
+                &proof.memories[first].address_commitment,
+            ) {
+                return Err(format!(
+                    "memory {}: addresses differ from memory {}",
+                    k, first
+                ));
+            }
+        }
+
+        let mut transcript = new_transcript(outputs, &proof.memories);
+
+        let r = transcript.challenge_fields::<F>(b"lasso_r", self.lookup_vars);
+        let oracle = PrimaryOracle {
+            r: &r,
+            table: &self.table,
+            value_evals: &proof.value_evals,
+        };
+        let claim = mle_evaluate(outputs, &r);
+        let verifier = VerifierState::with_oracle(claim, self.lookup_vars, oracle);
+        let degree = 1 + self
+            .primary_terms()
+            .iter()
+            .map(|(_, t)| t.len() - 1)
+            .max()
+            .unwrap_or(0);
+        let rz = verify_noninteractive(verifier, &proof.round_polys, degree, &mut transcript)
+            .map_err(|err| format!("primary sumcheck: {}", err))?;
+        transcript.append_serializable(b"lasso_value_evals", &proof.value_evals);
+
+        for (k, checking) in self.checkings.iter().enumerate() {
+            let memory = &proof.memories[k];
+            let value = proof.value_evals[k];
+            let (commitment, opening) = (&memory.read_value_commitment, &proof.openings[k]);
+            if !checking
+                .op_pcs()
+                .verify(commitment, &rz, value, opening, &mut transcript)
+            {
+                return Err("invalid opening".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    // eq(r, x) times every monomial of g, over the tables [eq, E_1, ..., E_k]
+    fn primary_terms(&self) -> Vec<(F, Vec<usize>)> {
+        self.table
+            .combine_terms()
+            .into_iter()
+            .map(|(coefficient, memories)| {
+                let factors = std::iter::once(0).chain(memories.iter().map(|m| m + 1));
+                (coefficient, factors.collect())
+            })
+            .collect()
+    }
+}
+
+fn new_transcript<F: Field, P: MultilinearPcs<F>>(
+    outputs: &[F],
+    memories: &[MemoryCheckingProof<F, P>],
+) -> Transcript {
+    let mut transcript = Transcript::new(b"lasso");
+    transcript.append_serializable(b"lasso_outputs", outputs);
+    for memory in memories {
+        transcript.append_serializable(b"lasso_commitments", &memory.address_commitment);
+        transcript.append_serializable(b"lasso_commitments", &memory.read_value_commitment);
+    }
+    transcript
+}
+
+fn same_commitment<C: CanonicalSerialize>(left: &C, right: &C) -> bool {
+    let bytes = |commitment: &C| {
+        let mut bytes = vec![];
+        commitment
+            .serialize_compressed(&mut bytes)
+            .expect("serialization into a vector does not fail");
+        bytes
+    };
+    bytes(left) == bytes(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hyrax::Hyrax;
+    use crate::pcs::IdealOracle;
+    use ark_poly::DenseMultilinearExtension;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    type Ideal = IdealOracle<DenseMultilinearExtension<Fr>>;
+
+    fn operand_pairs() -> Vec<(u32, u32)> {
+        vec![
+            (0xdead_beef, 0x1234_5678),
+            (3, 5),
+            (5, 3),
+            (0xffff_ffff, 0xffff_ffff),
+            (0x8000_0000, 0x7fff_ffff),
+            (0x0102_0304, 0x0102_0305),
+            (42, 42),
+        ]
+    }
+
+    fn check_table<T: DecomposableTable<Fr>>(table: T, op: impl Fn(u32, u32) -> u64) {
+        let lasso = Lasso::<Fr, Ideal, T>::new(table, 3, &mut test_rng());
+        let indices: Vec<u64> = operand_pairs()
+            .iter()
+            .map(|(x, y)| operands_index(*x, *y))
+            .collect();
+        let (outputs, proof) = lasso.prove(&indices);
+        for ((x, y), output) in operand_pairs().into_iter().zip(&outputs) {
+            assert_eq!(*output, Fr::from(op(x, y)));
+        }
+        assert_eq!(lasso.verify(&outputs, &proof), Ok(()));
+    }
+
+    #[test]
+    fn test_and() {
+        check_table(And32, |x, y| (x & y) as u64);
+    }
+
+    #[test]
+    fn test_xor() {
+        check_table(Xor32, |x, y| (x ^ y) as u64);
+    }
+
+    #[test]
+    fn test_lt() {
+        check_table(Lt32, |x, y| (x < y) as u64);
+    }
+
+    /// x XOR y for 8-bit x and y in 2-bit chunks, small enough for tests that
+    /// do not need the 32-bit tables
+    struct Xor8;
+
+    impl DecomposableTable<Fr> for Xor8 {
+        fn chunk_bits(&self) -> usize {
+            4
+        }
+
+        fn num_chunks(&self) -> usize {
+            4
+        }
+
+        fn subtables(&self) -> Vec<Vec<Fr>> {
+            vec![(0..16u64).map(|i| Fr::from((i >> 2) ^ (i & 3))).collect()]
+        }
+
+        fn memories(&self) -> Vec<(usize, usize)> {
+            (0..4).map(|i| (i, 0)).collect()
+        }
+
+        fn combine_terms(&self) -> Vec<(Fr, Vec<usize>)> {
+            (0..4)
+                .map(|i| (Fr::from(1u64 << (2 * i)), vec![i]))
+                .collect()
+        }
+
+        fn decompose(&self, index: u64) -> Vec<usize> {
+            let (x, y) = (index >> 8, index & 0xff);
+            (0..4)
+                .map(|i| ((((x >> (2 * i)) & 3) << 2) | ((y >> (2 * i)) & 3)) as usize)
+                .collect()
+        }
+
+        fn evaluate(&self, index: u64) -> Fr {
+            Fr::from((index >> 8) ^ (index & 0xff))
+        }
+    }
+
+    #[test]
+    fn test_small_table() {
+        let lasso = Lasso::<Fr, Ideal, Xor8>::new(Xor8, 2, &mut test_rng());
+        let indices = [0xab_cd, 0x00_ff, 0x12_12];
+        let (outputs, proof) = lasso.prove(&indices);
+        for (index, output) in indices.iter().zip(&outputs) {
+            assert_eq!(*output, Xor8.evaluate(*index));
+        }
+        assert_eq!(lasso.verify(&outputs, &proof), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_output() {
+        let lasso = Lasso::<Fr, Ideal, Xor8>::new(Xor8, 2, &mut test_rng());
+        let (mut outputs, proof) = lasso.prove(&[(6 << 8) | 3]);
+        assert_eq!(outputs[0], Fr::from(5));
+        outputs[0] = Fr::from(7);
+        assert!(lasso.verify(&outputs, &proof).is_err());
+    }
+
+    /// Xor8 with 2 XOR 3 = 3 in its subtable
+    struct WrongXor8;
+
+    impl DecomposableTable<Fr> for WrongXor8 {
+        fn chunk_bits(&self) -> usize {
+            Xor8.chunk_bits()
+        }
+
+        fn num_chunks(&self) -> usize {
+            Xor8.num_chunks()
+        }
+
+        fn subtables(&self) -> Vec<Vec<Fr>> {
+            let mut subtables = Xor8.subtables();
+            subtables[0][(2 << 2) | 3] = Fr::from(3);
+            subtables
+        }
+
+        fn memories(&self) -> Vec<(usize, usize)> {
+            Xor8.memories()
+        }
+
+        fn combine_terms(&self) -> Vec<(Fr, Vec<usize>)> {
+            Xor8.combine_terms()
+        }
+
+        fn decompose(&self, index: u64) -> Vec<usize> {
+            Xor8.decompose(index)
+        }
+
+        fn evaluate(&self, index: u64) -> Fr {
+            Xor8.evaluate(index)
+        }
+    }
+
+    #[test]
+    fn test_wrong_subtable_read() {
+        // claim that 2 XOR 3 = 3 by reading a wrong value in the subtable
+        let cheating = Lasso::<Fr, Ideal, WrongXor8>::new(WrongXor8, 1, &mut test_rng());
+        let (outputs, proof) = cheating.prove(&[(2 << 8) | 3]);
+        assert_eq!(outputs[0], Fr::from(3));
+        let honest = Lasso::<Fr, Ideal, Xor8>::new(Xor8, 1, &mut test_rng());
+        let err = honest.verify(&outputs, &proof).unwrap_err();
+        assert!(err.contains("memory 0"), "{}", err);
+    }
+
+    #[test]
+    fn test_subtable_is_read_only() {
+        // a consistent log that writes 7 at 2 XOR 3 before reading it
+        let lasso = Lasso::<Fr, Ideal, Xor8>::new(Xor8, 1, &mut test_rng());
+        let (outputs, mut proof) = lasso.prove(&[(2 << 8) | 3]);
+        let mut trace = MemoryTrace::new(lasso.subtables[0].clone());
+        trace.write((2 << 2) | 3, Fr::from(7));
+        trace.read((2 << 2) | 3);
+        proof.memories[0] = lasso.checkings[0].prove(&trace);
+        assert_eq!(
+            lasso.verify(&outputs, &proof),
+            Err("memory 0: the subtable is written".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hyrax() {
+        let lasso = Lasso::<Fr, Hyrax<G1Projective>, Xor8>::new(Xor8, 2, &mut test_rng());
+        let indices = [0xab_cd, 0x01_01];
+        let (outputs, proof) = lasso.prove(&indices);
+        assert_eq!(outputs[0], Fr::from(0xab ^ 0xcd));
+        assert_eq!(lasso.verify(&outputs, &proof), Ok(()));
+    }
+}
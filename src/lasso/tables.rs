@@ -0,0 +1,231 @@
+use crate::sumcheck::evaluate_terms;
+use ark_ff::Field;
+
+/// A big table T with T[index] = g(T_1[chunk_1], ..., T_k[chunk_k]) for small
+/// subtables T_i, where g is a polynomial and the chunks are read from the
+/// index. Lasso never materializes T, only its subtables.
+pub trait DecomposableTable<F: Field> {
+    /// Number of bits of a subtable index
+    fn chunk_bits(&self) -> usize;
+
+    fn num_chunks(&self) -> usize;
+
+    /// The distinct subtables, 2^chunk_bits entries each
+    fn subtables(&self) -> Vec<Vec<F>>;
+
+    /// (chunk, subtable) read by every memory, the arguments of g in order
+    fn memories(&self) -> Vec<(usize, usize)>;
+
+    /// g as a sum of monomials (coefficient, memories multiplied together)
+    fn combine_terms(&self) -> Vec<(F, Vec<usize>)>;
+
+    /// Subtable index of every chunk of a lookup index
+    fn decompose(&self, index: u64) -> Vec<usize>;
+
+    /// T[index], computed directly
+    fn evaluate(&self, index: u64) -> F;
+
+    /// g of the values read by the memories
+    fn combine(&self, values: &[F]) -> F {
+        evaluate_terms(&self.combine_terms(), values)
+    }
+}
+
+/// Lookup index of an operation on two 32-bit operands
+pub fn operands_index(x: u32, y: u32) -> u64 {
+    ((x as u64) << 32) | y as u64
+}
+
+fn operands(index: u64) -> (u32, u32) {
+    ((index >> 32) as u32, index as u32)
+}
+
+// chunk i is (x_i, y_i), the i-th bytes of the operands, as x_i * 2^8 + y_i
+fn byte_chunks(index: u64) -> Vec<usize> {
+    let (x, y) = operands(index);
+    (0..4)
+        .map(|i| (((x >> (8 * i)) & 0xff) << 8 | ((y >> (8 * i)) & 0xff)) as usize)
+        .collect()
+}
+
+fn byte_subtable<F: Field>(op: impl Fn(u64, u64) -> u64) -> Vec<F> {
+    (0..1u64 << 16)
+        .map(|i| F::from(op(i >> 8, i & 0xff)))
+        .collect()
+}
+
+// sum_i 2^(8i) T[chunk_i], the result of a bitwise operation
+fn bytes_terms<F: Field>() -> Vec<(F, Vec<usize>)> {
+    (0..4)
+        .map(|i| (F::from(1u64 << (8 * i)), vec![i]))
+        .collect()
+}
+
+/// x AND y for 32-bit x and y, from the AND of their bytes
+#[derive(Clone, Copy, Debug, Default)]
+pub struct And32;
+
+/// x XOR y for 32-bit x and y, from the XOR of their bytes
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Xor32;
+
+/// 1 if x < y for unsigned 32-bit x and y, else 0. With LT_i and EQ_i the
+/// comparisons of the i-th bytes, x < y = sum_i LT_i prod_{j > i} EQ_j.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lt32;
+
+impl<F: Field> DecomposableTable<F> for And32 {
+    fn chunk_bits(&self) -> usize {
+        16
+    }
+
+    fn num_chunks(&self) -> usize {
+        4
+    }
+
+    fn subtables(&self) -> Vec<Vec<F>> {
+        vec![byte_subtable(|a, b| a & b)]
+    }
+
+    fn memories(&self) -> Vec<(usize, usize)> {
+        (0..4).map(|i| (i, 0)).collect()
+    }
+
+    fn combine_terms(&self) -> Vec<(F, Vec<usize>)> {
+        bytes_terms()
+    }
+
+    fn decompose(&self, index: u64) -> Vec<usize> {
+        byte_chunks(index)
+    }
+
+    fn evaluate(&self, index: u64) -> F {
+        let (x, y) = operands(index);
+        F::from(x & y)
+    }
+}
+
+impl<F: Field> DecomposableTable<F> for Xor32 {
+    fn chunk_bits(&self) -> usize {
+        16
+    }
+
+    fn num_chunks(&self) -> usize {
+        4
+    }
+
+    fn subtables(&self) -> Vec<Vec<F>> {
+        vec![byte_subtable(|a, b| a ^ b)]
+    }
+
+    fn memories(&self) -> Vec<(usize, usize)> {
+        (0..4).map(|i| (i, 0)).collect()
+    }
+
+    fn combine_terms(&self) -> Vec<(F, Vec<usize>)> {
+        bytes_terms()
+    }
+
+    fn decompose(&self, index: u64) -> Vec<usize> {
+        byte_chunks(index)
+    }
+
+    fn evaluate(&self, index: u64) -> F {
+        let (x, y) = operands(index);
+        F::from(x ^ y)
+    }
+}
+
+impl<F: Field> DecomposableTable<F> for Lt32 {
+    fn chunk_bits(&self) -> usize {
+        16
+    }
+
+    fn num_chunks(&self) -> usize {
+        4
+    }
+
+    fn subtables(&self) -> Vec<Vec<F>> {
+        vec![
+            byte_subtable(|a, b| (a < b) as u64),
+            byte_subtable(|a, b| (a == b) as u64),
+        ]
+    }
+
+    /// LT_0, ..., LT_3, then EQ_1, EQ_2 and EQ_3, EQ_0 is never needed
+    fn memories(&self) -> Vec<(usize, usize)> {
+        (0..4)
+            .map(|i| (i, 0))
+            .chain((1..4).map(|i| (i, 1)))
+            .collect()
+    }
+
+    fn combine_terms(&self) -> Vec<(F, Vec<usize>)> {
+        (0..4)
+            .map(|i| {
+                let eqs = (i + 1..4).map(|j| 3 + j);
+                (F::ONE, std::iter::once(i).chain(eqs).collect())
+            })
+            .collect()
+    }
+
+    fn decompose(&self, index: u64) -> Vec<usize> {
+        byte_chunks(index)
+    }
+
+    fn evaluate(&self, index: u64) -> F {
+        let (x, y) = operands(index);
+        F::from((x < y) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    // T[index] = g(subtable values) for every table
+    fn check_decomposition<T: DecomposableTable<Fr>>(table: &T) {
+        let subtables = table.subtables();
+        let pairs = [
+            (0, 0),
+            (1, 2),
+            (0xdead_beef, 0xdead_beef),
+            (0x1234_5678, 0x1234_5679),
+            (0xff00_0000, 0x0fff_ffff),
+            (7, 0xffff_ffff),
+        ];
+        for (x, y) in pairs {
+            let index = operands_index(x, y);
+            let chunks = table.decompose(index);
+            assert_eq!(chunks.len(), table.num_chunks());
+            let values: Vec<Fr> = table
+                .memories()
+                .iter()
+                .map(|(chunk, subtable)| subtables[*subtable][chunks[*chunk]])
+                .collect();
+            assert_eq!(
+                table.combine(&values),
+                table.evaluate(index),
+                "{:x} {:x}",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_decompositions() {
+        check_decomposition(&And32);
+        check_decomposition(&Xor32);
+        check_decomposition(&Lt32);
+    }
+
+    #[test]
+    fn test_lt() {
+        let lt = |x, y| DecomposableTable::<Fr>::evaluate(&Lt32, operands_index(x, y));
+        assert_eq!(lt(3, 5), Fr::ONE);
+        assert_eq!(lt(5, 3), Fr::ZERO);
+        assert_eq!(lt(5, 5), Fr::ZERO);
+    }
+}
@@ -1,3 +1,28 @@
+pub mod fri;
+pub mod gkr;
+pub mod grand_product;
+pub mod groth16;
+pub mod hyrax;
+pub mod ipa;
+pub mod kzg;
+pub mod lasso;
+pub mod ligero;
+pub mod lookup;
+pub mod memory_checking;
+pub mod merkle;
+pub mod multilinear;
+pub mod oracle;
+pub mod parser;
+pub mod pcs;
+pub mod pedersen;
+pub mod plonk;
 pub mod polynomial;
+pub mod r1cs;
+pub mod sigma;
 pub mod solomon_reed_code;
+pub mod spartan;
 pub mod sumcheck;
+#[cfg(test)]
+mod test_fields;
+pub mod transcript;
+pub mod univariate;
@@ -0,0 +1,378 @@
+//! Ligero commitments to multilinear polynomials (Ames et al. 2017, in the
+//! multilinear form of Brakedown, Golovnev et al. 2021), Thaler Chapter 10.5.
+//!
+//! As in Hyrax, the evaluations form a matrix M with f(r) = L^T M R. Every row
+//! is encoded with Reed-Solomon and the columns of the encoded matrix are
+//! committed with a Merkle tree. To open, the prover sends a random
+//! combination of the rows, which tests that all of them are close to
+//! codewords, and the combination L^T M. Both are checked against the same
+//! random columns, since encoding commutes with linear combinations. The
+//! scheme only needs a hash function, and the prover time is dominated by the
+//! encoding. Rows are encoded with Reed-Solomon, the linear-time expander
+//! codes of Brakedown are not implemented.
+use crate::merkle::{MerkleHasher, MerkleTree, MultiProof};
+use crate::multilinear::{column_vars, combine_rows, inner_product, matrix_tensor};
+use crate::pcs::{open_separately, verify_separately, PolynomialCommitment};
+use crate::solomon_reed_code::ReedSolomon;
+use crate::transcript::Transcript;
+use ark_ff::{FftField, Field};
+use ark_poly::{DenseMultilinearExtension, EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+#[derive(Clone, Debug)]
+pub struct Ligero<H> {
+    num_vars: usize,
+    /// length of the encoded rows over the length of the rows
+    blowup_factor: usize,
+    /// number of columns checked by the verifier
+    num_queries: usize,
+    hasher: H,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroCommitment<D: CanonicalSerialize + CanonicalDeserialize> {
+    pub root: D,
+}
+
+/// Prover data of a commitment, the tree over the columns of the encoded rows
+pub struct CommittedMatrix<F: Field, H: MerkleHasher<F>> {
+    rows: Vec<Vec<F>>,
+    tree: MerkleTree<F, H>,
+}
+
+impl<F: Field, H: MerkleHasher<F>> CommittedMatrix<F, H> {
+    pub fn commitment(&self) -> LigeroCommitment<H::Digest> {
+        LigeroCommitment {
+            root: self.tree.root(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LigeroProof<F: Field, D: CanonicalSerialize + CanonicalDeserialize> {
+    /// random combination of the rows, for the proximity test
+    proximity_row: Vec<F>,
+    /// L^T M, for the evaluation
+    combined_row: Vec<F>,
+    columns: MultiProof<F, D>,
+}
+
+impl<H: Clone> Ligero<H> {
+    pub fn new(num_vars: usize, blowup_factor: usize, num_queries: usize, hasher: H) -> Self {
+        assert!(
+            blowup_factor.is_power_of_two() && blowup_factor >= 2,
+            "blowup factor must be a power of 2 greater than 1"
+        );
+        assert!(num_queries > 0, "at least one query is needed");
+        Ligero {
+            num_vars,
+            blowup_factor,
+            num_queries,
+            hasher,
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn commit<F: FftField>(&self, poly: &DenseMultilinearExtension<F>) -> CommittedMatrix<F, H>
+    where
+        H: MerkleHasher<F>,
+    {
+        assert_eq!(
+            poly.num_vars, self.num_vars,
+            "polynomial with a different number of variables"
+        );
+        let rows: Vec<Vec<F>> = poly
+            .evaluations
+            .chunks(self.row_length())
+            .map(|row| row.to_vec())
+            .collect();
+        let encoded: Vec<Vec<F>> = rows.iter().map(|row| self.encode(row)).collect();
+        let columns = (0..self.encoded_length())
+            .map(|j| encoded.iter().map(|row| row[j]).collect())
+            .collect();
+        CommittedMatrix {
+            rows,
+            tree: MerkleTree::new(self.hasher.clone(), columns),
+        }
+    }
+
+    /// Opens the polynomial at the point, returning f(point) and the proof
+    pub fn open<F: FftField>(
+        &self,
+        committed: &CommittedMatrix<F, H>,
+        point: &[F],
+        transcript: &mut Transcript,
+    ) -> (F, LigeroProof<F, H::Digest>)
+    where
+        H: MerkleHasher<F>,
+    {
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        let combined_row = combine_rows(&committed.rows, &left, self.row_length());
+        let value = inner_product(&combined_row, &right);
+
+        let gamma = self.absorb_claim(&committed.commitment(), point, value, transcript);
+        let proximity_row = combine_rows(&committed.rows, &gamma, self.row_length());
+        transcript.append_serializable(b"ligero_proximity_row", &proximity_row);
+        transcript.append_serializable(b"ligero_combined_row", &combined_row);
+        let columns = committed.tree.open_batch(&self.column_queries(transcript));
+
+        let proof = LigeroProof {
+            proximity_row,
+            combined_row,
+            columns,
+        };
+        (value, proof)
+    }
+
+    pub fn verify<F: FftField>(
+        &self,
+        commitment: &LigeroCommitment<H::Digest>,
+        point: &[F],
+        value: F,
+        proof: &LigeroProof<F, H::Digest>,
+        transcript: &mut Transcript,
+    ) -> bool
+    where
+        H: MerkleHasher<F>,
+    {
+        if point.len() != self.num_vars
+            || proof.proximity_row.len() != self.row_length()
+            || proof.combined_row.len() != self.row_length()
+        {
+            return false;
+        }
+        let (left, right) = matrix_tensor(point, self.num_vars);
+        if inner_product(&proof.combined_row, &right) != value {
+            return false;
+        }
+
+        let gamma = self.absorb_claim(commitment, point, value, transcript);
+        transcript.append_serializable(b"ligero_proximity_row", &proof.proximity_row);
+        transcript.append_serializable(b"ligero_combined_row", &proof.combined_row);
+        let queries = self.column_queries(transcript);
+        if !MerkleTree::verify(
+            &self.hasher,
+            &commitment.root,
+            self.encoded_length(),
+            &queries,
+            &proof.columns,
+        ) {
+            return false;
+        }
+
+        // Enc(sum_i c_i M_i)[j] = sum_i c_i Enc(M_i)[j] on every opened column
+        let proximity = self.encode(&proof.proximity_row);
+        let combined = self.encode(&proof.combined_row);
+        proof
+            .columns
+            .indices
+            .iter()
+            .zip(proof.columns.leaves.iter())
+            .all(|(j, column)| {
+                column.len() == left.len()
+                    && inner_product(column, &gamma) == proximity[*j]
+                    && inner_product(column, &left) == combined[*j]
+            })
+    }
+
+    fn row_length(&self) -> usize {
+        1 << column_vars(self.num_vars)
+    }
+
+    fn encoded_length(&self) -> usize {
+        self.row_length() * self.blowup_factor
+    }
+
+    fn encode<F: FftField>(&self, row: &[F]) -> Vec<F> {
+        let domain = Radix2EvaluationDomain::new(self.encoded_length())
+            .expect("field does not have a domain of this size");
+        ReedSolomon::new(row.to_vec()).encode_over_domain(&domain)
+    }
+
+    // binds the claim and draws the coefficients of the proximity test
+    fn absorb_claim<F: Field, D: CanonicalSerialize + CanonicalDeserialize>(
+        &self,
+        commitment: &LigeroCommitment<D>,
+        point: &[F],
+        value: F,
+        transcript: &mut Transcript,
+    ) -> Vec<F> {
+        transcript.append_serializable(b"ligero_commitment", commitment);
+        transcript.append_serializable(b"ligero_point", point);
+        transcript.append_field(b"ligero_value", &value);
+        let num_rows = 1 << (self.num_vars - column_vars(self.num_vars));
+        transcript.challenge_fields(b"ligero_gamma", num_rows)
+    }
+
+    fn column_queries(&self, transcript: &mut Transcript) -> Vec<usize> {
+        (0..self.num_queries)
+            .map(|_| transcript.challenge_index(b"ligero_column", self.encoded_length()))
+            .collect()
+    }
+}
+
+impl<F: FftField, H: MerkleHasher<F> + Clone + Default> PolynomialCommitment<F> for Ligero<H> {
+    type Polynomial = DenseMultilinearExtension<F>;
+    type Point = [F];
+    type Commitment = LigeroCommitment<H::Digest>;
+    type ProverData = CommittedMatrix<F, H>;
+    type Proof = LigeroProof<F, H::Digest>;
+    type BatchProof = Vec<LigeroProof<F, H::Digest>>;
+
+    /// Rate 1/4 and 32 opened columns
+    fn setup<R: RngCore>(size: usize, _rng: &mut R) -> Self {
+        Ligero::new(size, 4, 32, H::default())
+    }
+
+    fn commit(&self, poly: &Self::Polynomial) -> (Self::Commitment, Self::ProverData) {
+        let committed = Ligero::commit(self, poly);
+        (committed.commitment(), committed)
+    }
+
+    fn open(
+        &self,
+        _poly: &Self::Polynomial,
+        data: &Self::ProverData,
+        point: &[F],
+        transcript: &mut Transcript,
+    ) -> (F, Self::Proof) {
+        Ligero::open(self, data, point, transcript)
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        proof: &Self::Proof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        Ligero::verify(self, commitment, point, value, proof, transcript)
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &Self::ProverData)],
+        points: &[&[F]],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<F>>, Self::BatchProof) {
+        open_separately(self, polys, points, transcript)
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[Self::Commitment],
+        points: &[&[F]],
+        values: &[Vec<F>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool {
+        verify_separately(self, commitments, points, values, proof, transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::{Blake3Hasher, Sha256Hasher};
+    use ark_ff::UniformRand;
+    use ark_poly::MultilinearExtension;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn setup(num_vars: usize) -> (DenseMultilinearExtension<Fr>, Vec<Fr>) {
+        let mut rng = test_rng();
+        let poly = DenseMultilinearExtension::rand(num_vars, &mut rng);
+        let point = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        (poly, point)
+    }
+
+    #[test]
+    fn test_open() {
+        for num_vars in [2, 5, 8] {
+            let ligero = Ligero::new(num_vars, 4, 20, Sha256Hasher);
+            let (poly, point) = setup(num_vars);
+            let committed = ligero.commit(&poly);
+            let commitment = committed.commitment();
+
+            let (value, proof) = ligero.open(&committed, &point, &mut Transcript::new(b"ligero"));
+            assert_eq!(Some(value), poly.evaluate(&point));
+            assert!(ligero.verify(
+                &commitment,
+                &point,
+                value,
+                &proof,
+                &mut Transcript::new(b"ligero")
+            ));
+            assert!(!ligero.verify(
+                &commitment,
+                &point,
+                value + Fr::ONE,
+                &proof,
+                &mut Transcript::new(b"ligero")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let ligero = Ligero::new(6, 2, 10, Blake3Hasher);
+        let (poly, point) = setup(6);
+        let committed = ligero.commit(&poly);
+        let commitment = committed.commitment();
+        let (value, proof) = ligero.open(&committed, &point, &mut Transcript::new(b"ligero"));
+
+        // a row consistent with the value, but not with the committed matrix
+        let mut wrong = proof.clone();
+        wrong.combined_row[0] += Fr::ONE;
+        wrong.combined_row[1] -= Fr::ONE;
+        let (_, right) = matrix_tensor(&point, ligero.num_vars);
+        let value_of_wrong = inner_product(&wrong.combined_row, &right);
+        assert!(!ligero.verify(
+            &commitment,
+            &point,
+            value_of_wrong,
+            &wrong,
+            &mut Transcript::new(b"ligero")
+        ));
+
+        let mut wrong = proof.clone();
+        wrong.proximity_row[3] += Fr::ONE;
+        assert!(!ligero.verify(
+            &commitment,
+            &point,
+            value,
+            &wrong,
+            &mut Transcript::new(b"ligero")
+        ));
+
+        let mut wrong = proof;
+        wrong.columns.leaves[0][0] += Fr::ONE;
+        assert!(!ligero.verify(
+            &commitment,
+            &point,
+            value,
+            &wrong,
+            &mut Transcript::new(b"ligero")
+        ));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let ligero = Ligero::new(4, 2, 8, Sha256Hasher);
+        let (poly, point) = setup(4);
+        let (_, proof) = ligero.open(
+            &ligero.commit(&poly),
+            &point,
+            &mut Transcript::new(b"ligero"),
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = LigeroProof::<Fr, [u8; 32]>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+    }
+}
@@ -0,0 +1,346 @@
+use super::multiplicities;
+use crate::multilinear::{eq_evaluate, eq_table};
+use crate::oracle::Oracle;
+use crate::pcs::MultilinearPcs;
+use crate::sumcheck::{prove_noninteractive, verify_noninteractive, SumOfProducts, VerifierState};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::rand::RngCore;
+
+/// LogUp (Haböck 2022) with the sumcheck. After committing to the values f and
+/// the multiplicities m, and given a random α, the prover commits to the
+/// fractions h_f(x) = 1 / (α - f(x)) and h_t(y) = m(y) / (α - t(y)) and claims
+/// that both sum to S. Each claim is merged with a zero-check of its fraction,
+///
+///   S = sum_x h_f(x) + λ eq(τ, x) (h_f(x) (α - f(x)) - 1)
+///   S = sum_y h_t(y) + λ eq(τ', y) (h_t(y) (α - t(y)) - m(y)),
+///
+/// and proven with one sumcheck on each side. The verifier evaluates the
+/// table itself and opens f, h_f, m and h_t at the final points.
+pub struct LogUp<F: Field, P: MultilinearPcs<F>> {
+    /// padded to a power of 2 by repeating the first entry
+    table: Vec<F>,
+    table_vars: usize,
+    lookup_vars: usize,
+    lookup_pcs: P,
+    table_pcs: P,
+}
+
+pub struct LogUpProof<F: Field, P: MultilinearPcs<F>> {
+    pub values_commitment: P::Commitment,
+    multiplicities_commitment: P::Commitment,
+    /// commitments to h_f and h_t
+    inverse_commitments: [P::Commitment; 2],
+    sum: F,
+    values_round_polys: Vec<UnivariatePolynomial<F>>,
+    table_round_polys: Vec<UnivariatePolynomial<F>>,
+    /// f and h_f at the end of the first sumcheck, m and h_t at the end of the
+    /// second one
+    evaluations: [F; 4],
+    openings: Vec<P::Proof>,
+}
+
+// f(r), claimed by the prover, or the table that the verifier evaluates
+enum Denominator<'a, F> {
+    Claimed(F),
+    Table(&'a [F]),
+}
+
+// final check of one of the sumchecks, with the evaluations claimed by the
+// prover
+struct FractionOracle<'a, F: Field> {
+    tau: &'a [F],
+    alpha: F,
+    lambda: F,
+    inverse: F,
+    numerator: F,
+    denominator: Denominator<'a, F>,
+}
+
+impl<F: Field> Oracle<F> for FractionOracle<'_, F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        let denominator = match self.denominator {
+            Denominator::Claimed(value) => value,
+            Denominator::Table(table) => {
+                eq_table(point).iter().zip(table).map(|(e, t)| *e * t).sum()
+            }
+        };
+        let zero_check = self.inverse * (self.alpha - denominator) - self.numerator;
+        self.inverse + self.lambda * eq_evaluate(self.tau, point) * zero_check
+    }
+}
+
+impl<F: Field, P: MultilinearPcs<F>> LogUp<F, P> {
+    /// Lookups of up to 2^lookup_vars values into the table
+    pub fn new<R: RngCore>(table: &[F], lookup_vars: usize, rng: &mut R) -> Self {
+        assert!(!table.is_empty(), "empty table");
+        let table_vars = table.len().next_power_of_two().trailing_zeros() as usize;
+        let mut table = table.to_vec();
+        table.resize(1 << table_vars, table[0]);
+        LogUp {
+            table,
+            table_vars,
+            lookup_vars,
+            lookup_pcs: P::setup(lookup_vars, rng),
+            table_pcs: P::setup(table_vars, rng),
+        }
+    }
+
+    pub fn num_lookups(&self) -> usize {
+        1 << self.lookup_vars
+    }
+
+    /// Panics if a value is not in the table
+    pub fn prove(&self, values: &[F]) -> LogUpProof<F, P> {
+        assert!(values.len() <= self.num_lookups(), "too many values");
+        let mut values = values.to_vec();
+        values.resize(self.num_lookups(), self.table[0]);
+        let counts = multiplicities(&self.table, &values).expect("value not in the table");
+        self.prove_with_multiplicities(values, counts.into_iter().map(F::from).collect())
+    }
+
+    fn prove_with_multiplicities(&self, values: Vec<F>, counts: Vec<F>) -> LogUpProof<F, P> {
+        let f = DenseMultilinearExtension::from_evaluations_vec(self.lookup_vars, values);
+        let m = DenseMultilinearExtension::from_evaluations_vec(self.table_vars, counts);
+        let (values_commitment, f_data) = self.lookup_pcs.commit(&f);
+        let (multiplicities_commitment, m_data) = self.table_pcs.commit(&m);
+        let mut transcript = new_transcript(&values_commitment, &multiplicities_commitment);
+
+        let alpha = transcript.challenge_field::<F>(b"logup_alpha");
+        let inverses = |denominators: &[F]| {
+            let mut inverses: Vec<F> = denominators.iter().map(|d| alpha - d).collect();
+            ark_ff::batch_inversion(&mut inverses);
+            inverses
+        };
+        let h_f = inverses(&f.evaluations);
+        let h_t: Vec<F> = inverses(&self.table)
+            .iter()
+            .zip(&m.evaluations)
+            .map(|(inverse, count)| *inverse * count)
+            .collect();
+        let sum = h_f.iter().sum();
+        let h_f = DenseMultilinearExtension::from_evaluations_vec(self.lookup_vars, h_f);
+        let h_t = DenseMultilinearExtension::from_evaluations_vec(self.table_vars, h_t);
+        let (h_f_commitment, h_f_data) = self.lookup_pcs.commit(&h_f);
+        let (h_t_commitment, h_t_data) = self.table_pcs.commit(&h_t);
+        let inverse_commitments = [h_f_commitment, h_t_commitment];
+        transcript.append_serializable(b"logup_inverses", &inverse_commitments[..]);
+        transcript.append_field(b"logup_sum", &sum);
+
+        let tau = transcript.challenge_fields::<F>(b"logup_tau", self.lookup_vars);
+        let table_tau = transcript.challenge_fields::<F>(b"logup_tau", self.table_vars);
+        let lambda = transcript.challenge_field::<F>(b"logup_lambda");
+        // h + λα eq h - λ eq h d - λ eq n over the tables [h, eq, d, n]
+        let terms = vec![
+            (F::ONE, vec![0]),
+            (lambda * alpha, vec![1, 0]),
+            (-lambda, vec![1, 0, 2]),
+            (-lambda, vec![1, 3]),
+        ];
+        let ones = vec![F::ONE; self.num_lookups()];
+        let tables = vec![
+            h_f.evaluations.clone(),
+            eq_table(&tau),
+            f.evaluations.clone(),
+            ones,
+        ];
+        let (values_round_polys, prover) = prove_noninteractive(
+            SumOfProducts::new(self.lookup_vars, tables, terms.clone()),
+            &mut transcript,
+        );
+        let r = prover.get_random_vars().to_vec();
+        let tables = vec![
+            h_t.evaluations.clone(),
+            eq_table(&table_tau),
+            self.table.clone(),
+            m.evaluations.clone(),
+        ];
+        let (table_round_polys, prover) = prove_noninteractive(
+            SumOfProducts::new(self.table_vars, tables, terms),
+            &mut transcript,
+        );
+        let table_r = prover.get_random_vars().to_vec();
+
+        let (f_eval, f_proof) = self.lookup_pcs.open(&f, &f_data, &r, &mut transcript);
+        let (h_f_eval, h_f_proof) = self.lookup_pcs.open(&h_f, &h_f_data, &r, &mut transcript);
+        let (m_eval, m_proof) = self.table_pcs.open(&m, &m_data, &table_r, &mut transcript);
+        let (h_t_eval, h_t_proof) = self
+            .table_pcs
+            .open(&h_t, &h_t_data, &table_r, &mut transcript);
+        LogUpProof {
+            values_commitment,
+            multiplicities_commitment,
+            inverse_commitments,
+            sum,
+            values_round_polys,
+            table_round_polys,
+            evaluations: [f_eval, h_f_eval, m_eval, h_t_eval],
+            openings: vec![f_proof, h_f_proof, m_proof, h_t_proof],
+        }
+    }
+
+    pub fn verify(&self, proof: &LogUpProof<F, P>) -> Result<(), String> {
+        if proof.openings.len() != 4 {
+            return Err("wrong number of openings".to_string());
+        }
+        let mut transcript =
+            new_transcript(&proof.values_commitment, &proof.multiplicities_commitment);
+        let alpha = transcript.challenge_field::<F>(b"logup_alpha");
+        transcript.append_serializable(b"logup_inverses", &proof.inverse_commitments[..]);
+        transcript.append_field(b"logup_sum", &proof.sum);
+        let tau = transcript.challenge_fields::<F>(b"logup_tau", self.lookup_vars);
+        let table_tau = transcript.challenge_fields::<F>(b"logup_tau", self.table_vars);
+        let lambda = transcript.challenge_field::<F>(b"logup_lambda");
+        let [f_eval, h_f_eval, m_eval, h_t_eval] = proof.evaluations;
+
+        let oracle = FractionOracle {
+            tau: &tau,
+            alpha,
+            lambda,
+            inverse: h_f_eval,
+            numerator: F::ONE,
+            denominator: Denominator::Claimed(f_eval),
+        };
+        let verifier = VerifierState::with_oracle(proof.sum, self.lookup_vars, oracle);
+        let r = verify_noninteractive(verifier, &proof.values_round_polys, 3, &mut transcript)
+            .map_err(|err| format!("values sumcheck: {}", err))?;
+
+        let oracle = FractionOracle {
+            tau: &table_tau,
+            alpha,
+            lambda,
+            inverse: h_t_eval,
+            numerator: m_eval,
+            denominator: Denominator::Table(&self.table),
+        };
+        let verifier = VerifierState::with_oracle(proof.sum, self.table_vars, oracle);
+        let table_r = verify_noninteractive(verifier, &proof.table_round_polys, 3, &mut transcript)
+            .map_err(|err| format!("table sumcheck: {}", err))?;
+
+        let openings = [
+            (&self.lookup_pcs, &proof.values_commitment, &r, f_eval),
+            (
+                &self.lookup_pcs,
+                &proof.inverse_commitments[0],
+                &r,
+                h_f_eval,
+            ),
+            (
+                &self.table_pcs,
+                &proof.multiplicities_commitment,
+                &table_r,
+                m_eval,
+            ),
+            (
+                &self.table_pcs,
+                &proof.inverse_commitments[1],
+                &table_r,
+                h_t_eval,
+            ),
+        ];
+        for ((pcs, commitment, point, value), opening) in openings.into_iter().zip(&proof.openings)
+        {
+            if !pcs.verify(commitment, point, value, opening, &mut transcript) {
+                return Err("invalid opening".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn new_transcript<C: ark_serialize::CanonicalSerialize>(
+    values_commitment: &C,
+    multiplicities_commitment: &C,
+) -> Transcript {
+    let mut transcript = Transcript::new(b"logup");
+    transcript.append_serializable(b"logup_values", values_commitment);
+    transcript.append_serializable(b"logup_multiplicities", multiplicities_commitment);
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hyrax::Hyrax;
+    use crate::lookup::{range_table, xor_entry, xor_table};
+    use crate::pcs::IdealOracle;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    type Ideal = IdealOracle<DenseMultilinearExtension<Fr>>;
+
+    #[test]
+    fn test_byte_range() {
+        let logup = LogUp::<Fr, Hyrax<G1Projective>>::new(&range_table(8), 6, &mut test_rng());
+        let values: Vec<Fr> = (0..64u64).map(|i| Fr::from(i * 37 % 256)).collect();
+        let proof = logup.prove(&values);
+        assert_eq!(logup.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    fn test_xor() {
+        // the full 8-bit XOR table, 2^16 entries
+        let logup = LogUp::<Fr, Ideal>::new(&xor_table(8), 5, &mut test_rng());
+        let values: Vec<Fr> = (0..32u64)
+            .map(|i| {
+                let (a, b) = (i * 41 % 256, (i * 97 + 13) % 256);
+                xor_entry(a, b, a ^ b, 8)
+            })
+            .collect();
+        let proof = logup.prove(&values);
+        assert_eq!(logup.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    fn test_repeated_lookups() {
+        // a single entry looked up 16 times
+        let logup = LogUp::<Fr, Ideal>::new(&range_table(4), 4, &mut test_rng());
+        let proof = logup.prove(&[Fr::from(9); 16]);
+        assert_eq!(logup.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    fn test_value_out_of_range() {
+        let logup = LogUp::<Fr, Ideal>::new(&range_table(4), 3, &mut test_rng());
+        let mut values = vec![Fr::from(2); 8];
+        values[5] = Fr::from(16);
+        // the best the prover can do is to count the values in the table
+        let mut counts = vec![Fr::ZERO; 16];
+        counts[2] = Fr::from(7);
+        let proof = logup.prove_with_multiplicities(values.clone(), counts.clone());
+        assert!(logup.verify(&proof).is_err());
+        // or to account for the missing value somewhere else
+        counts[2] = Fr::from(8);
+        let proof = logup.prove_with_multiplicities(values, counts);
+        assert!(logup.verify(&proof).is_err());
+    }
+
+    #[test]
+    fn test_wrong_multiplicities() {
+        let logup = LogUp::<Fr, Ideal>::new(&range_table(4), 2, &mut test_rng());
+        let values: Vec<Fr> = [1, 1, 3, 4].into_iter().map(Fr::from).collect();
+        let mut counts = vec![Fr::ZERO; 16];
+        counts[1] = Fr::from(1);
+        counts[3] = Fr::from(2);
+        counts[4] = Fr::from(1);
+        let proof = logup.prove_with_multiplicities(values, counts);
+        assert!(logup.verify(&proof).is_err());
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let logup = LogUp::<Fr, Hyrax<G1Projective>>::new(&range_table(4), 2, &mut test_rng());
+        let values: Vec<Fr> = [1, 1, 3, 4].into_iter().map(Fr::from).collect();
+        let mut proof = logup.prove(&values);
+        proof.evaluations[2] += Fr::ONE;
+        assert!(logup.verify(&proof).is_err());
+
+        let mut proof = logup.prove(&values);
+        proof.sum += Fr::ONE;
+        assert!(logup.verify(&proof).is_err());
+    }
+}
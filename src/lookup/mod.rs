@@ -0,0 +1,72 @@
+//! Lookup arguments: the prover shows that every value of a committed vector
+//! f appears in a public table t.
+//!
+//! `plookup` works over a FFT domain with KZG commitments, as in PLONK: f ∪ t
+//! sorted by t has the same successive differences as t, checked with a grand
+//! product. `logup` works with multilinear polynomials and the sumcheck: f is
+//! in t iff sum_x 1 / (α - f(x)) = sum_y m(y) / (α - t(y)) for a random α,
+//! m(y) being the number of times t(y) is looked up.
+//!
+//! Tables of tuples, like XOR, are packed into single field elements.
+pub mod logup;
+pub mod plookup;
+
+pub use logup::{LogUp, LogUpProof};
+pub use plookup::{Plookup, PlookupProof};
+
+use ark_ff::Field;
+use std::collections::HashMap;
+
+/// Number of times every entry of the table is looked up, or None if a value
+/// is not in the table. Repeated entries count towards the first one.
+pub fn multiplicities<F: Field>(table: &[F], values: &[F]) -> Option<Vec<u64>> {
+    let mut positions = HashMap::new();
+    for (i, entry) in table.iter().enumerate().rev() {
+        positions.insert(*entry, i);
+    }
+    let mut counts = vec![0; table.len()];
+    for value in values {
+        counts[*positions.get(value)?] += 1;
+    }
+    Some(counts)
+}
+
+/// 0, 1, ..., 2^bits - 1
+pub fn range_table<F: Field>(bits: usize) -> Vec<F> {
+    (0..1u64 << bits).map(F::from).collect()
+}
+
+/// Entries (a, b, a XOR b) for a, b < 2^bits, packed by `xor_entry`
+pub fn xor_table<F: Field>(bits: usize) -> Vec<F> {
+    let size = 1u64 << bits;
+    (0..size)
+        .flat_map(|a| (0..size).map(move |b| xor_entry(a, b, a ^ b, bits)))
+        .collect()
+}
+
+/// a + 2^bits b + 2^(2 bits) c
+pub fn xor_entry<F: Field>(a: u64, b: u64, c: u64, bits: usize) -> F {
+    F::from(a) + F::from(b << bits) + F::from(c << (2 * bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_multiplicities() {
+        let table: Vec<Fr> = range_table(2);
+        let values: Vec<Fr> = [3, 1, 3, 3].into_iter().map(Fr::from).collect();
+        assert_eq!(multiplicities(&table, &values), Some(vec![0, 1, 0, 3]));
+        assert_eq!(multiplicities(&table, &[Fr::from(4)]), None);
+    }
+
+    #[test]
+    fn test_xor_table() {
+        let table: Vec<Fr> = xor_table(4);
+        assert_eq!(table.len(), 256);
+        assert!(table.contains(&xor_entry(0b1010, 0b0110, 0b1100, 4)));
+        assert!(!table.contains(&xor_entry(0b1010, 0b0110, 0b1110, 4)));
+    }
+}
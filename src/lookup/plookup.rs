@@ -0,0 +1,368 @@
+use super::multiplicities;
+use crate::grand_product::running_products;
+use crate::kzg::{KzgCommitment, KzgProof, Srs};
+use crate::transcript::Transcript;
+use crate::univariate::{divide_by_domain_vanishing, interpolate_over_domain, scale_argument};
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::collections::HashSet;
+
+/// Plookup (Gabizon, Williamson 2020). The domain H has n + 1 elements, the
+/// table t fills it and the n looked up values f the first n of them. The
+/// prover sorts s = f ∪ t by the order of t, splits it into h1 = s[..=n] and
+/// h2 = s[n..], and shows with the accumulator Z that
+///
+///   prod_i (1 + β)(γ + f_i)(γ(1 + β) + t_i + β t_{i+1})
+///     = prod_i (γ(1 + β) + s_i + β s_{i+1}),
+///
+/// i.e. every pair of neighbours in s is either a repeated value or a pair of
+/// neighbours in t. The quotient by Z_H of
+///
+///   L_0 (Z - 1) + α (X - ω^n)(Z(X) A(X) - Z(ωX) B(X))
+///     + α^2 L_n (h1(X) - h2(ωX)) + α^3 L_n (Z - 1)
+///
+/// is committed, and all polynomials are opened at ζ and ζω in one batch.
+pub struct Plookup<E: Pairing> {
+    srs: Srs<E>,
+    domain: GeneralEvaluationDomain<E::ScalarField>,
+    /// padded to n + 1 entries by repeating the last one
+    table: Vec<E::ScalarField>,
+    table_poly: DensePolynomial<E::ScalarField>,
+    table_commitment: KzgCommitment<E>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlookupProof<E: Pairing> {
+    pub values_commitment: KzgCommitment<E>,
+    h_commitments: Vec<KzgCommitment<E>>,
+    z_commitment: KzgCommitment<E>,
+    quotient_commitment: KzgCommitment<E>,
+    /// f, t, h1, h2, Z and the quotient at ζ and ζω
+    evaluations: Vec<Vec<E::ScalarField>>,
+    opening: KzgProof<E>,
+}
+
+// challenges of the protocol, with ζ drawn last
+struct Challenges<F> {
+    beta: F,
+    gamma: F,
+    alpha: F,
+}
+
+impl<E: Pairing> Plookup<E> {
+    /// Lookups of up to num_lookups values. The domain has the size n + 1 of
+    /// the smallest power of 2 above both num_lookups and the table, and the
+    /// SRS needs degree 2n and two points.
+    pub fn new(srs: Srs<E>, table: &[E::ScalarField], num_lookups: usize) -> Self {
+        assert!(!table.is_empty(), "empty table");
+        assert_eq!(
+            table.iter().collect::<HashSet<_>>().len(),
+            table.len(),
+            "repeated table entries"
+        );
+        let size = (num_lookups + 1).max(table.len()).next_power_of_two();
+        let domain = GeneralEvaluationDomain::new(size).expect("no FFT domain of this size");
+        assert!(
+            srs.max_degree() >= 2 * (size - 1),
+            "SRS too small for the table"
+        );
+        let mut table = table.to_vec();
+        table.resize(size, *table.last().unwrap());
+        let table_poly = interpolate_over_domain(&domain, &table);
+        Plookup {
+            table_commitment: srs.commit(&table_poly),
+            srs,
+            domain,
+            table,
+            table_poly,
+        }
+    }
+
+    /// n, the number of values looked up in a proof
+    pub fn num_lookups(&self) -> usize {
+        self.domain.size() - 1
+    }
+
+    pub fn table_commitment(&self) -> &KzgCommitment<E> {
+        &self.table_commitment
+    }
+
+    /// Panics if a value is not in the table
+    pub fn prove(&self, values: &[E::ScalarField]) -> PlookupProof<E> {
+        let n = self.num_lookups();
+        assert!(values.len() <= n, "too many values");
+        let mut values = values.to_vec();
+        values.resize(n, self.table[0]);
+        let counts = multiplicities(&self.table, &values).expect("value not in the table");
+        let mut sorted = vec![];
+        for (entry, count) in self.table.iter().zip(counts) {
+            sorted.extend(std::iter::repeat_n(*entry, count as usize + 1));
+        }
+        self.prove_sorted(values, sorted)
+    }
+
+    // sorted is f ∪ t sorted by t when the prover is honest
+    fn prove_sorted(
+        &self,
+        mut values: Vec<E::ScalarField>,
+        sorted: Vec<E::ScalarField>,
+    ) -> PlookupProof<E> {
+        let (domain, n) = (&self.domain, self.num_lookups());
+        let (h1, h2) = (sorted[..=n].to_vec(), sorted[n..].to_vec());
+        // f(ω^n) is never constrained
+        values.push(E::ScalarField::ZERO);
+        let f = interpolate_over_domain(domain, &values);
+        let values_commitment = self.srs.commit(&f);
+        let mut transcript = self.new_transcript(&values_commitment);
+
+        let h_polys = [&h1, &h2].map(|h| interpolate_over_domain(domain, h));
+        let h_commitments: Vec<_> = h_polys.iter().map(|h| self.srs.commit(h)).collect();
+        transcript.append_serializable(b"plookup_h", &h_commitments);
+
+        let beta = transcript.challenge_field::<E::ScalarField>(b"plookup_beta");
+        let gamma = transcript.challenge_field::<E::ScalarField>(b"plookup_gamma");
+        let shifted_gamma = gamma * (E::ScalarField::ONE + beta);
+        let t = &self.table;
+        let (numerators, denominators): (Vec<_>, Vec<_>) = (0..n)
+            .map(|i| {
+                (
+                    (E::ScalarField::ONE + beta)
+                        * (gamma + values[i])
+                        * (shifted_gamma + t[i] + beta * t[i + 1]),
+                    (shifted_gamma + h1[i] + beta * h1[i + 1])
+                        * (shifted_gamma + h2[i] + beta * h2[i + 1]),
+                )
+            })
+            .unzip();
+        let z = interpolate_over_domain(domain, &running_products(&numerators, &denominators));
+        let z_commitment = self.srs.commit(&z);
+        transcript.append_serializable(b"plookup_z", &z_commitment);
+
+        let alpha = transcript.challenge_field(b"plookup_alpha");
+        let challenges = Challenges { beta, gamma, alpha };
+        let quotient = self.quotient(&f, &h_polys, &z, &challenges);
+        let quotient_commitment = self.srs.commit(&quotient);
+        transcript.append_serializable(b"plookup_quotient", &quotient_commitment);
+
+        let zeta = transcript.challenge_field::<E::ScalarField>(b"plookup_zeta");
+        let points = [zeta, zeta * domain.group_gen()];
+        let polys = [
+            &f,
+            &self.table_poly,
+            &h_polys[0],
+            &h_polys[1],
+            &z,
+            &quotient,
+        ];
+        let (evaluations, opening) = self.srs.batch_open(&polys, &points, &mut transcript);
+        PlookupProof {
+            values_commitment,
+            h_commitments,
+            z_commitment,
+            quotient_commitment,
+            evaluations,
+            opening,
+        }
+    }
+
+    fn quotient(
+        &self,
+        f: &DensePolynomial<E::ScalarField>,
+        [h1, h2]: &[DensePolynomial<E::ScalarField>; 2],
+        z: &DensePolynomial<E::ScalarField>,
+        challenges: &Challenges<E::ScalarField>,
+    ) -> DensePolynomial<E::ScalarField> {
+        let Challenges { beta, gamma, alpha } = *challenges;
+        let domain = &self.domain;
+        let omega = domain.group_gen();
+        let one = E::ScalarField::ONE;
+        let constant = |c| DensePolynomial::from_coefficients_vec(vec![c]);
+        let lagrange = |i| {
+            let mut evals = vec![E::ScalarField::ZERO; domain.size()];
+            evals[i] = one;
+            interpolate_over_domain(domain, &evals)
+        };
+        let (first, last) = (lagrange(0), lagrange(domain.size() - 1));
+        let shifted_gamma = constant(gamma * (one + beta));
+        // g + p(X) + β p(ωX)
+        let difference = |p: &DensePolynomial<E::ScalarField>| {
+            let mut result = &shifted_gamma + p;
+            result += (beta, &scale_argument(p, omega));
+            result
+        };
+
+        let identity = &(&(f + &constant(gamma)) * &difference(&self.table_poly)) * (one + beta);
+        let sorted = &difference(h1) * &difference(h2);
+        let permutation = &(&(z * &identity) - &(&scale_argument(z, omega) * &sorted))
+            * &DensePolynomial::from_coefficients_vec(vec![
+                -domain.element(domain.size() - 1),
+                one,
+            ]);
+        let z_minus_one = z - &constant(one);
+        let mut numerator = &first * &z_minus_one;
+        numerator += (alpha, &permutation);
+        numerator += (
+            alpha.square(),
+            &(&last * &(h1 - &scale_argument(h2, omega))),
+        );
+        numerator += (alpha.square() * alpha, &(&last * &z_minus_one));
+        let (quotient, _) = divide_by_domain_vanishing(&numerator, domain);
+        quotient
+    }
+
+    pub fn verify(&self, proof: &PlookupProof<E>) -> Result<(), String> {
+        if proof.h_commitments.len() != 2
+            || proof.evaluations.len() != 6
+            || proof.evaluations.iter().any(|e| e.len() != 2)
+        {
+            return Err("malformed proof".to_string());
+        }
+        let mut transcript = self.new_transcript(&proof.values_commitment);
+        transcript.append_serializable(b"plookup_h", &proof.h_commitments);
+        let beta = transcript.challenge_field::<E::ScalarField>(b"plookup_beta");
+        let gamma = transcript.challenge_field::<E::ScalarField>(b"plookup_gamma");
+        transcript.append_serializable(b"plookup_z", &proof.z_commitment);
+        let alpha = transcript.challenge_field::<E::ScalarField>(b"plookup_alpha");
+        transcript.append_serializable(b"plookup_quotient", &proof.quotient_commitment);
+        let zeta = transcript.challenge_field::<E::ScalarField>(b"plookup_zeta");
+
+        let one = E::ScalarField::ONE;
+        let e = &proof.evaluations;
+        let [f, t, h1, h2, z, q] = [0, 1, 2, 3, 4, 5].map(|i| e[i][0]);
+        let [t_shifted, h1_shifted, h2_shifted, z_shifted] = [1, 2, 3, 4].map(|i| e[i][1]);
+        let lagrange = self.domain.evaluate_all_lagrange_coefficients(zeta);
+        let (first, last) = (lagrange[0], lagrange[self.num_lookups()]);
+        let shifted_gamma = gamma * (one + beta);
+        let identity = z * (one + beta) * (gamma + f) * (shifted_gamma + t + beta * t_shifted);
+        let sorted = z_shifted
+            * (shifted_gamma + h1 + beta * h1_shifted)
+            * (shifted_gamma + h2 + beta * h2_shifted);
+        let last_element = self.domain.element(self.num_lookups());
+        let numerator = first * (z - one)
+            + alpha * (zeta - last_element) * (identity - sorted)
+            + alpha.square() * last * (h1 - h2_shifted)
+            + alpha.square() * alpha * last * (z - one);
+        if numerator != q * self.domain.evaluate_vanishing_polynomial(zeta) {
+            return Err("constraints do not hold at ζ".to_string());
+        }
+
+        let commitments = [
+            proof.values_commitment,
+            self.table_commitment,
+            proof.h_commitments[0],
+            proof.h_commitments[1],
+            proof.z_commitment,
+            proof.quotient_commitment,
+        ];
+        let points = [zeta, zeta * self.domain.group_gen()];
+        if !self
+            .srs
+            .batch_verify(&commitments, &points, e, &proof.opening, &mut transcript)
+        {
+            return Err("invalid opening".to_string());
+        }
+        Ok(())
+    }
+
+    fn new_transcript(&self, values_commitment: &KzgCommitment<E>) -> Transcript {
+        let mut transcript = Transcript::new(b"plookup");
+        transcript.append_serializable(b"plookup_table", &self.table_commitment);
+        transcript.append_serializable(b"plookup_values", values_commitment);
+        transcript
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::{range_table, xor_entry, xor_table};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    fn setup(table: &[Fr], num_lookups: usize) -> Plookup<Bls12_381> {
+        let size = (num_lookups + 1).max(table.len()).next_power_of_two();
+        Plookup::new(
+            Srs::setup(2 * size - 2, 2, &mut test_rng()),
+            table,
+            num_lookups,
+        )
+    }
+
+    // values next to the equal table entry, those not in the table at the end
+    fn sort_by_table(table: &[Fr], values: &[Fr]) -> Vec<Fr> {
+        let mut sorted = vec![];
+        for entry in table {
+            sorted.push(*entry);
+            sorted.extend(values.iter().filter(|v| *v == entry));
+        }
+        sorted.extend(values.iter().filter(|v| !table.contains(v)));
+        sorted
+    }
+
+    #[test]
+    fn test_byte_range() {
+        let plookup = setup(&range_table(8), 100);
+        assert_eq!(plookup.num_lookups(), 255);
+        let values: Vec<Fr> = (0..100u64).map(|i| Fr::from(i * 37 % 256)).collect();
+        let proof = plookup.prove(&values);
+        assert_eq!(plookup.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    fn test_xor() {
+        let plookup = setup(&xor_table(4), 20);
+        let values: Vec<Fr> = (0..20u64)
+            .map(|i| {
+                let (a, b) = (i % 16, (i * 7 + 3) % 16);
+                xor_entry(a, b, a ^ b, 4)
+            })
+            .collect();
+        let proof = plookup.prove(&values);
+        assert_eq!(plookup.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "value not in the table")]
+    fn test_honest_prover_rejects_missing_value() {
+        let plookup = setup(&range_table(4), 8);
+        plookup.prove(&[Fr::from(16)]);
+    }
+
+    #[test]
+    fn test_value_out_of_range() {
+        // 16 is not in the table, sort it in anyway
+        let table: Vec<Fr> = range_table(4);
+        let plookup = setup(&table, 8);
+        let n = plookup.num_lookups();
+        let mut values = vec![Fr::from(3); n];
+        values[0] = Fr::from(16);
+        let sorted = sort_by_table(&plookup.table, &values);
+        let proof = plookup.prove_sorted(values, sorted);
+        assert!(plookup.verify(&proof).is_err());
+    }
+
+    #[test]
+    fn test_wrong_xor() {
+        let plookup = setup(&xor_table(4), 20);
+        let n = plookup.num_lookups();
+        let mut values = vec![xor_entry::<Fr>(1, 2, 3, 4); n];
+        // 5 ^ 6 = 3, but 5 + 6 = 11 is claimed
+        values[3] = xor_entry(5, 6, 11, 4);
+        let sorted = sort_by_table(&plookup.table, &values);
+        let proof = plookup.prove_sorted(values, sorted);
+        assert!(plookup.verify(&proof).is_err());
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let plookup = setup(&range_table(4), 8);
+        let values: Vec<Fr> = [1, 5, 5, 15].into_iter().map(Fr::from).collect();
+        let mut proof = plookup.prove(&values);
+        assert_eq!(plookup.verify(&proof), Ok(()));
+        proof.evaluations[2][1] += Fr::ONE;
+        assert!(plookup.verify(&proof).is_err());
+    }
+}
@@ -0,0 +1,688 @@
+//! Offline memory checking (Blum et al. 1991, Setty et al. 2018 "Spice"):
+//! proving that a log of memory accesses is consistent, i.e. every read
+//! returns the value last written at its address.
+//!
+//! The memory is a set of tuples (address, value, timestamp). The operation
+//! j, at time j + 1, reads the tuple (a_j, v_j, t_j) of its address and writes
+//! (a_j, w_j, j + 1) in its place, where w_j = v_j for a read. With init the
+//! initial tuples, at timestamp 0, and final the tuples left at the end, the
+//! log is consistent iff
+//!
+//!   init ∪ writes = reads ∪ final   as multisets, and t_j <= j for every j.
+//!
+//! Without the timestamp check a read could return a value from the future.
+//! The multisets are compared through the product of the fingerprints of
+//! their tuples, h_γ(a, v, t) - τ, where h_γ is the Reed-Solomon fingerprint of
+//! the message (t, v, a) at γ: two different multisets have the same product
+//! with probability at most n / |F| over τ, and two different tuples the same
+//! fingerprint with probability 2 / |F| over γ.
+//!
+//! `MemoryChecking` is the succinct version of the argument. The prover
+//! commits to the log and proves the four products with the GKR grand
+//! product, whose final claims are checked against openings of the
+//! commitments, and the identity and initial memory that the verifier
+//! evaluates itself. t_j <= j is shown by decomposing j - t_j into log m bits
+//! with a zero-check sumcheck.
+use crate::grand_product::{prove_product, reduce_product_claim, ProductProof};
+use crate::multilinear::{eq_evaluate, eq_table, identity_evaluate, mle, mle_evaluate};
+use crate::oracle::Oracle;
+use crate::pcs::MultilinearPcs;
+use crate::solomon_reed_code::ReedSolomon;
+use crate::sumcheck::{
+    evaluate_terms, prove_noninteractive, verify_noninteractive, SumOfProducts, VerifierState,
+};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::rand::RngCore;
+
+/// Fingerprint of the tuple (address, value, timestamp), its Reed-Solomon
+/// fingerprint at gamma minus tau
+pub fn fingerprint<F: Field>(address: F, value: F, timestamp: F, gamma: F, tau: F) -> F {
+    ReedSolomon::new(vec![timestamp, value, address]).univariate_fingerprint(&gamma) - tau
+}
+
+/// A tuple read from or written to the memory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Access<F: Field> {
+    pub address: usize,
+    pub value: F,
+    pub timestamp: u64,
+}
+
+impl<F: Field> Access<F> {
+    pub fn fingerprint(&self, gamma: F, tau: F) -> F {
+        fingerprint(
+            F::from(self.address as u64),
+            self.value,
+            F::from(self.timestamp),
+            gamma,
+            tau,
+        )
+    }
+}
+
+/// Product of the fingerprints of a multiset of tuples
+pub fn multiset_fingerprint<F: Field>(accesses: &[Access<F>], gamma: F, tau: F) -> F {
+    accesses.iter().map(|a| a.fingerprint(gamma, tau)).product()
+}
+
+/// Execution of a random access memory, recording the read and write logs
+#[derive(Clone, Debug)]
+pub struct MemoryTrace<F: Field> {
+    init: Vec<F>,
+    /// current value and timestamp of every address
+    memory: Vec<(F, u64)>,
+    reads: Vec<Access<F>>,
+    writes: Vec<Access<F>>,
+}
+
+impl<F: Field> MemoryTrace<F> {
+    pub fn new(init: Vec<F>) -> Self {
+        let memory = init.iter().map(|v| (*v, 0)).collect();
+        MemoryTrace {
+            init,
+            memory,
+            reads: vec![],
+            writes: vec![],
+        }
+    }
+
+    pub fn read(&mut self, address: usize) -> F {
+        self.access(address, None)
+    }
+
+    pub fn write(&mut self, address: usize, value: F) {
+        self.access(address, Some(value));
+    }
+
+    // reads the tuple at the address and writes it back with the new value, if
+    // any, and the current time
+    fn access(&mut self, address: usize, value: Option<F>) -> F {
+        let (old, timestamp) = self.memory[address];
+        let time = self.reads.len() as u64 + 1;
+        let new = value.unwrap_or(old);
+        self.reads.push(Access {
+            address,
+            value: old,
+            timestamp,
+        });
+        self.writes.push(Access {
+            address,
+            value: new,
+            timestamp: time,
+        });
+        self.memory[address] = (new, time);
+        old
+    }
+
+    pub fn num_operations(&self) -> usize {
+        self.reads.len()
+    }
+
+    pub fn reads(&self) -> &[Access<F>] {
+        &self.reads
+    }
+
+    pub fn writes(&self) -> &[Access<F>] {
+        &self.writes
+    }
+
+    /// Tuples at timestamp 0
+    pub fn initial_state(&self) -> Vec<Access<F>> {
+        self.init
+            .iter()
+            .enumerate()
+            .map(|(address, value)| Access {
+                address,
+                value: *value,
+                timestamp: 0,
+            })
+            .collect()
+    }
+
+    /// Tuples left in the memory after the last operation
+    pub fn final_state(&self) -> Vec<Access<F>> {
+        self.memory
+            .iter()
+            .enumerate()
+            .map(|(address, (value, timestamp))| Access {
+                address,
+                value: *value,
+                timestamp: *timestamp,
+            })
+            .collect()
+    }
+
+    /// Checks the log directly, comparing the fingerprints of init ∪ writes and
+    /// reads ∪ final and the timestamps of the reads
+    pub fn is_consistent(&self, gamma: F, tau: F) -> bool {
+        let left = multiset_fingerprint(&self.initial_state(), gamma, tau)
+            * multiset_fingerprint(&self.writes, gamma, tau);
+        let right = multiset_fingerprint(&self.reads, gamma, tau)
+            * multiset_fingerprint(&self.final_state(), gamma, tau);
+        let in_the_past = self
+            .reads
+            .iter()
+            .enumerate()
+            .all(|(j, read)| read.timestamp <= j as u64);
+        left == right && in_the_past
+    }
+}
+
+/// Argument that a committed log of 2^op_vars accesses to a memory with
+/// public initial values is consistent
+pub struct MemoryChecking<F: Field, P: MultilinearPcs<F>> {
+    init: Vec<F>,
+    memory_vars: usize,
+    op_vars: usize,
+    /// commits to the polynomials over the operations
+    op_pcs: P,
+    /// commits to the final state, over the addresses
+    memory_pcs: P,
+}
+
+pub struct MemoryCheckingProof<F: Field, P: MultilinearPcs<F>> {
+    pub address_commitment: P::Commitment,
+    pub read_value_commitment: P::Commitment,
+    pub write_value_commitment: P::Commitment,
+    read_timestamp_commitment: P::Commitment,
+    /// final values and timestamps
+    final_commitments: [P::Commitment; 2],
+    /// bits of j - t_j
+    bit_commitments: Vec<P::Commitment>,
+    /// products of the init, read, write and final fingerprints
+    products: [F; 4],
+    product_proofs: Vec<ProductProof<F>>,
+    /// a, v and t where the read product is reduced to
+    read_evals: [F; 3],
+    /// a and w where the write product is reduced to
+    write_evals: [F; 2],
+    /// value and timestamp where the final product is reduced to
+    final_evals: [F; 2],
+    range_round_polys: Vec<UnivariatePolynomial<F>>,
+    /// t and the bits at the end of the range sumcheck
+    range_evals: Vec<F>,
+    openings: Vec<P::Proof>,
+}
+
+// final check of the range sumcheck, the terms evaluated at eq(z, r), id(r)
+// and the values claimed by the prover
+struct RangeOracle<'a, F: Field> {
+    z: &'a [F],
+    terms: &'a [(F, Vec<usize>)],
+    range_evals: &'a [F],
+}
+
+impl<F: Field> Oracle<F> for RangeOracle<'_, F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        let mut values = vec![eq_evaluate(self.z, point), identity_evaluate(point)];
+        values.extend(self.range_evals);
+        evaluate_terms(self.terms, &values)
+    }
+}
+
+impl<F: Field, P: MultilinearPcs<F>> MemoryChecking<F, P> {
+    /// Memory with the given initial values, of a power of 2 size, accessed
+    /// 2^op_vars times
+    pub fn new<R: RngCore>(init: Vec<F>, op_vars: usize, rng: &mut R) -> Self {
+        assert!(
+            init.len() > 1 && init.len().is_power_of_two(),
+            "memory size must be a power of 2"
+        );
+        assert!(op_vars > 0, "at least 2 operations");
+        let memory_vars = init.len().trailing_zeros() as usize;
+        MemoryChecking {
+            init,
+            memory_vars,
+            op_vars,
+            op_pcs: P::setup(op_vars, rng),
+            memory_pcs: P::setup(memory_vars, rng),
+        }
+    }
+
+    pub fn num_operations(&self) -> usize {
+        1 << self.op_vars
+    }
+
+    /// Commits to the polynomials over the operations
+    pub fn op_pcs(&self) -> &P {
+        &self.op_pcs
+    }
+
+    /// Proves the log of the trace, padded with reads at address 0. Proves
+    /// invalid claims if the log is not consistent.
+    pub fn prove(&self, trace: &MemoryTrace<F>) -> MemoryCheckingProof<F, P> {
+        self.prove_with_read_values(trace).0
+    }
+
+    /// Same as `prove`, also returning the polynomial of the values read, with
+    /// its prover data for `op_pcs`, for arguments that open it elsewhere
+    pub fn prove_with_read_values(
+        &self,
+        trace: &MemoryTrace<F>,
+    ) -> (
+        MemoryCheckingProof<F, P>,
+        DenseMultilinearExtension<F>,
+        P::ProverData,
+    ) {
+        assert!(
+            trace.num_operations() <= self.num_operations(),
+            "too many operations"
+        );
+        assert_eq!(trace.init, self.init, "trace of a different memory");
+        let mut trace = trace.clone();
+        while trace.num_operations() < self.num_operations() {
+            trace.read(0);
+        }
+        let (k, m) = (self.op_vars, self.num_operations());
+
+        let field = |x: u64| F::from(x);
+        let column =
+            |log: &[Access<F>], f: &dyn Fn(&Access<F>) -> F| mle(k, log.iter().map(f).collect());
+        let addresses = column(&trace.reads, &|a| field(a.address as u64));
+        let read_values = column(&trace.reads, &|a| a.value);
+        let read_timestamps = column(&trace.reads, &|a| field(a.timestamp));
+        let write_values = column(&trace.writes, &|a| a.value);
+        let last = trace.final_state();
+        let final_values = mle(self.memory_vars, last.iter().map(|a| a.value).collect());
+        let final_timestamps = mle(
+            self.memory_vars,
+            last.iter().map(|a| field(a.timestamp)).collect(),
+        );
+        // j - t_j, wrapping around if the log reads from the future
+        let bits: Vec<_> = (0..k)
+            .map(|i| {
+                let bit = |(j, read): (usize, &Access<F>)| {
+                    field(((j as u64).wrapping_sub(read.timestamp) >> i) & 1)
+                };
+                mle(k, trace.reads.iter().enumerate().map(bit).collect())
+            })
+            .collect();
+
+        let (address_commitment, address_data) = self.op_pcs.commit(&addresses);
+        let (read_value_commitment, read_value_data) = self.op_pcs.commit(&read_values);
+        let (write_value_commitment, write_value_data) = self.op_pcs.commit(&write_values);
+        let (read_timestamp_commitment, read_timestamp_data) = self.op_pcs.commit(&read_timestamps);
+        let (final_value_commitment, final_value_data) = self.memory_pcs.commit(&final_values);
+        let (final_timestamp_commitment, final_timestamp_data) =
+            self.memory_pcs.commit(&final_timestamps);
+        let (bit_commitments, bit_data): (Vec<_>, Vec<_>) =
+            bits.iter().map(|b| self.op_pcs.commit(b)).unzip();
+        let final_commitments = [final_value_commitment, final_timestamp_commitment];
+        let mut transcript = self.new_transcript(
+            [
+                &address_commitment,
+                &read_value_commitment,
+                &write_value_commitment,
+                &read_timestamp_commitment,
+            ],
+            &final_commitments,
+            &bit_commitments,
+        );
+
+        // multiset equality
+        let gamma = transcript.challenge_field::<F>(b"memory_gamma");
+        let tau = transcript.challenge_field::<F>(b"memory_tau");
+        let fingerprints = |log: &[Access<F>]| -> Vec<F> {
+            log.iter().map(|a| a.fingerprint(gamma, tau)).collect()
+        };
+        let mut products = [F::ZERO; 4];
+        let mut product_proofs = vec![];
+        let mut points = vec![];
+        for (i, log) in [
+            trace.initial_state(),
+            trace.reads.clone(),
+            trace.writes.clone(),
+            last,
+        ]
+        .iter()
+        .enumerate()
+        {
+            let (product, proof) = prove_product(&fingerprints(log));
+            let num_vars = log.len().trailing_zeros() as usize;
+            let claim =
+                reduce_product_claim(num_vars, product, &proof).expect("honest product proof");
+            products[i] = product;
+            product_proofs.push(proof);
+            points.push(claim.point);
+        }
+        transcript.append_serializable(b"memory_products", &products[..]);
+
+        // t_j <= j
+        let lambda = transcript.challenge_field::<F>(b"memory_lambda");
+        let z = transcript.challenge_fields::<F>(b"memory_z", k);
+        let mut tables = vec![
+            eq_table(&z),
+            (0..m as u64).map(F::from).collect(),
+            read_timestamps.evaluations.clone(),
+        ];
+        tables.extend(bits.iter().map(|b| b.evaluations.clone()));
+        let (range_round_polys, prover) = prove_noninteractive(
+            SumOfProducts::new(k, tables, range_terms(lambda, k)),
+            &mut transcript,
+        );
+        let rz = prover.get_random_vars().to_vec();
+        let range_evals = prover.get_poly().final_evaluations()[2..].to_vec();
+        transcript.append_serializable(b"memory_range_evals", &range_evals);
+
+        let mut openings = vec![];
+        let mut opened = |pcs: &P, poly, data, point: &[F]| {
+            let (value, proof) = pcs.open(poly, data, point, &mut transcript);
+            openings.push(proof);
+            value
+        };
+        let op_pcs = &self.op_pcs;
+        let read_evals = [
+            opened(op_pcs, &addresses, &address_data, &points[1]),
+            opened(op_pcs, &read_values, &read_value_data, &points[1]),
+            opened(op_pcs, &read_timestamps, &read_timestamp_data, &points[1]),
+        ];
+        let write_evals = [
+            opened(op_pcs, &addresses, &address_data, &points[2]),
+            opened(op_pcs, &write_values, &write_value_data, &points[2]),
+        ];
+        let final_evals = [
+            opened(
+                &self.memory_pcs,
+                &final_values,
+                &final_value_data,
+                &points[3],
+            ),
+            opened(
+                &self.memory_pcs,
+                &final_timestamps,
+                &final_timestamp_data,
+                &points[3],
+            ),
+        ];
+        opened(op_pcs, &read_timestamps, &read_timestamp_data, &rz);
+        for (bit, data) in bits.iter().zip(&bit_data) {
+            opened(op_pcs, bit, data, &rz);
+        }
+
+        let proof = MemoryCheckingProof {
+            address_commitment,
+            read_value_commitment,
+            write_value_commitment,
+            read_timestamp_commitment,
+            final_commitments,
+            bit_commitments,
+            products,
+            product_proofs,
+            read_evals,
+            write_evals,
+            final_evals,
+            range_round_polys,
+            range_evals,
+            openings,
+        };
+        (proof, read_values, read_value_data)
+    }
+
+    pub fn verify(&self, proof: &MemoryCheckingProof<F, P>) -> Result<(), String> {
+        let k = self.op_vars;
+        if proof.bit_commitments.len() != k
+            || proof.product_proofs.len() != 4
+            || proof.range_evals.len() != k + 1
+            || proof.openings.len() != k + 8
+        {
+            return Err("malformed proof".to_string());
+        }
+        let mut transcript = self.new_transcript(
+            [
+                &proof.address_commitment,
+                &proof.read_value_commitment,
+                &proof.write_value_commitment,
+                &proof.read_timestamp_commitment,
+            ],
+            &proof.final_commitments,
+            &proof.bit_commitments,
+        );
+
+        let gamma = transcript.challenge_field::<F>(b"memory_gamma");
+        let tau = transcript.challenge_field::<F>(b"memory_tau");
+        let [init, reads, writes, last] = proof.products;
+        if init * writes != reads * last {
+            return Err("multisets differ".to_string());
+        }
+        let vars = [self.memory_vars, k, k, self.memory_vars];
+        let claims = vars
+            .iter()
+            .zip(&proof.products)
+            .zip(&proof.product_proofs)
+            .map(|((vars, product), proof)| reduce_product_claim(*vars, *product, proof))
+            .collect::<Result<Vec<_>, _>>()?;
+        transcript.append_serializable(b"memory_products", &proof.products[..]);
+
+        let [address, read_value, read_timestamp] = proof.read_evals;
+        let [write_address, write_value] = proof.write_evals;
+        let [final_value, final_timestamp] = proof.final_evals;
+        let (init_point, write_point, final_point) =
+            (&claims[0].point, &claims[2].point, &claims[3].point);
+        let checks = [
+            fingerprint(
+                identity_evaluate(init_point),
+                mle_evaluate(&self.init, init_point),
+                F::ZERO,
+                gamma,
+                tau,
+            ),
+            fingerprint(address, read_value, read_timestamp, gamma, tau),
+            fingerprint(
+                write_address,
+                write_value,
+                identity_evaluate(write_point) + F::ONE,
+                gamma,
+                tau,
+            ),
+            fingerprint(
+                identity_evaluate(final_point),
+                final_value,
+                final_timestamp,
+                gamma,
+                tau,
+            ),
+        ];
+        if checks
+            .iter()
+            .zip(&claims)
+            .any(|(check, claim)| *check != claim.value)
+        {
+            return Err("fingerprints do not match".to_string());
+        }
+
+        let lambda = transcript.challenge_field::<F>(b"memory_lambda");
+        let z = transcript.challenge_fields::<F>(b"memory_z", k);
+        let terms = range_terms(lambda, k);
+        let oracle = RangeOracle {
+            z: &z,
+            terms: &terms,
+            range_evals: &proof.range_evals,
+        };
+        let verifier = VerifierState::with_oracle(F::ZERO, k, oracle);
+        let rz = verify_noninteractive(verifier, &proof.range_round_polys, 3, &mut transcript)
+            .map_err(|err| format!("timestamp range sumcheck: {}", err))?;
+        transcript.append_serializable(b"memory_range_evals", &proof.range_evals);
+
+        let mut openings = proof.openings.iter();
+        let mut check_opening = |pcs: &P, commitment, point: &[F], value| {
+            let opening = openings.next().expect("number of openings checked above");
+            if pcs.verify(commitment, point, value, opening, &mut transcript) {
+                Ok(())
+            } else {
+                Err("invalid opening".to_string())
+            }
+        };
+        let (op_pcs, read_point) = (&self.op_pcs, &claims[1].point);
+        check_opening(op_pcs, &proof.address_commitment, read_point, address)?;
+        check_opening(op_pcs, &proof.read_value_commitment, read_point, read_value)?;
+        check_opening(
+            op_pcs,
+            &proof.read_timestamp_commitment,
+            read_point,
+            read_timestamp,
+        )?;
+        check_opening(
+            op_pcs,
+            &proof.address_commitment,
+            write_point,
+            write_address,
+        )?;
+        check_opening(
+            op_pcs,
+            &proof.write_value_commitment,
+            write_point,
+            write_value,
+        )?;
+        for (commitment, value) in proof.final_commitments.iter().zip(proof.final_evals) {
+            check_opening(&self.memory_pcs, commitment, final_point, value)?;
+        }
+        let range_commitments =
+            std::iter::once(&proof.read_timestamp_commitment).chain(&proof.bit_commitments);
+        for (commitment, value) in range_commitments.zip(&proof.range_evals) {
+            check_opening(op_pcs, commitment, &rz, *value)?;
+        }
+        Ok(())
+    }
+
+    fn new_transcript<C: ark_serialize::CanonicalSerialize>(
+        &self,
+        log_commitments: [&C; 4],
+        final_commitments: &[C],
+        bit_commitments: &[C],
+    ) -> Transcript {
+        let mut transcript = Transcript::new(b"memory_checking");
+        transcript.append_serializable(b"memory_init", &self.init);
+        for commitment in log_commitments {
+            transcript.append_serializable(b"memory_commitments", commitment);
+        }
+        transcript.append_serializable(b"memory_commitments", final_commitments);
+        transcript.append_serializable(b"memory_commitments", bit_commitments);
+        transcript
+    }
+}
+
+// eq(z, x) (sum_i λ^i (b_i(x)^2 - b_i(x)) + λ^k (id(x) - t(x) - sum_i 2^i b_i(x))),
+// over the tables [eq, id, t, b_0, ..., b_{k-1}], which vanishes on the
+// hypercube iff the bits are bits of j - t_j
+fn range_terms<F: Field>(lambda: F, k: usize) -> Vec<(F, Vec<usize>)> {
+    let powers: Vec<F> = (0..=k).map(|i| lambda.pow([i as u64])).collect();
+    let mut terms = vec![(powers[k], vec![0, 1]), (-powers[k], vec![0, 2])];
+    for i in 0..k {
+        let bit = 3 + i;
+        let two_i = F::from(1u64 << i);
+        terms.push((powers[i], vec![0, bit, bit]));
+        terms.push((-powers[i] - powers[k] * two_i, vec![0, bit]));
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hyrax::Hyrax;
+    use crate::pcs::IdealOracle;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    type Ideal = IdealOracle<DenseMultilinearExtension<Fr>>;
+
+    fn sample_trace() -> MemoryTrace<Fr> {
+        let mut trace = MemoryTrace::new((0..8u64).map(Fr::from).collect());
+        assert_eq!(trace.read(3), Fr::from(3));
+        trace.write(3, Fr::from(30));
+        trace.write(5, Fr::from(50));
+        assert_eq!(trace.read(3), Fr::from(30));
+        trace.write(3, Fr::from(31));
+        assert_eq!(trace.read(5), Fr::from(50));
+        assert_eq!(trace.read(3), Fr::from(31));
+        trace
+    }
+
+    // op 0 reads 7 from the write of op 1, then op 1 reads the value written
+    // back by op 0. The multisets match but the timestamps go backwards.
+    fn read_from_future() -> MemoryTrace<Fr> {
+        let mut trace = MemoryTrace::new(vec![Fr::from(0); 4]);
+        trace.read(1);
+        trace.write(1, Fr::from(7));
+        let access = |value: u64, timestamp| Access {
+            address: 1,
+            value: Fr::from(value),
+            timestamp,
+        };
+        trace.reads = vec![access(7, 2), access(7, 1)];
+        trace.writes = vec![access(7, 1), access(7, 2)];
+        trace.memory[1] = (Fr::from(0), 0);
+        trace
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let (gamma, tau) = (Fr::from(10), Fr::from(1));
+        let fp = fingerprint(Fr::from(1), Fr::from(2), Fr::from(3), gamma, tau);
+        assert_eq!(fp, Fr::from(122));
+    }
+
+    #[test]
+    fn test_is_consistent() {
+        let mut rng = test_rng();
+        let (gamma, tau) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let trace = sample_trace();
+        assert!(trace.is_consistent(gamma, tau));
+
+        let mut wrong = trace.clone();
+        wrong.reads[3].value = Fr::from(31);
+        assert!(!wrong.is_consistent(gamma, tau));
+
+        let future = read_from_future();
+        let left = multiset_fingerprint(&future.initial_state(), gamma, tau)
+            * multiset_fingerprint(&future.writes, gamma, tau);
+        let right = multiset_fingerprint(&future.reads, gamma, tau)
+            * multiset_fingerprint(&future.final_state(), gamma, tau);
+        assert_eq!(left, right);
+        assert!(!future.is_consistent(gamma, tau));
+    }
+
+    #[test]
+    fn test_memory_checking() {
+        let trace = sample_trace();
+        let checking = MemoryChecking::<Fr, Ideal>::new(trace.init.clone(), 3, &mut test_rng());
+        let proof = checking.prove(&trace);
+        assert_eq!(checking.verify(&proof), Ok(()));
+    }
+
+    #[test]
+    fn test_wrong_read() {
+        // read 30 and write it back where 31 was written
+        let mut trace = sample_trace();
+        trace.reads[6].value = Fr::from(30);
+        trace.writes[6].value = Fr::from(30);
+        trace.memory[3].0 = Fr::from(30);
+        let checking = MemoryChecking::<Fr, Ideal>::new(trace.init.clone(), 3, &mut test_rng());
+        let proof = checking.prove(&trace);
+        assert_eq!(checking.verify(&proof), Err("multisets differ".to_string()));
+    }
+
+    #[test]
+    fn test_read_from_future() {
+        let trace = read_from_future();
+        let checking = MemoryChecking::<Fr, Ideal>::new(trace.init.clone(), 1, &mut test_rng());
+        let proof = checking.prove(&trace);
+        let err = checking.verify(&proof).unwrap_err();
+        assert!(err.contains("timestamp"), "{}", err);
+    }
+
+    #[test]
+    fn test_hyrax() {
+        let trace = sample_trace();
+        let checking =
+            MemoryChecking::<Fr, Hyrax<G1Projective>>::new(trace.init.clone(), 3, &mut test_rng());
+        let proof = checking.prove(&trace);
+        assert_eq!(checking.verify(&proof), Ok(()));
+    }
+}
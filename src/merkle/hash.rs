@@ -0,0 +1,89 @@
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Debug;
+
+// domain separation between leaves and internal nodes
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Hash function used to build a Merkle tree over leaves of field elements
+pub trait MerkleHasher<F: Field> {
+    type Digest: Clone + Debug + PartialEq + Eq + CanonicalSerialize + CanonicalDeserialize;
+
+    fn hash_leaf(&self, leaf: &[F]) -> Self::Digest;
+
+    fn hash_nodes(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+}
+
+fn leaf_bytes<F: Field>(leaf: &[F]) -> Vec<u8> {
+    let mut bytes = vec![LEAF_PREFIX];
+    for element in leaf {
+        element
+            .serialize_compressed(&mut bytes)
+            .expect("serialization into a vector does not fail");
+    }
+    bytes
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Hasher;
+
+impl<F: Field> MerkleHasher<F> for Sha256Hasher {
+    type Digest = [u8; 32];
+
+    fn hash_leaf(&self, leaf: &[F]) -> [u8; 32] {
+        Sha256::digest(leaf_bytes(leaf)).into()
+    }
+
+    fn hash_nodes(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blake3Hasher;
+
+impl<F: Field> MerkleHasher<F> for Blake3Hasher {
+    type Digest = [u8; 32];
+
+    fn hash_leaf(&self, leaf: &[F]) -> [u8; 32] {
+        blake3::hash(&leaf_bytes(leaf)).into()
+    }
+
+    fn hash_nodes(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn check_hasher<H: MerkleHasher<Fr>>(hasher: H) {
+        let a = hasher.hash_leaf(&[Fr::from(1), Fr::from(2)]);
+        let b = hasher.hash_leaf(&[Fr::from(2), Fr::from(1)]);
+        assert_eq!(a, hasher.hash_leaf(&[Fr::from(1), Fr::from(2)]));
+        assert_ne!(a, b);
+        assert_ne!(hasher.hash_nodes(&a, &b), hasher.hash_nodes(&b, &a));
+    }
+
+    #[test]
+    fn test_sha256() {
+        check_hasher(Sha256Hasher);
+    }
+
+    #[test]
+    fn test_blake3() {
+        check_hasher(Blake3Hasher);
+    }
+}
@@ -0,0 +1,324 @@
+//! Merkle tree commitments to vectors of leaves, every leaf being a slice of
+//! field elements (a single value, or e.g. a column of a matrix).
+//!
+//! The tree is stored as a heap: node 1 is the root, the children of node k are
+//! 2k and 2k + 1, and leaf i is the node n + i.
+mod hash;
+mod poseidon;
+
+pub use hash::{Blake3Hasher, MerkleHasher, Sha256Hasher};
+pub use poseidon::Poseidon;
+
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+pub struct MerkleTree<F: Field, H: MerkleHasher<F>> {
+    hasher: H,
+    leaves: Vec<Vec<F>>,
+    nodes: Vec<H::Digest>,
+}
+
+/// Opening of several leaves. The authentication paths share the nodes that
+/// the verifier can compute by itself, so every sibling is sent only once.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiProof<F: Field, D: CanonicalSerialize + CanonicalDeserialize> {
+    /// opened positions, sorted and without duplicates
+    pub indices: Vec<usize>,
+    /// leaves at the opened positions
+    pub leaves: Vec<Vec<F>>,
+    siblings: Vec<D>,
+}
+
+impl<F: Field, D: CanonicalSerialize + CanonicalDeserialize> MultiProof<F, D> {
+    /// Returns the opened leaf at the given position
+    pub fn leaf(&self, index: usize) -> Option<&[F]> {
+        self.indices
+            .binary_search(&index)
+            .ok()
+            .map(|i| self.leaves[i].as_slice())
+    }
+}
+
+fn sorted_unique(indices: &[usize]) -> Vec<usize> {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+impl<F: Field, H: MerkleHasher<F>> MerkleTree<F, H> {
+    pub fn new(hasher: H, leaves: Vec<Vec<F>>) -> Self {
+        let n = leaves.len();
+        assert!(n.is_power_of_two(), "number of leaves must be a power of 2");
+
+        let leaf_hashes: Vec<H::Digest> = leaves.iter().map(|l| hasher.hash_leaf(l)).collect();
+        // node 0 is unused
+        let mut nodes = vec![leaf_hashes[0].clone(); n];
+        nodes.extend(leaf_hashes);
+        for k in (1..n).rev() {
+            nodes[k] = hasher.hash_nodes(&nodes[2 * k], &nodes[2 * k + 1]);
+        }
+
+        MerkleTree {
+            hasher,
+            leaves,
+            nodes,
+        }
+    }
+
+    /// Tree with a single field element per leaf
+    pub fn from_elements(hasher: H, elements: &[F]) -> Self {
+        Self::new(hasher, elements.iter().map(|e| vec![*e]).collect())
+    }
+
+    pub fn root(&self) -> H::Digest {
+        self.nodes[1].clone()
+    }
+
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn leaves(&self) -> &[Vec<F>] {
+        &self.leaves
+    }
+
+    pub fn open(&self, index: usize) -> MultiProof<F, H::Digest> {
+        self.open_batch(&[index])
+    }
+
+    pub fn open_batch(&self, indices: &[usize]) -> MultiProof<F, H::Digest> {
+        let n = self.num_leaves();
+        let indices = sorted_unique(indices);
+        assert!(indices.iter().all(|i| *i < n), "index out of range");
+
+        let mut siblings = vec![];
+        let mut level: Vec<usize> = indices.iter().map(|i| n + i).collect();
+        while level.first().is_some_and(|node| *node > 1) {
+            let mut parents = Vec::with_capacity(level.len());
+            let mut i = 0;
+            while i < level.len() {
+                let node = level[i];
+                if i + 1 < level.len() && level[i + 1] == node ^ 1 {
+                    i += 2;
+                } else {
+                    siblings.push(self.nodes[node ^ 1].clone());
+                    i += 1;
+                }
+                parents.push(node / 2);
+            }
+            level = parents;
+        }
+
+        MultiProof {
+            leaves: indices.iter().map(|i| self.leaves[*i].clone()).collect(),
+            indices,
+            siblings,
+        }
+    }
+
+    /// Verifies that the proof opens the given positions of the tree with the
+    /// given root and number of leaves
+    pub fn verify(
+        hasher: &H,
+        root: &H::Digest,
+        num_leaves: usize,
+        indices: &[usize],
+        proof: &MultiProof<F, H::Digest>,
+    ) -> bool {
+        let indices = sorted_unique(indices);
+        if !num_leaves.is_power_of_two()
+            || indices != proof.indices
+            || indices.len() != proof.leaves.len()
+            || indices.iter().any(|i| *i >= num_leaves)
+        {
+            return false;
+        }
+
+        let mut siblings = proof.siblings.iter();
+        let mut level: Vec<(usize, H::Digest)> = indices
+            .iter()
+            .zip(proof.leaves.iter())
+            .map(|(i, leaf)| (num_leaves + i, hasher.hash_leaf(leaf)))
+            .collect();
+        while level.first().is_some_and(|(node, _)| *node > 1) {
+            let mut parents = Vec::with_capacity(level.len());
+            let mut i = 0;
+            while i < level.len() {
+                let (node, digest) = &level[i];
+                let sibling = if i + 1 < level.len() && level[i + 1].0 == node ^ 1 {
+                    i += 2;
+                    level[i - 1].1.clone()
+                } else {
+                    i += 1;
+                    match siblings.next() {
+                        Some(sibling) => sibling.clone(),
+                        None => return false,
+                    }
+                };
+                let parent = if node % 2 == 0 {
+                    hasher.hash_nodes(digest, &sibling)
+                } else {
+                    hasher.hash_nodes(&sibling, digest)
+                };
+                parents.push((node / 2, parent));
+            }
+            level = parents;
+        }
+
+        siblings.next().is_none() && level.first().is_some_and(|(_, digest)| digest == root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn setup<H: MerkleHasher<Fr>>(hasher: H) -> MerkleTree<Fr, H> {
+        let mut rng = test_rng();
+        let elements: Vec<Fr> = (0..16).map(|_| Fr::rand(&mut rng)).collect();
+        MerkleTree::from_elements(hasher, &elements)
+    }
+
+    fn check_openings<H: MerkleHasher<Fr> + Clone>(hasher: H) {
+        let tree = setup(hasher.clone());
+        let root = tree.root();
+        for index in 0..16 {
+            let proof = tree.open(index);
+            assert_eq!(proof.siblings.len(), 4);
+            assert!(MerkleTree::verify(&hasher, &root, 16, &[index], &proof));
+        }
+
+        let indices = [3, 2, 9, 15, 3];
+        let proof = tree.open_batch(&indices);
+        assert!(MerkleTree::verify(&hasher, &root, 16, &indices, &proof));
+        assert_eq!(proof.leaf(9).unwrap(), tree.leaves()[9].as_slice());
+        assert!(proof.leaf(4).is_none());
+    }
+
+    #[test]
+    fn test_sha256_openings() {
+        check_openings(Sha256Hasher);
+    }
+
+    #[test]
+    fn test_blake3_openings() {
+        check_openings(Blake3Hasher);
+    }
+
+    #[test]
+    fn test_poseidon_openings() {
+        check_openings(Poseidon::<Fr>::new());
+    }
+
+    #[test]
+    fn test_batch_deduplicates_paths() {
+        let tree = setup(Sha256Hasher);
+        // 2 and 3 are siblings, so only one node per level above them is sent
+        let proof = tree.open_batch(&[2, 3]);
+        assert_eq!(proof.siblings.len(), 3);
+        // everything can be computed from the leaves
+        let proof = tree.open_batch(&(0..16).collect::<Vec<_>>());
+        assert!(proof.siblings.is_empty());
+        assert!(MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &(0..16).collect::<Vec<_>>(),
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_wrong_leaf() {
+        let tree = setup(Sha256Hasher);
+        let mut proof = tree.open_batch(&[1, 6]);
+        proof.leaves[1][0] += Fr::ONE;
+        assert!(!MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &[1, 6],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_wrong_indices() {
+        let tree = setup(Sha256Hasher);
+        let proof = tree.open_batch(&[1, 6]);
+        assert!(!MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &[1, 7],
+            &proof
+        ));
+        assert!(!MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &[1],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_wrong_sibling() {
+        let tree = setup(Sha256Hasher);
+        let mut proof = tree.open(5);
+        proof.siblings[2][0] ^= 1;
+        assert!(!MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &[5],
+            &proof
+        ));
+        proof.siblings.pop();
+        assert!(!MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            16,
+            &[5],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_single_leaf() {
+        let tree = MerkleTree::from_elements(Sha256Hasher, &[Fr::from(7)]);
+        let proof = tree.open(0);
+        assert!(MerkleTree::verify(
+            &Sha256Hasher,
+            &tree.root(),
+            1,
+            &[0],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let tree = setup(Poseidon::<Fr>::new());
+        let proof = tree.open_batch(&[0, 7, 8]);
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = MultiProof::<Fr, Fr>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(MerkleTree::verify(
+            &Poseidon::new(),
+            &tree.root(),
+            16,
+            &[0, 7, 8],
+            &decoded
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+use super::MerkleHasher;
+use ark_ff::{BigInteger, PrimeField};
+use sha2::{Digest, Sha256};
+
+// rate 2, capacity 1
+const WIDTH: usize = 3;
+// round numbers of the 128-bit security instance for t = 3 and alpha = 5
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+
+/// Poseidon sponge over a prime field. Hashing is native to the field, which
+/// makes it cheap to prove inside a circuit. The round constants are derived
+/// from SHA-256 and the MDS matrix is the Cauchy matrix 1 / (x_i + y_j).
+#[derive(Clone, Debug)]
+pub struct Poseidon<F: PrimeField> {
+    alpha: u64,
+    round_constants: Vec<[F; WIDTH]>,
+    mds: [[F; WIDTH]; WIDTH],
+}
+
+impl<F: PrimeField> Default for Poseidon<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> Poseidon<F> {
+    pub fn new() -> Self {
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|round| {
+                let mut constants = [F::ZERO; WIDTH];
+                for (i, constant) in constants.iter_mut().enumerate() {
+                    let mut hasher = Sha256::new();
+                    hasher.update(b"poseidon");
+                    hasher.update((round as u64).to_le_bytes());
+                    hasher.update((i as u64).to_le_bytes());
+                    *constant = F::from_le_bytes_mod_order(&hasher.finalize());
+                }
+                constants
+            })
+            .collect();
+
+        let mut mds = [[F::ZERO; WIDTH]; WIDTH];
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = F::from((i + WIDTH + j) as u64)
+                    .inverse()
+                    .expect("field too small for the MDS matrix");
+            }
+        }
+
+        Poseidon {
+            alpha: sbox_exponent::<F>(),
+            round_constants,
+            mds,
+        }
+    }
+
+    pub fn permute(&self, state: &mut [F; WIDTH]) {
+        let half_full = FULL_ROUNDS / 2;
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants) {
+                *s += c;
+            }
+
+            let is_full = round < half_full || round >= half_full + PARTIAL_ROUNDS;
+            if is_full {
+                for s in state.iter_mut() {
+                    *s = s.pow([self.alpha]);
+                }
+            } else {
+                state[0] = state[0].pow([self.alpha]);
+            }
+
+            let mut mixed = [F::ZERO; WIDTH];
+            for (m, row) in mixed.iter_mut().zip(self.mds.iter()) {
+                *m = row.iter().zip(state.iter()).map(|(a, s)| *a * s).sum();
+            }
+            *state = mixed;
+        }
+    }
+
+    /// Absorbs the inputs two at a time and squeezes one element. The domain
+    /// is placed in the capacity to separate the different uses of the sponge.
+    pub fn hash(&self, inputs: &[F], domain: F) -> F {
+        let mut state = [domain, F::ZERO, F::ZERO];
+        if inputs.is_empty() {
+            self.permute(&mut state);
+        }
+        for chunk in inputs.chunks(WIDTH - 1) {
+            for (s, input) in state[1..].iter_mut().zip(chunk) {
+                *s += input;
+            }
+            self.permute(&mut state);
+        }
+        state[1]
+    }
+}
+
+impl<F: PrimeField> MerkleHasher<F> for Poseidon<F> {
+    type Digest = F;
+
+    fn hash_leaf(&self, leaf: &[F]) -> F {
+        // even domains for leaves, including the length to avoid padding collisions
+        self.hash(leaf, F::from(2 * leaf.len() as u64))
+    }
+
+    fn hash_nodes(&self, left: &F, right: &F) -> F {
+        self.hash(&[*left, *right], F::ONE)
+    }
+}
+
+// smallest alpha such that x^alpha is a permutation, i.e. gcd(alpha, p - 1) = 1
+fn sbox_exponent<F: PrimeField>() -> u64 {
+    let mut p_minus_one = F::MODULUS;
+    p_minus_one.sub_with_borrow(&F::BigInt::from(1u64));
+    [3u64, 5, 7, 11, 13, 17, 19, 23]
+        .into_iter()
+        .find(|alpha| {
+            let remainder = p_minus_one.as_ref().iter().rev().fold(0u128, |acc, limb| {
+                ((acc << 64) + *limb as u128) % *alpha as u128
+            });
+            remainder != 0
+        })
+        .expect("no small s-box exponent for this field")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fields::F17;
+    use ark_ff::Field;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_sbox_exponent() {
+        // gcd(3, 16) = 1
+        assert_eq!(sbox_exponent::<F17>(), 3);
+        // the scalar field of BLS12-381 uses alpha = 5
+        assert_eq!(sbox_exponent::<Fr>(), 5);
+    }
+
+    #[test]
+    fn test_hash() {
+        let poseidon = Poseidon::<Fr>::new();
+        let a = poseidon.hash(&[Fr::from(1), Fr::from(2)], Fr::ZERO);
+        assert_eq!(a, poseidon.hash(&[Fr::from(1), Fr::from(2)], Fr::ZERO));
+        assert_ne!(a, poseidon.hash(&[Fr::from(2), Fr::from(1)], Fr::ZERO));
+        assert_ne!(a, poseidon.hash(&[Fr::from(1), Fr::from(2)], Fr::ONE));
+    }
+
+    #[test]
+    fn test_padding() {
+        let poseidon = Poseidon::<Fr>::new();
+        assert_ne!(
+            poseidon.hash_leaf(&[Fr::from(1)]),
+            poseidon.hash_leaf(&[Fr::from(1), Fr::ZERO])
+        );
+    }
+
+    #[test]
+    fn test_permutation_is_injective_on_small_field() {
+        let poseidon = Poseidon::<F17>::new();
+        let mut outputs = std::collections::HashSet::new();
+        for a in 0..17u64 {
+            for b in 0..17u64 {
+                let mut state = [F17::ZERO, F17::from(a), F17::from(b)];
+                poseidon.permute(&mut state);
+                outputs.insert(state);
+            }
+        }
+        assert_eq!(outputs.len(), 17 * 17);
+    }
+}
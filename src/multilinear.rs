@@ -0,0 +1,141 @@
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+
+/// Evaluations of eq(z, x) = prod_i (z_i x_i + (1 - z_i)(1 - x_i)) for every x
+/// in {0,1}^v, where the i-th variable is the i-th bit of the index
+pub fn eq_table<F: Field>(z: &[F]) -> Vec<F> {
+    let mut table = vec![F::ONE];
+    for z_i in z {
+        let mut next = vec![F::ZERO; table.len() * 2];
+        let mask = table.len();
+        for (x, value) in table.iter().enumerate() {
+            let high = *value * z_i;
+            next[x | mask] = high;
+            next[x] = *value - high;
+        }
+        table = next;
+    }
+    table
+}
+
+/// Evaluates eq(z, r) = prod_i (z_i r_i + (1 - z_i)(1 - r_i))
+pub fn eq_evaluate<F: Field>(z: &[F], r: &[F]) -> F {
+    assert_eq!(z.len(), r.len(), "points of different dimension");
+    z.iter().zip(r).fold(F::ONE, |acc, (z_i, r_i)| {
+        acc * (*z_i * r_i + (F::ONE - z_i) * (F::ONE - r_i))
+    })
+}
+
+/// Evaluates at the point the multilinear extension of the values over
+/// {0,1}^v
+pub fn mle_evaluate<F: Field>(values: &[F], point: &[F]) -> F {
+    eq_table(point)
+        .iter()
+        .zip(values)
+        .map(|(e, v)| *e * v)
+        .sum()
+}
+
+/// Multilinear extension of 2^num_vars evaluations over {0,1}^num_vars
+pub fn mle<F: Field>(num_vars: usize, evaluations: Vec<F>) -> DenseMultilinearExtension<F> {
+    DenseMultilinearExtension::from_evaluations_vec(num_vars, evaluations)
+}
+
+/// Evaluates the multilinear extension of the identity, sum_i 2^i x_i
+pub fn identity_evaluate<F: Field>(point: &[F]) -> F {
+    point.iter().rev().fold(F::ZERO, |acc, x| acc.double() + x)
+}
+
+/// <a, b>
+pub fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(x, y)| *x * y).sum()
+}
+
+/// Number of variables indexing the columns when the evaluations over
+/// {0,1}^num_vars are arranged row by row in a matrix M, as Hyrax and Ligero
+/// do, so that f(r) = L^T M R
+pub fn column_vars(num_vars: usize) -> usize {
+    num_vars.div_ceil(2)
+}
+
+/// L = eq(r_hi, ·) over the rows and R = eq(r_lo, ·) over the columns of the
+/// matrix of a polynomial in num_vars variables
+pub fn matrix_tensor<F: Field>(point: &[F], num_vars: usize) -> (Vec<F>, Vec<F>) {
+    assert_eq!(point.len(), num_vars, "point of a different dimension");
+    let (low, high) = point.split_at(column_vars(num_vars));
+    (eq_table(high), eq_table(low))
+}
+
+/// sum_i c_i M_i for the rows M_i, of the given length
+pub fn combine_rows<F: Field, R: AsRef<[F]>>(
+    rows: impl IntoIterator<Item = R>,
+    coefficients: &[F],
+    row_length: usize,
+) -> Vec<F> {
+    let mut combined = vec![F::ZERO; row_length];
+    for (row, c) in rows.into_iter().zip(coefficients) {
+        for (x, m) in combined.iter_mut().zip(row.as_ref()) {
+            *x += *c * m;
+        }
+    }
+    combined
+}
+
+/// Convert number into {0, 1}^domain, least significant bit first
+pub fn number_to_domain<F: Field>(number: usize, domain: usize) -> Vec<F> {
+    (0..domain)
+        .map(|j| {
+            if (number & (1 << j)) != 0 {
+                F::ONE
+            } else {
+                F::ZERO
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_eq_table_on_hypercube() {
+        let v = 3;
+        for i in 0..(1 << v) {
+            let table = eq_table::<Fr>(&number_to_domain(i, v));
+            for (j, value) in table.iter().enumerate() {
+                assert_eq!(*value == Fr::ONE, i == j);
+                assert_eq!(value.is_zero(), i != j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eq_table_matches_evaluate() {
+        let mut rng = test_rng();
+        let z: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let r: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let mle = DenseMultilinearExtension::from_evaluations_vec(4, eq_table(&z));
+        assert_eq!(mle.evaluate(&r).unwrap(), eq_evaluate(&z, &r));
+    }
+
+    #[test]
+    fn test_mle_evaluate() {
+        let mut rng = test_rng();
+        let values: Vec<Fr> = (0..8).map(|_| Fr::rand(&mut rng)).collect();
+        let point: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let mle = DenseMultilinearExtension::from_evaluations_slice(3, &values);
+        assert_eq!(mle_evaluate(&values, &point), mle.evaluate(&point).unwrap());
+
+        let identity: Vec<Fr> = (0..8u64).map(Fr::from).collect();
+        assert_eq!(identity_evaluate(&point), mle_evaluate(&identity, &point));
+        assert_eq!(
+            identity_evaluate::<Fr>(&number_to_domain(5, 3)),
+            Fr::from(5)
+        );
+    }
+}
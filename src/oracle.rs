@@ -0,0 +1,53 @@
+use ark_ff::Field;
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseMultilinearExtension, MultilinearExtension, Polynomial};
+
+/// Query access to a polynomial the verifier is not allowed to read directly.
+/// The polynomials themselves are ideal oracles, answering by evaluation.
+pub trait Oracle<F: Field> {
+    type Point: ?Sized;
+
+    fn query(&self, point: &Self::Point) -> F;
+}
+
+/// Ideal oracle: answers the queries by evaluating the polynomial itself
+pub struct PolynomialOracle<P>(P);
+
+impl<P> PolynomialOracle<P> {
+    pub fn new(poly: P) -> Self {
+        PolynomialOracle(poly)
+    }
+}
+
+impl<F: Field, P: Oracle<F>> Oracle<F> for PolynomialOracle<P> {
+    type Point = P::Point;
+
+    fn query(&self, point: &P::Point) -> F {
+        self.0.query(point)
+    }
+}
+
+impl<F: Field> Oracle<F> for DensePolynomial<F> {
+    type Point = F;
+
+    fn query(&self, point: &F) -> F {
+        self.evaluate(point)
+    }
+}
+
+impl<F: Field> Oracle<F> for SparsePolynomial<F, SparseTerm> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        self.evaluate(&point.to_vec())
+    }
+}
+
+impl<F: Field> Oracle<F> for DenseMultilinearExtension<F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        MultilinearExtension::evaluate(self, point).expect("point of a different dimension")
+    }
+}
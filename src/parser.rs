@@ -0,0 +1,415 @@
+//! Parser and pretty-printer for multivariate polynomials written as
+//! `2*x0^3 + x0*x2 + x1*x2`.
+//!
+//! A polynomial is a sum of terms separated by `+` or `-`, a term a product of
+//! factors separated by `*`, and a factor either a non-negative integer or a
+//! variable with an optional exponent, `x1^2`. Whitespace is ignored.
+use ark_ff::Field;
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm, Term};
+use ark_poly::DenseMVPolynomial;
+use std::fmt;
+
+/// Names of the variables of a polynomial
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VariableNames {
+    /// Prefix followed by the index of the variable: x0, x1, ...
+    Indexed(String),
+    /// Explicit name for every variable, e.g. x, y, z
+    Named(Vec<String>),
+}
+
+impl Default for VariableNames {
+    fn default() -> Self {
+        VariableNames::Indexed("x".to_string())
+    }
+}
+
+impl VariableNames {
+    fn index_of(&self, name: &str) -> Option<usize> {
+        match self {
+            VariableNames::Indexed(prefix) => {
+                let digits = name.strip_prefix(prefix.as_str())?;
+                if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                digits.parse().ok()
+            }
+            VariableNames::Named(names) => names.iter().position(|n| n == name),
+        }
+    }
+
+    fn name_of(&self, index: usize) -> String {
+        match self {
+            VariableNames::Indexed(prefix) => format!("{}{}", prefix, index),
+            VariableNames::Named(names) => names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("x{}", index)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a polynomial with variables x0, x1, ... The number of variables is
+/// the highest index used plus one.
+pub fn parse_polynomial<F: Field>(
+    input: &str,
+) -> Result<SparsePolynomial<F, SparseTerm>, ParseError> {
+    parse_polynomial_with(input, &VariableNames::default(), None)
+}
+
+/// Parses a polynomial with the given variable names. If `num_vars` is not
+/// given, it is inferred from the highest variable used, or from the number
+/// of names when they are given explicitly.
+pub fn parse_polynomial_with<F: Field>(
+    input: &str,
+    names: &VariableNames,
+    num_vars: Option<usize>,
+) -> Result<SparsePolynomial<F, SparseTerm>, ParseError> {
+    let mut parser = Parser {
+        chars: input.char_indices().collect(),
+        position: 0,
+        names,
+    };
+    let terms = parser.parse_sum()?;
+
+    let used_vars = terms
+        .iter()
+        .flat_map(|(_, factors)| factors.iter().map(|(var, _)| var + 1))
+        .max()
+        .unwrap_or(0);
+    let num_vars = match (num_vars, names) {
+        (Some(n), _) => n,
+        (None, VariableNames::Named(names)) => names.len(),
+        (None, VariableNames::Indexed(_)) => used_vars,
+    };
+    if used_vars > num_vars {
+        return Err(ParseError {
+            position: 0,
+            message: format!(
+                "polynomial uses {} variables, expected {}",
+                used_vars, num_vars
+            ),
+        });
+    }
+
+    let terms = terms
+        .into_iter()
+        .map(|(coeff, factors)| (coeff, SparseTerm::new(factors)))
+        .collect();
+    Ok(SparsePolynomial::from_coefficients_vec(num_vars, terms))
+}
+
+struct Parser<'a> {
+    chars: Vec<(usize, char)>,
+    position: usize,
+    names: &'a VariableNames,
+}
+
+type ParsedTerm<F> = (F, Vec<(usize, usize)>);
+
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        while let Some((_, c)) = self.chars.get(self.position) {
+            if !c.is_whitespace() {
+                return Some(*c);
+            }
+            self.position += 1;
+        }
+        None
+    }
+
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.position)
+            .map(|(i, _)| *i)
+            .unwrap_or_else(|| {
+                self.chars
+                    .last()
+                    .map(|(i, c)| i + c.len_utf8())
+                    .unwrap_or(0)
+            })
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.offset(),
+            message: message.to_string(),
+        })
+    }
+
+    fn parse_sum<F: Field>(&mut self) -> Result<Vec<ParsedTerm<F>>, ParseError> {
+        let mut terms = vec![];
+        let mut negative = false;
+        if self.peek() == Some('-') {
+            self.position += 1;
+            negative = true;
+        }
+        loop {
+            let (coeff, factors) = self.parse_term::<F>()?;
+            terms.push((if negative { -coeff } else { coeff }, factors));
+            match self.peek() {
+                Some('+') => negative = false,
+                Some('-') => negative = true,
+                None => return Ok(terms),
+                Some(_) => return self.error("expected '+' or '-'"),
+            }
+            self.position += 1;
+        }
+    }
+
+    fn parse_term<F: Field>(&mut self) -> Result<ParsedTerm<F>, ParseError> {
+        let mut coeff = F::ONE;
+        let mut factors: Vec<(usize, usize)> = vec![];
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_digit() => coeff *= self.parse_number::<F>(),
+                Some(c) if c.is_alphabetic() || c == '_' => {
+                    let var = self.parse_variable()?;
+                    let power = if self.peek() == Some('^') {
+                        self.position += 1;
+                        self.parse_exponent()?
+                    } else {
+                        1
+                    };
+                    match factors.iter_mut().find(|(v, _)| *v == var) {
+                        Some((_, p)) => *p += power,
+                        None => factors.push((var, power)),
+                    }
+                }
+                _ => return self.error("expected a number or a variable"),
+            }
+            if self.peek() != Some('*') {
+                factors.retain(|(_, power)| *power > 0);
+                return Ok((coeff, factors));
+            }
+            self.position += 1;
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+        let mut token = String::new();
+        while let Some((_, c)) = self.chars.get(self.position) {
+            if !f(*c) {
+                break;
+            }
+            token.push(*c);
+            self.position += 1;
+        }
+        token
+    }
+
+    fn parse_number<F: Field>(&mut self) -> F {
+        let ten = F::from(10u64);
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.chars().fold(F::ZERO, |acc, d| {
+            acc * ten + F::from(d.to_digit(10).unwrap() as u64)
+        })
+    }
+
+    fn parse_exponent(&mut self) -> Result<usize, ParseError> {
+        if !matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            return self.error("expected an exponent");
+        }
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        match digits.parse() {
+            Ok(exponent) => Ok(exponent),
+            Err(_) => self.error("exponent too large"),
+        }
+    }
+
+    fn parse_variable(&mut self) -> Result<usize, ParseError> {
+        let start = self.position;
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        match self.names.index_of(&name) {
+            Some(index) => Ok(index),
+            None => {
+                self.position = start;
+                self.error(&format!("unknown variable '{}'", name))
+            }
+        }
+    }
+}
+
+/// Formats a polynomial with the syntax accepted by the parser, highest degree
+/// terms first
+pub struct PolynomialDisplay<'a, F: Field> {
+    poly: &'a SparsePolynomial<F, SparseTerm>,
+    names: VariableNames,
+}
+
+pub fn display<F: Field>(poly: &SparsePolynomial<F, SparseTerm>) -> PolynomialDisplay<'_, F> {
+    display_with(poly, &VariableNames::default())
+}
+
+pub fn display_with<'a, F: Field>(
+    poly: &'a SparsePolynomial<F, SparseTerm>,
+    names: &VariableNames,
+) -> PolynomialDisplay<'a, F> {
+    PolynomialDisplay {
+        poly,
+        names: names.clone(),
+    }
+}
+
+impl<F: Field> fmt::Display for PolynomialDisplay<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<&(F, SparseTerm)> = self
+            .poly
+            .terms
+            .iter()
+            .rev()
+            .filter(|(coeff, _)| !coeff.is_zero())
+            .collect();
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (coeff, term)) in terms.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            let mut factors: Vec<String> = term
+                .iter()
+                .map(|(var, power)| match power {
+                    1 => self.names.name_of(*var),
+                    _ => format!("{}^{}", self.names.name_of(*var), power),
+                })
+                .collect();
+            if !coeff.is_one() || factors.is_empty() {
+                factors.insert(0, coeff.to_string());
+            }
+            write!(f, "{}", factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fields::F17;
+    use ark_test_curves::bls12_381::Fr;
+
+    /// examples and solutions taken from SumCheck example in
+    /// Thaler's Chp. 4
+    fn setup() -> SparsePolynomial<F17, SparseTerm> {
+        // 2*x_0^3 + x_0*x_2 + x_1*x_2
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (F17::from(2), SparseTerm::new(vec![(0, 3)])),
+                (F17::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (F17::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let poly = parse_polynomial::<F17>("2*x0^3 + x0*x2 + x1*x2").unwrap();
+        assert_eq!(poly, setup());
+        assert_eq!(poly.num_vars, 3);
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_signs() {
+        let poly = parse_polynomial::<F17>(" -x0 +2 * x0^3+x2*x0 -  3 + x1*x2 + 3 + x0 ").unwrap();
+        assert_eq!(poly, setup());
+    }
+
+    #[test]
+    fn test_parse_repeated_variables_and_coefficients() {
+        let poly = parse_polynomial::<F17>("x0^2*x0*2 + x2*3*x1*6").unwrap();
+        let should = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (F17::from(2), SparseTerm::new(vec![(0, 3)])),
+                (F17::from(18), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        );
+        assert_eq!(poly, should);
+    }
+
+    #[test]
+    fn test_parse_named_variables() {
+        let names = VariableNames::Named(vec!["x".into(), "y".into(), "z".into()]);
+        let poly = parse_polynomial_with::<F17>("2*x^3 + x*z + y*z", &names, None).unwrap();
+        assert_eq!(poly, setup());
+    }
+
+    #[test]
+    fn test_parse_explicit_num_vars() {
+        let poly = parse_polynomial_with::<F17>("x0 + x1", &VariableNames::default(), Some(4));
+        assert_eq!(poly.unwrap().num_vars, 4);
+
+        let poly = parse_polynomial_with::<F17>("x0 + x5", &VariableNames::default(), Some(4));
+        assert!(poly.is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_polynomial::<F17>("2*x0^3 + y").unwrap_err();
+        assert_eq!(err.position, 9);
+        assert!(parse_polynomial::<F17>("").is_err());
+        assert!(parse_polynomial::<F17>("x0 +").is_err());
+        assert!(parse_polynomial::<F17>("x0^").is_err());
+        assert!(parse_polynomial::<F17>("x0 x1").is_err());
+        assert!(parse_polynomial::<F17>("xa").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(display(&setup()).to_string(), "2*x0^3 + x0*x2 + x1*x2");
+        let names = VariableNames::Named(vec!["x".into(), "y".into(), "z".into()]);
+        assert_eq!(
+            display_with(&setup(), &names).to_string(),
+            "2*x^3 + x*z + y*z"
+        );
+        assert_eq!(
+            display(&SparsePolynomial::<F17, SparseTerm>::from_coefficients_vec(
+                2,
+                vec![]
+            ))
+            .to_string(),
+            "0"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            "2*x0^3 + x0*x2 + x1*x2",
+            "5 + x3",
+            "x0*x1*x2 + 7*x1^4 + 16",
+            "0",
+        ];
+        for input in inputs {
+            let poly = parse_polynomial::<F17>(input).unwrap();
+            let printed = display(&poly).to_string();
+            let reparsed =
+                parse_polynomial_with(&printed, &VariableNames::default(), Some(poly.num_vars));
+            assert_eq!(reparsed.unwrap(), poly);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_large_field() {
+        let poly = parse_polynomial::<Fr>("x0 - 2*x1^2").unwrap();
+        let printed = display(&poly).to_string();
+        assert_eq!(parse_polynomial::<Fr>(&printed).unwrap(), poly);
+    }
+}
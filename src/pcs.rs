@@ -0,0 +1,388 @@
+//! Common interface of the polynomial commitment schemes, so that protocols
+//! can be generic over them. A scheme commits to univariate polynomials
+//! (`Point = F`) or multilinear ones (`Point = [F]`).
+//!
+//! `IdealOracle` is the scheme the interactive protocols assume: the
+//! commitment is the polynomial itself, which the verifier evaluates.
+use crate::oracle::Oracle;
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+
+pub trait PolynomialCommitment<F: Field>: Sized {
+    type Polynomial;
+    type Point: ?Sized;
+    type Commitment: Clone + CanonicalSerialize + CanonicalDeserialize;
+    /// What the prover keeps from the commitment to open it later
+    type ProverData;
+    type Proof: Clone + CanonicalSerialize + CanonicalDeserialize;
+    type BatchProof: Clone;
+
+    /// Parameters for polynomials of degree < size, or in size variables for
+    /// multilinear schemes
+    fn setup<R: RngCore>(size: usize, rng: &mut R) -> Self;
+
+    fn commit(&self, poly: &Self::Polynomial) -> (Self::Commitment, Self::ProverData);
+
+    /// Opens the polynomial at the point, returning its evaluation and the proof
+    fn open(
+        &self,
+        poly: &Self::Polynomial,
+        data: &Self::ProverData,
+        point: &Self::Point,
+        transcript: &mut Transcript,
+    ) -> (F, Self::Proof);
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: F,
+        proof: &Self::Proof,
+        transcript: &mut Transcript,
+    ) -> bool;
+
+    /// Opens every polynomial at every point, values[i][j] being f_i(z_j)
+    fn batch_open(
+        &self,
+        polys: &[(&Self::Polynomial, &Self::ProverData)],
+        points: &[&Self::Point],
+        transcript: &mut Transcript,
+    ) -> (Vec<Vec<F>>, Self::BatchProof);
+
+    fn batch_verify(
+        &self,
+        commitments: &[Self::Commitment],
+        points: &[&Self::Point],
+        values: &[Vec<F>],
+        proof: &Self::BatchProof,
+        transcript: &mut Transcript,
+    ) -> bool;
+}
+
+/// Commitment scheme for univariate polynomials
+pub trait UnivariatePcs<F: Field>:
+    PolynomialCommitment<F, Polynomial = DensePolynomial<F>, Point = F>
+{
+}
+
+impl<F: Field, P> UnivariatePcs<F> for P where
+    P: PolynomialCommitment<F, Polynomial = DensePolynomial<F>, Point = F>
+{
+}
+
+/// Commitment scheme for multilinear polynomials
+pub trait MultilinearPcs<F: Field>:
+    PolynomialCommitment<F, Polynomial = DenseMultilinearExtension<F>, Point = [F]>
+{
+}
+
+impl<F: Field, P> MultilinearPcs<F> for P where
+    P: PolynomialCommitment<F, Polynomial = DenseMultilinearExtension<F>, Point = [F]>
+{
+}
+
+/// Batch opening with one proof per polynomial and point, in the order of
+/// values, for schemes without a dedicated batching
+pub fn open_separately<F: Field, P: PolynomialCommitment<F>>(
+    pcs: &P,
+    polys: &[(&P::Polynomial, &P::ProverData)],
+    points: &[&P::Point],
+    transcript: &mut Transcript,
+) -> (Vec<Vec<F>>, Vec<P::Proof>) {
+    let mut proofs = vec![];
+    let values = polys
+        .iter()
+        .map(|(poly, data)| {
+            points
+                .iter()
+                .map(|point| {
+                    let (value, proof) = pcs.open(poly, data, point, transcript);
+                    proofs.push(proof);
+                    value
+                })
+                .collect()
+        })
+        .collect();
+    (values, proofs)
+}
+
+pub fn verify_separately<F: Field, P: PolynomialCommitment<F>>(
+    pcs: &P,
+    commitments: &[P::Commitment],
+    points: &[&P::Point],
+    values: &[Vec<F>],
+    proofs: &[P::Proof],
+    transcript: &mut Transcript,
+) -> bool {
+    if values.len() != commitments.len()
+        || values.iter().any(|v| v.len() != points.len())
+        || proofs.len() != commitments.len() * points.len()
+    {
+        return false;
+    }
+    let mut proofs = proofs.iter();
+    commitments.iter().zip(values).all(|(commitment, evals)| {
+        points.iter().zip(evals).all(|(point, value)| {
+            pcs.verify(
+                commitment,
+                point,
+                *value,
+                proofs.next().unwrap(),
+                transcript,
+            )
+        })
+    })
+}
+
+/// The polynomial is sent as its own commitment, and openings are checked by
+/// evaluating it
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IdealOracle<P>(PhantomData<P>);
+
+impl<F: Field, P: Oracle<F> + Clone + CanonicalSerialize + CanonicalDeserialize>
+    PolynomialCommitment<F> for IdealOracle<P>
+{
+    type Polynomial = P;
+    type Point = P::Point;
+    type Commitment = P;
+    type ProverData = ();
+    type Proof = ();
+    type BatchProof = ();
+
+    fn setup<R: RngCore>(_size: usize, _rng: &mut R) -> Self {
+        IdealOracle(PhantomData)
+    }
+
+    fn commit(&self, poly: &P) -> (P, ()) {
+        (poly.clone(), ())
+    }
+
+    fn open(
+        &self,
+        poly: &P,
+        _data: &(),
+        point: &P::Point,
+        _transcript: &mut Transcript,
+    ) -> (F, ()) {
+        (poly.query(point), ())
+    }
+
+    fn verify(
+        &self,
+        commitment: &P,
+        point: &P::Point,
+        value: F,
+        _proof: &(),
+        _transcript: &mut Transcript,
+    ) -> bool {
+        commitment.query(point) == value
+    }
+
+    fn batch_open(
+        &self,
+        polys: &[(&P, &())],
+        points: &[&P::Point],
+        _transcript: &mut Transcript,
+    ) -> (Vec<Vec<F>>, ()) {
+        let values = polys
+            .iter()
+            .map(|(poly, _)| points.iter().map(|point| poly.query(point)).collect())
+            .collect();
+        (values, ())
+    }
+
+    fn batch_verify(
+        &self,
+        commitments: &[P],
+        points: &[&P::Point],
+        values: &[Vec<F>],
+        _proof: &(),
+        _transcript: &mut Transcript,
+    ) -> bool {
+        values.len() == commitments.len()
+            && commitments.iter().zip(values).all(|(commitment, evals)| {
+                evals.len() == points.len()
+                    && points
+                        .iter()
+                        .zip(evals)
+                        .all(|(point, value)| commitment.query(point) == *value)
+            })
+    }
+}
+
+/// Oracle answered by the prover with an opening of its committed polynomial,
+/// which is checked against the commitment. Querying a polynomial other than
+/// the committed one panics, as `VerifierState::verify_round` does on a wrong
+/// round polynomial.
+pub struct OpeningOracle<'a, F: Field, P: PolynomialCommitment<F>> {
+    pub pcs: &'a P,
+    pub commitment: P::Commitment,
+    pub poly: &'a P::Polynomial,
+    pub data: &'a P::ProverData,
+}
+
+impl<F: Field, P: PolynomialCommitment<F>> Oracle<F> for OpeningOracle<'_, F, P> {
+    type Point = P::Point;
+
+    fn query(&self, point: &P::Point) -> F {
+        let label = b"opening-oracle";
+        let (value, proof) =
+            self.pcs
+                .open(self.poly, self.data, point, &mut Transcript::new(label));
+        assert!(
+            self.pcs.verify(
+                &self.commitment,
+                point,
+                value,
+                &proof,
+                &mut Transcript::new(label)
+            ),
+            "invalid opening"
+        );
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fri::pcs::FriPcs;
+    use crate::hyrax::Hyrax;
+    use crate::ipa::IpaPcs;
+    use crate::kzg::{MultilinearSrs, Srs};
+    use crate::ligero::Ligero;
+    use crate::merkle::Sha256Hasher;
+    use crate::sumcheck::{ProverState, VerifierState};
+    use ark_ff::UniformRand;
+    use ark_poly::multivariate::{SparsePolynomial, SparseTerm, Term};
+    use ark_poly::{DenseMVPolynomial, DenseUVPolynomial, MultilinearExtension};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Projective};
+
+    fn check_univariate<P: UnivariatePcs<Fr>>() {
+        let mut rng = test_rng();
+        let pcs = P::setup(16, &mut rng);
+        let f = DensePolynomial::rand(15, &mut rng);
+        let g = DensePolynomial::rand(7, &mut rng);
+        let (cf, df) = pcs.commit(&f);
+        let (cg, dg) = pcs.commit(&g);
+        let (z1, z2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+
+        let (value, proof) = pcs.open(&f, &df, &z1, &mut Transcript::new(b"pcs"));
+        assert!(pcs.verify(&cf, &z1, value, &proof, &mut Transcript::new(b"pcs")));
+        assert!(!pcs.verify(&cg, &z1, value, &proof, &mut Transcript::new(b"pcs")));
+
+        let (values, proof) = pcs.batch_open(
+            &[(&f, &df), (&g, &dg)],
+            &[&z1, &z2],
+            &mut Transcript::new(b"pcs"),
+        );
+        assert_eq!(values[1][1], g.query(&z2));
+        let commitments = [cf, cg];
+        assert!(pcs.batch_verify(
+            &commitments,
+            &[&z1, &z2],
+            &values,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+        assert!(!pcs.batch_verify(
+            &commitments,
+            &[&z2, &z1],
+            &values,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+
+    fn check_multilinear<P: MultilinearPcs<Fr>>() {
+        let mut rng = test_rng();
+        let pcs = P::setup(5, &mut rng);
+        let f = DenseMultilinearExtension::rand(5, &mut rng);
+        let g = DenseMultilinearExtension::rand(5, &mut rng);
+        let (cf, df) = pcs.commit(&f);
+        let (cg, dg) = pcs.commit(&g);
+        let r1: Vec<Fr> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        let r2: Vec<Fr> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+
+        let (value, proof) = pcs.open(&f, &df, &r1, &mut Transcript::new(b"pcs"));
+        assert!(pcs.verify(&cf, &r1, value, &proof, &mut Transcript::new(b"pcs")));
+        assert!(!pcs.verify(&cg, &r1, value, &proof, &mut Transcript::new(b"pcs")));
+
+        let points = [r1.as_slice(), r2.as_slice()];
+        let (values, proof) = pcs.batch_open(
+            &[(&f, &df), (&g, &dg)],
+            &points,
+            &mut Transcript::new(b"pcs"),
+        );
+        assert_eq!(values[1][0], g.query(&r1));
+        let commitments = [cf, cg];
+        assert!(pcs.batch_verify(
+            &commitments,
+            &points,
+            &values,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+        let mut wrong = values.clone();
+        wrong[0][1] += Fr::ONE;
+        assert!(!pcs.batch_verify(
+            &commitments,
+            &points,
+            &wrong,
+            &proof,
+            &mut Transcript::new(b"pcs")
+        ));
+    }
+
+    #[test]
+    fn test_univariate_schemes() {
+        check_univariate::<IdealOracle<DensePolynomial<Fr>>>();
+        check_univariate::<FriPcs<Sha256Hasher>>();
+        check_univariate::<Srs<Bls12_381>>();
+        check_univariate::<IpaPcs<G1Projective>>();
+    }
+
+    #[test]
+    fn test_multilinear_schemes() {
+        check_multilinear::<IdealOracle<DenseMultilinearExtension<Fr>>>();
+        check_multilinear::<MultilinearSrs<Bls12_381>>();
+        check_multilinear::<Hyrax<G1Projective>>();
+        check_multilinear::<Ligero<Sha256Hasher>>();
+    }
+
+    #[test]
+    fn test_sumcheck_with_ideal_oracle() {
+        // 2*x_0^3 + x_0*x_2 + x_1*x_2, as in the sumcheck verifier tests
+        let poly = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fr::from(2), SparseTerm::new(vec![(0, 3)])),
+                (Fr::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fr::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+            ],
+        );
+        let pcs = IdealOracle::<SparsePolynomial<Fr, SparseTerm>>::setup(3, &mut test_rng());
+        let (commitment, data) = pcs.commit(&poly);
+        let oracle = OpeningOracle {
+            pcs: &pcs,
+            commitment,
+            poly: &poly,
+            data: &data,
+        };
+
+        let mut prover = ProverState::new(poly.clone());
+        let sum = prover.calculate_sum();
+        assert_eq!(sum, Fr::from(12));
+        let mut verifier = VerifierState::with_oracle(sum, 3, oracle);
+        for _ in 0..3 {
+            let r = verifier.verify_round(prover.calculate_round_poly());
+            prover.update_random_vars(r);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Pedersen vector commitments. A vector a is committed as
+//! sum_i a_i G_i + r H, which is binding as long as nobody knows a discrete
+//! logarithm relation between the generators, and hiding thanks to r.
+use crate::transcript::Transcript;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::CanonicalSerialize;
+
+/// Generators G_0, ..., G_{n-1} and H, derived by hashing a public label so
+/// that no one knows relations between them. H is hashed under its own label,
+/// so it is not a G_i of a larger set built from the same label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenGenerators<C: CurveGroup> {
+    generators: Vec<C::Affine>,
+    blinding: C::Affine,
+}
+
+impl<C: CurveGroup> PedersenGenerators<C> {
+    pub fn new(n: usize, label: &[u8]) -> Self {
+        PedersenGenerators {
+            generators: (0..n as u64)
+                .map(|i| hash_to_curve::<C>(label, i))
+                .collect(),
+            blinding: hash_to_curve::<C>(&[label, b"/blinding"].concat(), 0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    pub fn generators(&self) -> &[C::Affine] {
+        &self.generators
+    }
+
+    pub fn blinding_generator(&self) -> C::Affine {
+        self.blinding
+    }
+
+    /// sum_i a_i G_i + r H
+    pub fn commit(&self, values: &[C::ScalarField], blinding: C::ScalarField) -> C {
+        self.commit_unblinded(values) + self.blinding * blinding
+    }
+
+    /// sum_i a_i G_i, binding but not hiding
+    pub fn commit_unblinded(&self, values: &[C::ScalarField]) -> C {
+        assert!(values.len() <= self.len(), "more values than generators");
+        C::msm_unchecked(&self.generators[..values.len()], values)
+    }
+}
+
+/// Try-and-increment hash to the prime order subgroup: the label and index
+/// are hashed into candidate x coordinates until one is on the curve
+pub fn hash_to_curve<C: CurveGroup>(label: &[u8], index: u64) -> C::Affine {
+    let mut transcript = Transcript::new(b"hash-to-curve");
+    transcript.append_message(b"label", label);
+    transcript.append_message(b"index", &index.to_le_bytes());
+    let size = C::Affine::generator().compressed_size();
+    loop {
+        let bytes = transcript.challenge_bytes(b"candidate", size);
+        if let Some(point) = C::Affine::from_random_bytes(&bytes) {
+            let point = point.clear_cofactor();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    #[test]
+    fn test_generators() {
+        let gens = PedersenGenerators::<G1Projective>::new(8, b"test");
+        assert_eq!(gens, PedersenGenerators::new(8, b"test"));
+        assert_ne!(gens, PedersenGenerators::new(8, b"other"));
+        for (i, g) in gens.generators().iter().enumerate() {
+            assert!(g.is_on_curve() && g.is_in_correct_subgroup_assuming_on_curve());
+            assert!(gens.generators()[i + 1..].iter().all(|h| h != g));
+            assert_ne!(*g, gens.blinding_generator());
+        }
+    }
+
+    #[test]
+    fn test_blinding_generator_is_not_in_larger_sets() {
+        // otherwise a blinded commitment of size n is an unblinded one of size n + 1
+        let gens = PedersenGenerators::<G1Projective>::new(4, b"test");
+        let larger = PedersenGenerators::<G1Projective>::new(16, b"test");
+        assert_eq!(gens.generators(), &larger.generators()[..4]);
+        assert!(larger
+            .generators()
+            .iter()
+            .all(|g| *g != gens.blinding_generator()));
+    }
+
+    #[test]
+    fn test_commitment_is_homomorphic() {
+        let mut rng = test_rng();
+        let gens = PedersenGenerators::<G1Projective>::new(4, b"test");
+        let a: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let b: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let (r, s) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let sum: Vec<Fr> = a.iter().zip(&b).map(|(x, y)| *x + y).collect();
+        assert_eq!(
+            gens.commit(&a, r) + gens.commit(&b, s),
+            gens.commit(&sum, r + s)
+        );
+    }
+
+    #[test]
+    fn test_hiding() {
+        let gens = PedersenGenerators::<G1Projective>::new(2, b"test");
+        let a = [Fr::from(1), Fr::from(2)];
+        assert_ne!(gens.commit(&a, Fr::from(3)), gens.commit(&a, Fr::from(4)));
+        assert_eq!(gens.commit(&a, Fr::from(0)), gens.commit_unblinded(&a));
+    }
+}
@@ -0,0 +1,220 @@
+use ark_ff::Field;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Variable(usize);
+
+/// Selectors of the gate q_L a + q_R b + q_O c + q_M ab + q_C = 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Selectors<F: Field> {
+    pub q_l: F,
+    pub q_r: F,
+    pub q_o: F,
+    pub q_m: F,
+    pub q_c: F,
+}
+
+impl<F: Field> Selectors<F> {
+    pub fn zero() -> Self {
+        Selectors {
+            q_l: F::ZERO,
+            q_r: F::ZERO,
+            q_o: F::ZERO,
+            q_m: F::ZERO,
+            q_c: F::ZERO,
+        }
+    }
+
+    /// a + b = c
+    pub fn add() -> Self {
+        Selectors {
+            q_l: F::ONE,
+            q_r: F::ONE,
+            q_o: -F::ONE,
+            ..Self::zero()
+        }
+    }
+
+    /// ab = c
+    pub fn mul() -> Self {
+        Selectors {
+            q_o: -F::ONE,
+            q_m: F::ONE,
+            ..Self::zero()
+        }
+    }
+
+    pub fn evaluate(&self, a: F, b: F, c: F) -> F {
+        self.q_l * a + self.q_r * b + self.q_o * c + self.q_m * a * b + self.q_c
+    }
+
+    pub(crate) fn to_array(self) -> [F; 5] {
+        [self.q_l, self.q_r, self.q_o, self.q_m, self.q_c]
+    }
+}
+
+/// Circuit of PLONK gates together with the values of its variables. Every
+/// gate reads three variables, and the gates sharing a variable are tied
+/// by copy constraints. A public input x is the a wire of a gate with q_L = 1,
+/// checked against the term -x L_i(X) that the verifier adds to the gate
+/// constraint of that row.
+#[derive(Clone, Debug)]
+pub struct PlonkCircuit<F: Field> {
+    values: Vec<F>,
+    gates: Vec<Selectors<F>>,
+    wires: Vec<[Variable; 3]>,
+    public_gates: Vec<usize>,
+}
+
+impl<F: Field> Default for PlonkCircuit<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> PlonkCircuit<F> {
+    pub fn new() -> Self {
+        PlonkCircuit {
+            values: vec![],
+            gates: vec![],
+            wires: vec![],
+            public_gates: vec![],
+        }
+    }
+
+    pub fn alloc(&mut self, value: F) -> Variable {
+        self.values.push(value);
+        Variable(self.values.len() - 1)
+    }
+
+    pub fn alloc_public(&mut self, value: F) -> Variable {
+        let variable = self.alloc(value);
+        let selectors = Selectors {
+            q_l: F::ONE,
+            ..Selectors::zero()
+        };
+        self.public_gates.push(self.gates.len());
+        self.gate(variable, variable, variable, selectors);
+        variable
+    }
+
+    pub fn value(&self, variable: Variable) -> F {
+        self.values[variable.0]
+    }
+
+    pub fn gate(&mut self, a: Variable, b: Variable, c: Variable, selectors: Selectors<F>) {
+        self.gates.push(selectors);
+        self.wires.push([a, b, c]);
+    }
+
+    pub fn add(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc(self.value(a) + self.value(b));
+        self.gate(a, b, c, Selectors::add());
+        c
+    }
+
+    pub fn mul(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc(self.value(a) * self.value(b));
+        self.gate(a, b, c, Selectors::mul());
+        c
+    }
+
+    /// a + constant
+    pub fn add_constant(&mut self, a: Variable, constant: F) -> Variable {
+        let c = self.alloc(self.value(a) + constant);
+        let selectors = Selectors {
+            q_l: F::ONE,
+            q_o: -F::ONE,
+            q_c: constant,
+            ..Selectors::zero()
+        };
+        self.gate(a, a, c, selectors);
+        c
+    }
+
+    pub fn assert_equal(&mut self, a: Variable, b: Variable) {
+        let selectors = Selectors {
+            q_l: F::ONE,
+            q_r: -F::ONE,
+            ..Selectors::zero()
+        };
+        self.gate(a, b, a, selectors);
+    }
+
+    pub fn num_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn gates(&self) -> &[Selectors<F>] {
+        &self.gates
+    }
+
+    /// Variables read by every gate as (a, b, c), as indices into `values`
+    pub fn wires(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.wires.iter().map(|w| w.map(|v| v.0))
+    }
+
+    /// Gates holding the public inputs, in order
+    pub fn public_gates(&self) -> &[usize] {
+        &self.public_gates
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.public_gates
+            .iter()
+            .map(|gate| self.values[self.wires[*gate][0].0])
+            .collect()
+    }
+
+    /// Returns the first gate that does not hold with the given public inputs
+    pub fn is_satisfied(&self, public: &[F]) -> Result<(), usize> {
+        assert_eq!(
+            public.len(),
+            self.public_gates.len(),
+            "wrong number of public inputs"
+        );
+        let mut pi = vec![F::ZERO; self.num_gates()];
+        for (gate, x) in self.public_gates.iter().zip(public) {
+            pi[*gate] = -*x;
+        }
+        for (i, (selectors, [a, b, c])) in self.gates.iter().zip(&self.wires).enumerate() {
+            let [a, b, c] = [a, b, c].map(|v| self.values[v.0]);
+            if selectors.evaluate(a, b, c) + pi[i] != F::ZERO {
+                return Err(i);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_cubic() {
+        // x^3 + x + 5 = 35
+        let mut circuit = PlonkCircuit::new();
+        let out = circuit.alloc_public(Fr::from(35));
+        let x = circuit.alloc(Fr::from(3));
+        let x2 = circuit.mul(x, x);
+        let x3 = circuit.mul(x2, x);
+        let sum = circuit.add(x3, x);
+        let lhs = circuit.add_constant(sum, Fr::from(5));
+        circuit.assert_equal(lhs, out);
+
+        assert_eq!(circuit.num_gates(), 6);
+        assert_eq!(circuit.public_inputs(), vec![Fr::from(35)]);
+        assert_eq!(circuit.is_satisfied(&[Fr::from(35)]), Ok(()));
+        // the public input gate fails first
+        assert_eq!(circuit.is_satisfied(&[Fr::from(36)]), Err(0));
+    }
+}
@@ -0,0 +1,320 @@
+//! PLONK (Gabizon, Williamson, Ciobotaru 2019) with KZG commitments.
+//!
+//! The gates are placed on a FFT domain H = <ω> of size n. The wires a, b and
+//! c, the selectors and the public inputs satisfy
+//!
+//!   q_L a + q_R b + q_O c + q_M ab + q_C + PI = 0 on H,
+//!
+//! and the copy constraints say that the 3n wire values are invariant under a
+//! permutation σ. Labelling the wire (j, i) with k_j ω^i, the prover shows it
+//! with the accumulator Z(ω) = 1, Z(ω^{i+1}) = Z(ω^i) prod_j (w_j(ω^i) + β k_j ω^i + γ)
+//! / (w_j(ω^i) + β S_σj(ω^i) + γ), a grand product that comes back to 1.
+//! Both constraints and Z(ω) = 1 are combined with powers of α into the
+//! quotient t(X), computed over a coset of a domain of size 4n where Z_H does
+//! not vanish, and committed in three parts of n coefficients.
+//!
+//! After opening the wires and the permutation polynomials at a random ζ, the
+//! verifier forms by linearity the commitment to the linearisation r(X), which
+//! vanishes at ζ if t was honest, and one batched KZG opening at ζ checks r
+//! together with the sent evaluations. Z is opened separately at ζω.
+//!
+//! The verifier only needs the `VerifyingKey`: the commitments to the
+//! selector and permutation polynomials and the part of the SRS used to check
+//! the openings.
+//!
+//! There are no blinding factors, so the proofs are sound but not zero
+//! knowledge.
+mod circuit;
+mod prover;
+mod verifier;
+
+pub use circuit::{PlonkCircuit, Selectors, Variable};
+pub use verifier::verify;
+
+use crate::kzg::{KzgCommitment, KzgProof, Srs};
+use crate::transcript::Transcript;
+use crate::univariate::interpolate_over_domain;
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifyingKey<E: Pairing> {
+    /// size of the domain
+    pub n: usize,
+    /// rows of the public inputs
+    pub public_gates: Vec<usize>,
+    /// q_L, q_R, q_O, q_M and q_C
+    pub selector_commitments: Vec<KzgCommitment<E>>,
+    /// S_σ1, S_σ2 and S_σ3
+    pub sigma_commitments: Vec<KzgCommitment<E>>,
+    /// [1]_1, [1]_2 and [τ]_2, to check the openings
+    pub srs: Srs<E>,
+}
+
+/// Evaluations sent by the prover, at ζ except for Z
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlonkEvaluations<F: Field> {
+    pub a: F,
+    pub b: F,
+    pub c: F,
+    pub sigma_1: F,
+    pub sigma_2: F,
+    /// Z(ζω)
+    pub z_shifted: F,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlonkProof<E: Pairing> {
+    pub wire_commitments: Vec<KzgCommitment<E>>,
+    pub z_commitment: KzgCommitment<E>,
+    /// t_lo, t_mid and t_hi
+    pub quotient_commitments: Vec<KzgCommitment<E>>,
+    pub evaluations: PlonkEvaluations<E::ScalarField>,
+    /// batched opening of r, a, b, c, S_σ1 and S_σ2 at ζ
+    pub opening: KzgProof<E>,
+    /// opening of Z at ζω
+    pub shifted_opening: KzgProof<E>,
+}
+
+/// Preprocessed circuit: selector and permutation polynomials over H
+pub struct Plonk<E: Pairing> {
+    srs: Srs<E>,
+    domain: GeneralEvaluationDomain<E::ScalarField>,
+    wires: [Vec<usize>; 3],
+    selectors: [DensePolynomial<E::ScalarField>; 5],
+    /// evaluations of S_σj over H and their interpolations
+    sigma_evals: [Vec<E::ScalarField>; 3],
+    sigmas: [DensePolynomial<E::ScalarField>; 3],
+    vk: VerifyingKey<E>,
+}
+
+/// Coset representatives k_0 = 1, k_1 = g and k_2 = g^2 of the wire labels.
+/// The generator g of the multiplicative group is in neither H nor g^{-1} H.
+fn coset_shifts<F: FftField>() -> [F; 3] {
+    [F::ONE, F::GENERATOR, F::GENERATOR.square()]
+}
+
+impl<E: Pairing> Plonk<E> {
+    /// Preprocesses the circuit with a universal SRS of degree at least n - 1,
+    /// n being the number of gates rounded up to a power of 2
+    pub fn preprocess(srs: Srs<E>, circuit: &PlonkCircuit<E::ScalarField>) -> Self {
+        let n = circuit.num_gates().next_power_of_two().max(4);
+        let domain = GeneralEvaluationDomain::new(n).expect("no FFT domain of this size");
+        assert!(srs.max_degree() + 1 >= n, "SRS too small for the circuit");
+
+        // padding gates are all zero and read an extra variable, fixed to 0
+        let padding = circuit.num_variables();
+        let mut wires: [Vec<usize>; 3] = [vec![padding; n], vec![padding; n], vec![padding; n]];
+        for (i, gate_wires) in circuit.wires().enumerate() {
+            for (column, variable) in wires.iter_mut().zip(gate_wires) {
+                column[i] = variable;
+            }
+        }
+        let mut selector_evals = [(); 5].map(|_| vec![E::ScalarField::ZERO; n]);
+        for (i, gate) in circuit.gates().iter().enumerate() {
+            for (evals, q) in selector_evals.iter_mut().zip(gate.to_array()) {
+                evals[i] = q;
+            }
+        }
+        let selectors = selector_evals.map(|evals| interpolate_over_domain(&domain, &evals));
+
+        // σ sends every wire to the next one reading the same variable
+        let mut occurrences = vec![vec![]; padding + 1];
+        for (j, column) in wires.iter().enumerate() {
+            for (i, variable) in column.iter().enumerate() {
+                occurrences[*variable].push((j, i));
+            }
+        }
+        let shifts = coset_shifts::<E::ScalarField>();
+        let label = |(j, i): (usize, usize)| shifts[j] * domain.element(i);
+        let mut sigma_evals = [(); 3].map(|_| vec![E::ScalarField::ZERO; n]);
+        for cycle in occurrences.iter().filter(|cycle| !cycle.is_empty()) {
+            for (k, (j, i)) in cycle.iter().enumerate() {
+                sigma_evals[*j][*i] = label(cycle[(k + 1) % cycle.len()]);
+            }
+        }
+        let sigmas = [0, 1, 2].map(|j| interpolate_over_domain(&domain, &sigma_evals[j]));
+
+        let vk = VerifyingKey {
+            n,
+            public_gates: circuit.public_gates().to_vec(),
+            selector_commitments: selectors.iter().map(|q| srs.commit(q)).collect(),
+            sigma_commitments: sigmas.iter().map(|s| srs.commit(s)).collect(),
+            srs: srs.trim(0, 1),
+        };
+        Plonk {
+            srs,
+            domain,
+            wires,
+            selectors,
+            sigma_evals,
+            sigmas,
+            vk,
+        }
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        &self.vk
+    }
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    fn domain(&self) -> GeneralEvaluationDomain<E::ScalarField> {
+        GeneralEvaluationDomain::new(self.n).expect("no FFT domain of this size")
+    }
+
+    fn new_transcript(&self, public: &[E::ScalarField]) -> Transcript {
+        let mut transcript = Transcript::new(b"plonk");
+        transcript.append_serializable(b"plonk_vk", self);
+        transcript.append_serializable(b"plonk_public", public);
+        transcript
+    }
+
+    /// PI(ζ) = -sum_i x_i L_{g_i}(ζ) and L_0(ζ), the Lagrange polynomial of ω^0
+    fn public_and_first_lagrange(
+        &self,
+        public: &[E::ScalarField],
+        zeta: E::ScalarField,
+    ) -> (E::ScalarField, E::ScalarField) {
+        let lagrange = self.domain().evaluate_all_lagrange_coefficients(zeta);
+        let pi = self
+            .public_gates
+            .iter()
+            .zip(public)
+            .map(|(gate, x)| -lagrange[*gate] * x)
+            .sum();
+        (pi, lagrange[0])
+    }
+}
+
+/// Fiat-Shamir challenges of the protocol
+#[derive(Clone, Copy)]
+struct Challenges<F> {
+    beta: F,
+    gamma: F,
+    alpha: F,
+    zeta: F,
+}
+
+/// r(X) = constant + sum_i selectors_i q_i(X) + z Z(X) + sigma_3 S_σ3(X)
+///        + sum_i quotient_i t_i(X)
+struct LinearisationCoefficients<F> {
+    constant: F,
+    selectors: [F; 5],
+    z: F,
+    sigma_3: F,
+    quotient: [F; 3],
+}
+
+impl<E: Pairing> VerifyingKey<E> {
+    fn linearisation_coefficients(
+        &self,
+        public: &[E::ScalarField],
+        evals: &PlonkEvaluations<E::ScalarField>,
+        challenges: &Challenges<E::ScalarField>,
+    ) -> LinearisationCoefficients<E::ScalarField> {
+        let Challenges {
+            beta,
+            gamma,
+            alpha,
+            zeta,
+        } = *challenges;
+        let PlonkEvaluations {
+            a,
+            b,
+            c,
+            sigma_1,
+            sigma_2,
+            z_shifted,
+        } = *evals;
+        let (pi, first_lagrange) = self.public_and_first_lagrange(public, zeta);
+        let [_, k_1, k_2] = coset_shifts::<E::ScalarField>();
+        let identity = (a + beta * zeta + gamma)
+            * (b + beta * k_1 * zeta + gamma)
+            * (c + beta * k_2 * zeta + gamma);
+        let permuted = (a + beta * sigma_1 + gamma) * (b + beta * sigma_2 + gamma) * z_shifted;
+        let zeta_n = zeta.pow([self.n as u64]);
+        let vanishing = zeta_n - E::ScalarField::ONE;
+        LinearisationCoefficients {
+            constant: pi - alpha * permuted * (c + gamma) - alpha.square() * first_lagrange,
+            selectors: [a, b, c, a * b, E::ScalarField::ONE],
+            z: alpha * identity + alpha.square() * first_lagrange,
+            sigma_3: -alpha * beta * permuted,
+            quotient: [
+                -vanishing,
+                -vanishing * zeta_n,
+                -vanishing * zeta_n.square(),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    pub(super) fn cubic(x: u64, out: u64) -> PlonkCircuit<Fr> {
+        // x^3 + x + 5 = out
+        let mut circuit = PlonkCircuit::new();
+        let out = circuit.alloc_public(Fr::from(out));
+        let x = circuit.alloc(Fr::from(x));
+        let x2 = circuit.mul(x, x);
+        let x3 = circuit.mul(x2, x);
+        let sum = circuit.add(x3, x);
+        let lhs = circuit.add_constant(sum, Fr::from(5));
+        circuit.assert_equal(lhs, out);
+        circuit
+    }
+
+    pub(super) fn preprocess(circuit: &PlonkCircuit<Fr>) -> Plonk<Bls12_381> {
+        let n = circuit.num_gates().next_power_of_two().max(4);
+        Plonk::preprocess(Srs::setup(n - 1, 1, &mut test_rng()), circuit)
+    }
+
+    // the verifier gets the key through its serialization only
+    pub(super) fn verifying_key(plonk: &Plonk<Bls12_381>) -> VerifyingKey<Bls12_381> {
+        let mut bytes = vec![];
+        plonk
+            .verifying_key()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        let vk = VerifyingKey::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(&vk, plonk.verifying_key());
+        vk
+    }
+
+    #[test]
+    fn test_permutation_cycles() {
+        let circuit = cubic(3, 35);
+        let plonk = preprocess(&circuit);
+        // σ is a permutation of the 3n labels that keeps the wire values
+        let shifts = coset_shifts::<Fr>();
+        let mut labels = vec![];
+        let mut targets = vec![];
+        let mut values = circuit.values().to_vec();
+        values.push(Fr::ZERO);
+        for j in 0..3 {
+            for i in 0..plonk.domain.size() {
+                labels.push((
+                    shifts[j] * plonk.domain.element(i),
+                    values[plonk.wires[j][i]],
+                ));
+                targets.push(plonk.sigma_evals[j][i]);
+            }
+        }
+        for target in &targets {
+            let (_, value) = labels.iter().find(|(label, _)| label == target).unwrap();
+            let source = targets.iter().position(|t| t == target).unwrap();
+            assert_eq!(*value, labels[source].1);
+        }
+        let mut sorted_labels: Vec<Fr> = labels.iter().map(|(l, _)| *l).collect();
+        sorted_labels.sort();
+        targets.sort();
+        assert_eq!(sorted_labels, targets);
+    }
+}
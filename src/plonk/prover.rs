@@ -0,0 +1,212 @@
+use super::{coset_shifts, Challenges, Plonk, PlonkEvaluations, PlonkProof};
+use crate::grand_product::running_products;
+use crate::univariate::{interpolate_over_domain, scale_argument};
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+
+impl<E: Pairing> Plonk<E> {
+    /// Proves the circuit with the given values of its variables, returning
+    /// the public inputs and the proof
+    pub fn prove(&self, values: &[E::ScalarField]) -> (Vec<E::ScalarField>, PlonkProof<E>) {
+        let mut values = values.to_vec();
+        // value of the padding variable
+        values.push(E::ScalarField::ZERO);
+        let columns = self
+            .wires
+            .clone()
+            .map(|column| column.iter().map(|v| values[*v]).collect::<Vec<_>>());
+        let public: Vec<E::ScalarField> = self
+            .vk
+            .public_gates
+            .iter()
+            .map(|gate| columns[0][*gate])
+            .collect();
+        let proof = self.prove_columns(&public, columns);
+        (public, proof)
+    }
+
+    // the wire values need not respect the copy constraints, which lets the
+    // tests check that the permutation argument catches them
+    pub(super) fn prove_columns(
+        &self,
+        public: &[E::ScalarField],
+        columns: [Vec<E::ScalarField>; 3],
+    ) -> PlonkProof<E> {
+        let domain = &self.domain;
+        let n = domain.size();
+        let mut transcript = self.vk.new_transcript(public);
+
+        // round 1: wires
+        let [a, b, c] = [0, 1, 2].map(|j| interpolate_over_domain(domain, &columns[j]));
+        let wire_commitments: Vec<_> = [&a, &b, &c].iter().map(|w| self.srs.commit(w)).collect();
+        transcript.append_serializable(b"plonk_wires", &wire_commitments);
+
+        // round 2: permutation accumulator
+        let beta = transcript.challenge_field::<E::ScalarField>(b"plonk_beta");
+        let gamma = transcript.challenge_field::<E::ScalarField>(b"plonk_gamma");
+        let shifts = coset_shifts::<E::ScalarField>();
+        let (numerators, denominators): (Vec<_>, Vec<_>) = (0..n)
+            .map(|i| {
+                let x = domain.element(i);
+                (0..3).fold(
+                    (E::ScalarField::ONE, E::ScalarField::ONE),
+                    |(num, den), j| {
+                        (
+                            num * (columns[j][i] + beta * shifts[j] * x + gamma),
+                            den * (columns[j][i] + beta * self.sigma_evals[j][i] + gamma),
+                        )
+                    },
+                )
+            })
+            .unzip();
+        let accumulator = running_products(&numerators, &denominators);
+        let z = interpolate_over_domain(domain, &accumulator[..n]);
+        let z_commitment = self.srs.commit(&z);
+        transcript.append_serializable(b"plonk_z", &z_commitment);
+
+        // round 3: quotient
+        let alpha = transcript.challenge_field::<E::ScalarField>(b"plonk_alpha");
+        let challenges = Challenges {
+            beta,
+            gamma,
+            alpha,
+            zeta: E::ScalarField::ZERO,
+        };
+        let t = self.quotient(public, [&a, &b, &c], &z, &challenges);
+        let mut parts: Vec<DensePolynomial<E::ScalarField>> = t
+            .coeffs
+            .chunks(n)
+            .map(DensePolynomial::from_coefficients_slice)
+            .collect();
+        parts.resize(3, DensePolynomial::from_coefficients_vec(vec![]));
+        let quotient_commitments: Vec<_> = parts[..3].iter().map(|t| self.srs.commit(t)).collect();
+        transcript.append_serializable(b"plonk_quotient", &quotient_commitments);
+
+        // round 4: evaluations
+        let zeta = transcript.challenge_field::<E::ScalarField>(b"plonk_zeta");
+        let challenges = Challenges { zeta, ..challenges };
+        let evaluations = PlonkEvaluations {
+            a: a.evaluate(&zeta),
+            b: b.evaluate(&zeta),
+            c: c.evaluate(&zeta),
+            sigma_1: self.sigmas[0].evaluate(&zeta),
+            sigma_2: self.sigmas[1].evaluate(&zeta),
+            z_shifted: z.evaluate(&(zeta * domain.group_gen())),
+        };
+        transcript.append_serializable(b"plonk_evaluations", &evaluations);
+
+        // round 5: openings
+        let r = self.linearisation(public, &evaluations, &z, &parts[..3], &challenges);
+        let (_, opening) = self.srs.batch_open(
+            &[&r, &a, &b, &c, &self.sigmas[0], &self.sigmas[1]],
+            &[zeta],
+            &mut transcript,
+        );
+        let (_, shifted_opening) = self.srs.open(&z, zeta * domain.group_gen());
+
+        PlonkProof {
+            wire_commitments,
+            z_commitment,
+            quotient_commitments,
+            evaluations,
+            opening,
+            shifted_opening,
+        }
+    }
+
+    // t(X) = (gate(X) + α permutation(X) + α^2 (Z(X) - 1) L_0(X)) / Z_H(X),
+    // evaluated pointwise over a coset of size 4n and interpolated back
+    fn quotient(
+        &self,
+        public: &[E::ScalarField],
+        [a, b, c]: [&DensePolynomial<E::ScalarField>; 3],
+        z: &DensePolynomial<E::ScalarField>,
+        challenges: &Challenges<E::ScalarField>,
+    ) -> DensePolynomial<E::ScalarField> {
+        let domain = &self.domain;
+        let large = GeneralEvaluationDomain::<E::ScalarField>::new(4 * domain.size())
+            .expect("no FFT domain of this size");
+        let offset = E::ScalarField::GENERATOR;
+        let coset = large
+            .get_coset(offset)
+            .expect("the generator is not in the domain");
+        let on_coset = |poly: &DensePolynomial<E::ScalarField>| coset.fft(&poly.coeffs);
+
+        let mut pi = vec![E::ScalarField::ZERO; domain.size()];
+        for (gate, x) in self.vk.public_gates.iter().zip(public) {
+            pi[*gate] = -*x;
+        }
+        let mut first = vec![E::ScalarField::ZERO; domain.size()];
+        first[0] = E::ScalarField::ONE;
+        let pi = on_coset(&interpolate_over_domain(domain, &pi));
+        let first = on_coset(&interpolate_over_domain(domain, &first));
+        let z_shifted = on_coset(&scale_argument(z, domain.group_gen()));
+        let [a, b, c, z] = [a, b, c, z].map(on_coset);
+        let [q_l, q_r, q_o, q_m, q_c] = self.selectors.each_ref().map(on_coset);
+        let sigmas = self.sigmas.each_ref().map(on_coset);
+
+        let points: Vec<E::ScalarField> = (0..large.size())
+            .map(|i| offset * large.element(i))
+            .collect();
+        let mut vanishing_inverses: Vec<E::ScalarField> = points
+            .iter()
+            .map(|x| domain.evaluate_vanishing_polynomial(*x))
+            .collect();
+        ark_ff::batch_inversion(&mut vanishing_inverses);
+
+        let Challenges {
+            beta, gamma, alpha, ..
+        } = *challenges;
+        let shifts = coset_shifts::<E::ScalarField>();
+        let evals: Vec<E::ScalarField> = (0..large.size())
+            .map(|i| {
+                let x = points[i];
+                let gate = q_l[i] * a[i]
+                    + q_r[i] * b[i]
+                    + q_o[i] * c[i]
+                    + q_m[i] * a[i] * b[i]
+                    + q_c[i]
+                    + pi[i];
+                let identity = (a[i] + beta * shifts[0] * x + gamma)
+                    * (b[i] + beta * shifts[1] * x + gamma)
+                    * (c[i] + beta * shifts[2] * x + gamma)
+                    * z[i];
+                let permuted = (a[i] + beta * sigmas[0][i] + gamma)
+                    * (b[i] + beta * sigmas[1][i] + gamma)
+                    * (c[i] + beta * sigmas[2][i] + gamma)
+                    * z_shifted[i];
+                let start = (z[i] - E::ScalarField::ONE) * first[i];
+                (gate + alpha * (identity - permuted) + alpha.square() * start)
+                    * vanishing_inverses[i]
+            })
+            .collect();
+        DensePolynomial::from_coefficients_vec(coset.ifft(&evals))
+    }
+
+    // r(X), the combination of the constraints of t with every polynomial
+    // replaced by its evaluation at ζ except for one factor of each term
+    fn linearisation(
+        &self,
+        public: &[E::ScalarField],
+        evals: &PlonkEvaluations<E::ScalarField>,
+        z: &DensePolynomial<E::ScalarField>,
+        quotient_parts: &[DensePolynomial<E::ScalarField>],
+        challenges: &Challenges<E::ScalarField>,
+    ) -> DensePolynomial<E::ScalarField> {
+        let coefficients = self
+            .vk
+            .linearisation_coefficients(public, evals, challenges);
+        let mut r = DensePolynomial::from_coefficients_vec(vec![coefficients.constant]);
+        for (q, k) in self.selectors.iter().zip(coefficients.selectors) {
+            r += (k, q);
+        }
+        r += (coefficients.z, z);
+        r += (coefficients.sigma_3, &self.sigmas[2]);
+        for (t, k) in quotient_parts.iter().zip(coefficients.quotient) {
+            r += (k, t);
+        }
+        r
+    }
+}
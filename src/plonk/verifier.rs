@@ -0,0 +1,198 @@
+use super::{Challenges, PlonkProof, VerifyingKey};
+use crate::kzg::KzgCommitment;
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+
+/// Verifies the proof with the public inputs, in the order of
+/// `PlonkCircuit::public_inputs`
+pub fn verify<E: Pairing>(
+    vk: &VerifyingKey<E>,
+    public: &[E::ScalarField],
+    proof: &PlonkProof<E>,
+) -> Result<(), String> {
+    if public.len() != vk.public_gates.len() {
+        return Err("wrong number of public inputs".to_string());
+    }
+    if proof.wire_commitments.len() != 3 || proof.quotient_commitments.len() != 3 {
+        return Err("wrong number of commitments".to_string());
+    }
+    let mut transcript = vk.new_transcript(public);
+    transcript.append_serializable(b"plonk_wires", &proof.wire_commitments);
+    let beta = transcript.challenge_field(b"plonk_beta");
+    let gamma = transcript.challenge_field(b"plonk_gamma");
+    transcript.append_serializable(b"plonk_z", &proof.z_commitment);
+    let alpha = transcript.challenge_field(b"plonk_alpha");
+    transcript.append_serializable(b"plonk_quotient", &proof.quotient_commitments);
+    let zeta = transcript.challenge_field(b"plonk_zeta");
+    transcript.append_serializable(b"plonk_evaluations", &proof.evaluations);
+    let challenges = Challenges {
+        beta,
+        gamma,
+        alpha,
+        zeta,
+    };
+
+    // commitment to r(X) by linearity, r(ζ) = 0 iff the constraints hold at ζ
+    let coefficients = vk.linearisation_coefficients(public, &proof.evaluations, &challenges);
+    let mut r = E::G1::generator() * coefficients.constant;
+    for (q, k) in vk.selector_commitments.iter().zip(coefficients.selectors) {
+        r += q.0 * k;
+    }
+    r += proof.z_commitment.0 * coefficients.z;
+    r += vk.sigma_commitments[2].0 * coefficients.sigma_3;
+    for (t, k) in proof.quotient_commitments.iter().zip(coefficients.quotient) {
+        r += t.0 * k;
+    }
+
+    let evals = &proof.evaluations;
+    let commitments = [
+        KzgCommitment(r.into_affine()),
+        proof.wire_commitments[0],
+        proof.wire_commitments[1],
+        proof.wire_commitments[2],
+        vk.sigma_commitments[0],
+        vk.sigma_commitments[1],
+    ];
+    let values = [
+        E::ScalarField::ZERO,
+        evals.a,
+        evals.b,
+        evals.c,
+        evals.sigma_1,
+        evals.sigma_2,
+    ]
+    .map(|v| vec![v]);
+    if !vk.srs.batch_verify(
+        &commitments,
+        &[zeta],
+        &values,
+        &proof.opening,
+        &mut transcript,
+    ) {
+        return Err("invalid opening at ζ".to_string());
+    }
+    let shifted = zeta * vk.domain().group_gen();
+    if !vk.srs.verify(
+        &proof.z_commitment,
+        shifted,
+        evals.z_shifted,
+        &proof.shifted_opening,
+    ) {
+        return Err("invalid opening of Z at ζω".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{cubic, preprocess, verifying_key};
+    use super::super::{Plonk, PlonkCircuit};
+    use super::*;
+    use crate::kzg::Srs;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    #[test]
+    fn test_cubic() {
+        let circuit = cubic(3, 35);
+        let plonk = preprocess(&circuit);
+        let vk = verifying_key(&plonk);
+        let (public, proof) = plonk.prove(circuit.values());
+        assert_eq!(public, vec![Fr::from(35)]);
+        assert_eq!(verify(&vk, &public, &proof), Ok(()));
+        assert!(verify(&vk, &[Fr::from(36)], &proof).is_err());
+    }
+
+    #[test]
+    fn test_wrong_witness() {
+        let circuit = cubic(3, 35);
+        let plonk = preprocess(&circuit);
+        let vk = verifying_key(&plonk);
+        let mut values = circuit.values().to_vec();
+        // x^2 = 9 becomes 10
+        values[2] += Fr::ONE;
+        let (public, proof) = plonk.prove(&values);
+        assert!(verify(&vk, &public, &proof).is_err());
+    }
+
+    #[test]
+    fn test_broken_copy_constraint() {
+        // x + y = s and x * y = p, with x set to 2 in the first gate and to 5
+        // in the second: both gates hold but the wires disagree
+        let mut circuit = PlonkCircuit::new();
+        let x = circuit.alloc(Fr::from(2));
+        let y = circuit.alloc(Fr::from(3));
+        circuit.add(x, y);
+        circuit.mul(x, y);
+        let plonk = preprocess(&circuit);
+        let vk = verifying_key(&plonk);
+
+        let mut columns = plonk.wires.clone().map(|column| {
+            let mut values = circuit.values().to_vec();
+            values.push(Fr::ZERO);
+            column.iter().map(|v| values[*v]).collect::<Vec<_>>()
+        });
+        let proof = plonk.prove_columns(&[], columns.clone());
+        assert_eq!(verify(&vk, &[], &proof), Ok(()));
+
+        columns[0][1] = Fr::from(5);
+        columns[2][1] = Fr::from(15);
+        let proof = plonk.prove_columns(&[], columns);
+        assert!(verify(&vk, &[], &proof).is_err());
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let circuit = cubic(3, 35);
+        let plonk = preprocess(&circuit);
+        let vk = verifying_key(&plonk);
+        let (public, proof) = plonk.prove(circuit.values());
+
+        let mut tampered = proof.clone();
+        tampered.evaluations.a += Fr::ONE;
+        assert!(verify(&vk, &public, &tampered).is_err());
+        let mut tampered = proof.clone();
+        tampered.evaluations.z_shifted += Fr::ONE;
+        assert!(verify(&vk, &public, &tampered).is_err());
+        let mut tampered = proof.clone();
+        tampered.quotient_commitments.swap(0, 1);
+        assert!(verify(&vk, &public, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_larger_circuit() {
+        // x^(2^10) = y, with a public x and a public y
+        let mut circuit = PlonkCircuit::new();
+        let x = circuit.alloc_public(Fr::from(7));
+        let mut power = x;
+        for _ in 0..10 {
+            power = circuit.mul(power, power);
+        }
+        let y = circuit.alloc_public(circuit.value(power));
+        circuit.assert_equal(power, y);
+        assert_eq!(circuit.is_satisfied(&circuit.public_inputs()), Ok(()));
+
+        let plonk = Plonk::<Bls12_381>::preprocess(Srs::setup(15, 1, &mut test_rng()), &circuit);
+
+        let vk = verifying_key(&plonk);
+        let (public, proof) = plonk.prove(circuit.values());
+        assert_eq!(verify(&vk, &public, &proof), Ok(()));
+        assert!(verify(&vk, &[public[0], public[0]], &proof).is_err());
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let circuit = cubic(3, 35);
+        let plonk = preprocess(&circuit);
+        let vk = verifying_key(&plonk);
+        let (public, proof) = plonk.prove(circuit.values());
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = PlonkProof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(verify(&vk, &public, &decoded), Ok(()));
+    }
+}
@@ -4,10 +4,78 @@ use ark_poly::{
     univariate::SparsePolynomial as UnivariatePolynomial,
     DenseMVPolynomial,
 };
-use itertools::{Either, Itertools};
 use std::collections::HashSet;
 
-type Factor = (usize, usize);
+/// Fixes the given variables to the given values. The remaining variables keep
+/// their index and the number of variables of the polynomial does not change.
+pub fn partial_evaluate<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    values: &[(usize, F)],
+) -> SparsePolynomial<F, SparseTerm> {
+    let assignment = assignment(polynomial.num_vars, values);
+    let terms = evaluate_terms(polynomial, &assignment, |var| var);
+    SparsePolynomial::from_coefficients_vec(polynomial.num_vars, terms)
+}
+
+/// Fixes the given variables to the given values and reindexes the remaining
+/// ones to 0, 1, ..., keeping their order.
+pub fn partial_evaluate_compact<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    values: &[(usize, F)],
+) -> SparsePolynomial<F, SparseTerm> {
+    let assignment = assignment(polynomial.num_vars, values);
+    // new index of variable i = i - number of fixed variables before i
+    let mut reindex = Vec::with_capacity(polynomial.num_vars);
+    let mut next = 0;
+    for value in &assignment {
+        reindex.push(next);
+        if value.is_none() {
+            next += 1;
+        }
+    }
+
+    let terms = evaluate_terms(polynomial, &assignment, |var| reindex[var]);
+    SparsePolynomial::from_coefficients_vec(next, terms)
+}
+
+fn assignment<F: Field>(num_vars: usize, values: &[(usize, F)]) -> Vec<Option<F>> {
+    let mut assignment: Vec<Option<F>> = vec![None; num_vars];
+    for (var, value) in values {
+        assert!(
+            *var < num_vars,
+            "Invalid variable: index has to be in range [0 , .. , i-1]"
+        );
+        assert!(
+            assignment[*var].is_none(),
+            "variable {} assigned twice",
+            var
+        );
+        assignment[*var] = Some(*value);
+    }
+    assignment
+}
+
+fn evaluate_terms<F: Field>(
+    polynomial: &SparsePolynomial<F, SparseTerm>,
+    assignment: &[Option<F>],
+    reindex: impl Fn(usize) -> usize,
+) -> Vec<(F, SparseTerm)> {
+    polynomial
+        .terms
+        .iter()
+        .map(|(coeff, term)| {
+            let mut new_coeff = *coeff;
+            let mut factors = Vec::with_capacity(term.len());
+            for (var, power) in term.iter() {
+                match assignment[*var] {
+                    Some(value) => new_coeff *= value.pow([*power as u64]),
+                    None => factors.push((reindex(*var), *power)),
+                }
+            }
+            (new_coeff, SparseTerm::new(factors))
+        })
+        .collect()
+}
 
 /// Assigns a value to an specific variable of the polynomial
 pub fn assign_value<F: Field>(
@@ -15,41 +83,15 @@ pub fn assign_value<F: Field>(
     variable: usize,
     value: F,
 ) -> SparsePolynomial<F, SparseTerm> {
-    assert!(
-        variable < polynomial.num_vars,
-        "Invalid variable: index has to be in range [0 , .. , i-1]"
-    );
-
-    let terms = polynomial.terms;
-    let mut new_terms = vec![];
-    for (coeff, term) in terms {
-        let (matches, failure): (Vec<Factor>, Vec<Factor>) =
-            term.iter().partition_map(|r| match r {
-                (var, _power) if *var == variable => Either::Left(r),
-                (var, power) if *var > variable => Either::Right((*var - 1, *power)),
-                _ => Either::Right(*r),
-            });
-
-        let new_coeff: F = matches
-            .iter()
-            .fold(coeff, |acc, (_, i)| acc * value.pow([*i as u64]));
-        new_terms.push((new_coeff, SparseTerm::new(failure)));
-    }
-
-    // num_vars remain the same, otherwise variables must be reindex
-    SparsePolynomial::from_coefficients_vec(polynomial.num_vars - 1, new_terms)
+    // num_vars decreases by one, the following variables are reindexed
+    partial_evaluate_compact(&polynomial, &[(variable, value)])
 }
 
 fn assign_values<F: Field>(
     polynomial: &SparsePolynomial<F, SparseTerm>,
     values: Vec<(usize, F)>,
 ) -> SparsePolynomial<F, SparseTerm> {
-    let mut reduced_polynomial = polynomial.clone();
-    let rev_sorted_values = values.iter().sorted_by(|a, b| Ord::cmp(&b.0, &a.0));
-    for (variable, value) in rev_sorted_values {
-        reduced_polynomial = assign_value(reduced_polynomial, *variable, *value);
-    }
-    reduced_polynomial
+    partial_evaluate_compact(polynomial, &values)
 }
 
 pub fn reduced_to_univariate<F: Field>(
@@ -88,15 +130,9 @@ pub fn cast_mv_to_uv_polynomial<F: Field>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use crate::test_fields::F17;
     use ark_poly::multivariate::SparseTerm;
-    use ark_poly::DenseMVPolynomial;
-
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    pub struct F17Config;
-    pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+    use ark_poly::{DenseMVPolynomial, Polynomial};
 
     /// examples and solutions taken from SumCheck example in
     /// Thaler's Chp. 4
@@ -155,4 +191,76 @@ mod test {
         let poly_reduced = assign_values(&poly, values);
         assert_eq!(should, poly_reduced);
     }
+
+    #[test]
+    fn test_partial_evaluate_keeps_indexes() {
+        // 2*x_0^3 + x_0*x_2 + x_1*x_2, x_1 = 2
+        let poly = setup();
+        let should = SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (F17::from(2), SparseTerm::new(vec![(0, 3)])),
+                (F17::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (F17::from(2), SparseTerm::new(vec![(2, 1)])),
+            ],
+        );
+        assert_eq!(partial_evaluate(&poly, &[(1, F17::from(2))]), should);
+    }
+
+    #[test]
+    fn test_partial_evaluate_compact() {
+        // 2*x_0^3 + x_0*x_2 + x_1*x_2, x_1 = 2 => 2*x_0^3 + x_0*x_1 + 2*x_1
+        let poly = setup();
+        let should = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![
+                (F17::from(2), SparseTerm::new(vec![(0, 3)])),
+                (F17::from(1), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                (F17::from(2), SparseTerm::new(vec![(1, 1)])),
+            ],
+        );
+        assert_eq!(
+            partial_evaluate_compact(&poly, &[(1, F17::from(2))]),
+            should
+        );
+    }
+
+    #[test]
+    fn test_partial_evaluate_merges_and_drops_zeros() {
+        // x_0 = 1, x_1 = 16: 2 + x_2 - x_2 = 2
+        let poly = setup();
+        let reduced = partial_evaluate_compact(&poly, &[(0, F17::from(1)), (1, F17::from(16))]);
+        let should = SparsePolynomial::from_coefficients_vec(
+            1,
+            vec![(F17::from(2), SparseTerm::new(vec![]))],
+        );
+        assert_eq!(reduced, should);
+        assert_eq!(reduced.terms.len(), 1);
+    }
+
+    #[test]
+    fn test_partial_evaluate_any_order() {
+        let poly = setup();
+        let values = [(2, F17::from(5)), (0, F17::from(3))];
+        let reversed = [(0, F17::from(3)), (2, F17::from(5))];
+        assert_eq!(
+            partial_evaluate(&poly, &values),
+            partial_evaluate(&poly, &reversed)
+        );
+        let point = vec![F17::from(3), F17::from(7), F17::from(5)];
+        let reduced = partial_evaluate_compact(&poly, &values);
+        assert_eq!(reduced.evaluate(&vec![F17::from(7)]), poly.evaluate(&point));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_partial_evaluate_twice() {
+        partial_evaluate(&setup(), &[(1, F17::from(2)), (1, F17::from(3))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid variable")]
+    fn test_partial_evaluate_compact_out_of_range() {
+        partial_evaluate_compact(&setup(), &[(7, F17::from(2))]);
+    }
 }
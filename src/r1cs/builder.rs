@@ -0,0 +1,260 @@
+use super::{SparseMatrix, R1CS};
+use ark_ff::Field;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+    One,
+    Public(usize),
+    Witness(usize),
+}
+
+/// sum_i c_i v_i, the variables being repeated as needed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearCombination<F>(pub Vec<(Variable, F)>);
+
+impl<F: Field> LinearCombination<F> {
+    pub fn zero() -> Self {
+        LinearCombination(vec![])
+    }
+
+    pub fn constant(value: F) -> Self {
+        LinearCombination(vec![(Variable::One, value)])
+    }
+}
+
+impl<F: Field> From<Variable> for LinearCombination<F> {
+    fn from(variable: Variable) -> Self {
+        LinearCombination(vec![(variable, F::ONE)])
+    }
+}
+
+impl<F: Field, T: Into<LinearCombination<F>>> Add<T> for LinearCombination<F> {
+    type Output = Self;
+
+    fn add(mut self, other: T) -> Self {
+        self.0.extend(other.into().0);
+        self
+    }
+}
+
+impl<F: Field, T: Into<LinearCombination<F>>> Sub<T> for LinearCombination<F> {
+    type Output = Self;
+
+    fn sub(self, other: T) -> Self {
+        self + -other.into()
+    }
+}
+
+impl<F: Field> Neg for LinearCombination<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * -F::ONE
+    }
+}
+
+impl<F: Field> Mul<F> for LinearCombination<F> {
+    type Output = Self;
+
+    fn mul(self, scalar: F) -> Self {
+        LinearCombination(self.0.into_iter().map(|(v, c)| (v, c * scalar)).collect())
+    }
+}
+
+struct Constraint<F> {
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+    c: LinearCombination<F>,
+    label: String,
+}
+
+/// Allocates variables together with their values and enforces constraints
+/// on them, producing the system and a satisfying assignment
+pub struct R1CSBuilder<F> {
+    public: Vec<F>,
+    witness: Vec<F>,
+    constraints: Vec<Constraint<F>>,
+}
+
+impl<F: Field> Default for R1CSBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> R1CSBuilder<F> {
+    pub fn new() -> Self {
+        R1CSBuilder {
+            public: vec![],
+            witness: vec![],
+            constraints: vec![],
+        }
+    }
+
+    pub fn alloc_public(&mut self, value: F) -> Variable {
+        self.public.push(value);
+        Variable::Public(self.public.len() - 1)
+    }
+
+    pub fn alloc_witness(&mut self, value: F) -> Variable {
+        self.witness.push(value);
+        Variable::Witness(self.witness.len() - 1)
+    }
+
+    pub fn value(&self, variable: Variable) -> F {
+        match variable {
+            Variable::One => F::ONE,
+            Variable::Public(i) => self.public[i],
+            Variable::Witness(i) => self.witness[i],
+        }
+    }
+
+    pub fn evaluate(&self, lc: &LinearCombination<F>) -> F {
+        lc.0.iter().map(|(v, c)| self.value(*v) * c).sum()
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Adds the constraint a * b = c
+    pub fn enforce(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+        c: impl Into<LinearCombination<F>>,
+        label: &str,
+    ) {
+        self.constraints.push(Constraint {
+            a: a.into(),
+            b: b.into(),
+            c: c.into(),
+            label: label.to_string(),
+        });
+    }
+
+    /// New witness equal to a + b
+    pub fn add(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+    ) -> Variable {
+        let sum = a.into() + b.into();
+        let result = self.alloc_witness(self.evaluate(&sum));
+        self.enforce(sum, Variable::One, result, "add");
+        result
+    }
+
+    /// New witness equal to a * b
+    pub fn mul(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+    ) -> Variable {
+        let (a, b) = (a.into(), b.into());
+        let result = self.alloc_witness(self.evaluate(&a) * self.evaluate(&b));
+        self.enforce(a, b, result, "mul");
+        result
+    }
+
+    /// Enforces v in {0, 1}, with v * (1 - v) = 0
+    pub fn boolean(&mut self, v: impl Into<LinearCombination<F>>) {
+        let v = v.into();
+        let one_minus_v = LinearCombination::constant(F::ONE) - v.clone();
+        self.enforce(v, one_minus_v, LinearCombination::zero(), "boolean");
+    }
+
+    /// Enforces a = b, with (a - b) * 1 = 0
+    pub fn enforce_equal(
+        &mut self,
+        a: impl Into<LinearCombination<F>>,
+        b: impl Into<LinearCombination<F>>,
+    ) {
+        let difference = a.into() - b.into();
+        self.enforce(
+            difference,
+            Variable::One,
+            LinearCombination::zero(),
+            "equal",
+        );
+    }
+
+    /// The system with z = (1, io, w), the public values and the witness
+    pub fn build(self) -> (R1CS<F>, Vec<F>, Vec<F>) {
+        let num_public = self.public.len();
+        let num_cols = 1 + num_public + self.witness.len();
+        let column = |v: Variable| match v {
+            Variable::One => 0,
+            Variable::Public(i) => 1 + i,
+            Variable::Witness(i) => 1 + num_public + i,
+        };
+        let matrix = |select: fn(&Constraint<F>) -> &LinearCombination<F>| {
+            let rows = self
+                .constraints
+                .iter()
+                .map(|constraint| {
+                    // merge repeated variables
+                    let mut row: Vec<(usize, F)> = vec![];
+                    for (v, c) in select(constraint).0.iter() {
+                        match row.iter_mut().find(|(j, _)| *j == column(*v)) {
+                            Some((_, value)) => *value += c,
+                            None => row.push((column(*v), *c)),
+                        }
+                    }
+                    row.retain(|(_, c)| !c.is_zero());
+                    row
+                })
+                .collect();
+            SparseMatrix::new(num_cols, rows)
+        };
+        let (a, b, c) = (matrix(|c| &c.a), matrix(|c| &c.b), matrix(|c| &c.c));
+        let labels = self.constraints.iter().map(|c| c.label.clone()).collect();
+        let r1cs = R1CS::with_labels(a, b, c, num_public, labels);
+        (r1cs, self.public, self.witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r1cs::fixtures::cubic;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_cubic() {
+        let (r1cs, public, witness) = cubic::<Fr>(3, 35);
+        assert_eq!(r1cs.num_constraints(), 3);
+        assert_eq!(r1cs.num_variables(), 5);
+        assert_eq!(witness, vec![Fr::from(3), Fr::from(9), Fr::from(27)]);
+        assert!(r1cs.is_satisfied(&public, &witness).is_ok());
+
+        let (r1cs, public, witness) = cubic::<Fr>(3, 36);
+        let err = r1cs.is_satisfied(&public, &witness).unwrap_err();
+        assert_eq!((err.index, err.label.as_str()), (2, "equal"));
+    }
+
+    #[test]
+    fn test_gadgets() {
+        let mut builder = R1CSBuilder::<Fr>::new();
+        let a = builder.alloc_witness(Fr::from(1));
+        let b = builder.alloc_witness(Fr::from(0));
+        let c = builder.alloc_witness(Fr::from(2));
+        builder.boolean(a);
+        builder.boolean(b);
+        let sum = builder.add(a, c);
+        assert_eq!(builder.value(sum), Fr::from(3));
+        let doubled = builder.add(c, c);
+        builder.enforce_equal(LinearCombination::from(c) * Fr::from(2), doubled);
+        let (r1cs, public, witness) = builder.build();
+        assert!(public.is_empty());
+        assert!(r1cs.is_satisfied(&public, &witness).is_ok());
+        // c + c is merged into a single entry
+        assert_eq!(r1cs.a.rows()[3], vec![(3, Fr::from(2))]);
+
+        let mut witness = witness;
+        witness[1] = Fr::from(2);
+        let err = r1cs.is_satisfied(&public, &witness).unwrap_err();
+        assert_eq!((err.index, err.label.as_str()), (1, "boolean"));
+    }
+}
@@ -0,0 +1,31 @@
+//! Instances shared by the tests of the proof systems over R1CS
+use super::{LinearCombination, R1CSBuilder, R1CS};
+use ark_ff::Field;
+
+/// x^3 + x + 5 = out
+pub(crate) fn cubic<F: Field>(x: u64, out: u64) -> (R1CS<F>, Vec<F>, Vec<F>) {
+    let mut builder = R1CSBuilder::new();
+    let out = builder.alloc_public(F::from(out));
+    let x = builder.alloc_witness(F::from(x));
+    let x2 = builder.mul(x, x);
+    let x3 = builder.mul(x2, x);
+    let lhs = LinearCombination::from(x3) + x + LinearCombination::constant(F::from(5u64));
+    builder.enforce_equal(lhs, out);
+    builder.build()
+}
+
+/// x^(2^10) = y for x = 7, with a few boolean constraints on the side
+pub(crate) fn repeated_squaring<F: Field>() -> (R1CS<F>, Vec<F>, Vec<F>) {
+    let mut builder = R1CSBuilder::new();
+    let mut power = builder.alloc_witness(F::from(7u64));
+    for _ in 0..10 {
+        power = builder.mul(power, power);
+    }
+    let y = builder.alloc_public(builder.value(power));
+    builder.enforce_equal(power, y);
+    for bit in [0u64, 1, 1, 0] {
+        let b = builder.alloc_witness(F::from(bit));
+        builder.boolean(b);
+    }
+    builder.build()
+}
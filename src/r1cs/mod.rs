@@ -0,0 +1,336 @@
+//! Rank-1 constraint systems: a vector z = (1, io, w) satisfies the system
+//! when Az ∘ Bz = Cz, ∘ being the entrywise product. Every row of the sparse
+//! matrices A, B, C is a constraint <a, z> * <b, z> = <c, z>.
+mod builder;
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+pub use builder::{LinearCombination, R1CSBuilder, Variable};
+
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::DenseMultilinearExtension;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMatrix<F> {
+    num_cols: usize,
+    /// non zero entries (column, value) of every row
+    rows: Vec<Vec<(usize, F)>>,
+}
+
+impl<F: Field> SparseMatrix<F> {
+    pub fn new(num_cols: usize, rows: Vec<Vec<(usize, F)>>) -> Self {
+        assert!(
+            rows.iter().flatten().all(|(col, _)| *col < num_cols),
+            "column out of range"
+        );
+        SparseMatrix { num_cols, rows }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    pub fn rows(&self) -> &[Vec<(usize, F)>] {
+        &self.rows
+    }
+
+    /// Non zero entries as (row, column, value)
+    pub fn entries(&self) -> impl Iterator<Item = (usize, usize, F)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.iter().map(move |(j, v)| (i, *j, *v)))
+    }
+
+    pub fn mul_vector(&self, z: &[F]) -> Vec<F> {
+        assert_eq!(z.len(), self.num_cols, "vector of the wrong length");
+        self.rows
+            .iter()
+            .map(|row| row.iter().map(|(j, v)| *v * z[*j]).sum())
+            .collect()
+    }
+
+    /// Same matrix with the columns moved by the map, in a matrix with the
+    /// given number of columns
+    pub fn map_columns(&self, num_cols: usize, map: impl Fn(usize) -> usize) -> Self {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|(j, v)| (map(*j), *v)).collect())
+            .collect();
+        SparseMatrix::new(num_cols, rows)
+    }
+
+    /// Multilinear extension M(x, y) of the matrix padded with zeros to
+    /// 2^row_vars x 2^col_vars, the row variables coming first
+    pub fn to_mle(&self, row_vars: usize, col_vars: usize) -> DenseMultilinearExtension<F> {
+        assert!(self.num_rows() <= 1 << row_vars, "too many rows");
+        assert!(self.num_cols <= 1 << col_vars, "too many columns");
+        let mut evaluations = vec![F::ZERO; 1 << (row_vars + col_vars)];
+        for (i, j, v) in self.entries() {
+            evaluations[i + (j << row_vars)] += v;
+        }
+        DenseMultilinearExtension::from_evaluations_vec(row_vars + col_vars, evaluations)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1CS<F> {
+    pub a: SparseMatrix<F>,
+    pub b: SparseMatrix<F>,
+    pub c: SparseMatrix<F>,
+    num_public: usize,
+    /// description of every constraint, for error reports
+    labels: Vec<String>,
+}
+
+/// First constraint not satisfied by an assignment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint<F> {
+    pub index: usize,
+    pub label: String,
+    /// values of <a, z>, <b, z> and <c, z>
+    pub a: F,
+    pub b: F,
+    pub c: F,
+}
+
+impl<F: Field> fmt::Display for UnsatisfiedConstraint<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} ({}) is not satisfied: {} * {} != {}",
+            self.index, self.label, self.a, self.b, self.c
+        )
+    }
+}
+
+impl<F: Field> std::error::Error for UnsatisfiedConstraint<F> {}
+
+impl<F: Field> R1CS<F> {
+    /// num_public is the number of public inputs and outputs io
+    pub fn new(
+        a: SparseMatrix<F>,
+        b: SparseMatrix<F>,
+        c: SparseMatrix<F>,
+        num_public: usize,
+    ) -> Self {
+        let labels = (0..a.num_rows()).map(|i| format!("#{}", i)).collect();
+        Self::with_labels(a, b, c, num_public, labels)
+    }
+
+    pub fn with_labels(
+        a: SparseMatrix<F>,
+        b: SparseMatrix<F>,
+        c: SparseMatrix<F>,
+        num_public: usize,
+        labels: Vec<String>,
+    ) -> Self {
+        assert!(
+            a.num_rows() == b.num_rows() && a.num_rows() == c.num_rows(),
+            "matrices with different number of rows"
+        );
+        assert!(
+            a.num_cols() == b.num_cols() && a.num_cols() == c.num_cols(),
+            "matrices with different number of columns"
+        );
+        assert!(
+            num_public < a.num_cols(),
+            "more public values than variables"
+        );
+        assert_eq!(labels.len(), a.num_rows(), "one label per constraint");
+        R1CS {
+            a,
+            b,
+            c,
+            num_public,
+            labels,
+        }
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.a.num_rows()
+    }
+
+    /// Length of z, including the constant 1
+    pub fn num_variables(&self) -> usize {
+        self.a.num_cols()
+    }
+
+    pub fn num_public(&self) -> usize {
+        self.num_public
+    }
+
+    pub fn num_witness(&self) -> usize {
+        self.num_variables() - self.num_public - 1
+    }
+
+    /// Hash of the dimensions and of the matrices, for proofs to commit to
+    /// the instance they are about
+    pub fn digest(&self) -> Vec<u8> {
+        let mut transcript = Transcript::new(b"r1cs");
+        let dimensions = [
+            self.num_constraints(),
+            self.num_variables(),
+            self.num_public,
+        ];
+        transcript.append_serializable(b"dimensions", &dimensions.map(|d| d as u64)[..]);
+        for matrix in [&self.a, &self.b, &self.c] {
+            let entries: Vec<(u64, u64, F)> = matrix
+                .entries()
+                .map(|(i, j, v)| (i as u64, j as u64, v))
+                .collect();
+            transcript.append_serializable(b"matrix", &entries);
+        }
+        transcript.challenge_bytes(b"digest", 32)
+    }
+
+    /// z = (1, io, w)
+    pub fn assignment(&self, public: &[F], witness: &[F]) -> Vec<F> {
+        assert_eq!(
+            public.len(),
+            self.num_public,
+            "wrong number of public values"
+        );
+        assert_eq!(witness.len(), self.num_witness(), "wrong witness length");
+        let mut z = vec![F::ONE];
+        z.extend_from_slice(public);
+        z.extend_from_slice(witness);
+        z
+    }
+
+    pub fn is_satisfied(
+        &self,
+        public: &[F],
+        witness: &[F],
+    ) -> Result<(), UnsatisfiedConstraint<F>> {
+        let z = self.assignment(public, witness);
+        let (az, bz, cz) = (
+            self.a.mul_vector(&z),
+            self.b.mul_vector(&z),
+            self.c.mul_vector(&z),
+        );
+        for (index, ((a, b), c)) in az.into_iter().zip(bz).zip(cz).enumerate() {
+            if a * b != c {
+                return Err(UnsatisfiedConstraint {
+                    index,
+                    label: self.labels[index].clone(),
+                    a,
+                    b,
+                    c,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of variables of the multilinear extensions indexing the constraints
+    pub fn row_vars(&self) -> usize {
+        log2_ceil(self.num_constraints())
+    }
+
+    /// Number of variables of the multilinear extensions indexing z
+    pub fn col_vars(&self) -> usize {
+        log2_ceil(self.num_variables())
+    }
+
+    /// Multilinear extensions of A, B and C over (row, column) variables
+    pub fn matrix_mles(&self) -> [DenseMultilinearExtension<F>; 3] {
+        let (rows, cols) = (self.row_vars(), self.col_vars());
+        [
+            self.a.to_mle(rows, cols),
+            self.b.to_mle(rows, cols),
+            self.c.to_mle(rows, cols),
+        ]
+    }
+}
+
+/// Multilinear extension of the vector padded with zeros to a power of 2
+pub fn vector_mle<F: Field>(values: &[F]) -> DenseMultilinearExtension<F> {
+    let num_vars = log2_ceil(values.len());
+    let mut evaluations = values.to_vec();
+    evaluations.resize(1 << num_vars, F::ZERO);
+    DenseMultilinearExtension::from_evaluations_vec(num_vars, evaluations)
+}
+
+fn log2_ceil(n: usize) -> usize {
+    n.next_power_of_two().trailing_zeros() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::cubic;
+    use super::*;
+    use crate::multilinear::number_to_domain;
+    use crate::test_fields::F101;
+    use ark_poly::MultilinearExtension;
+
+    #[test]
+    fn test_satisfied() {
+        let (r1cs, _, _) = cubic::<F101>(3, 35);
+        assert_eq!(r1cs.num_witness(), 3);
+        let witness = [3, 9, 27].map(F101::from);
+        assert!(r1cs.is_satisfied(&[F101::from(35)], &witness).is_ok());
+    }
+
+    #[test]
+    fn test_first_violated_constraint() {
+        let (r1cs, _, _) = cubic::<F101>(3, 35);
+        let err = r1cs
+            .is_satisfied(&[F101::from(35)], &[3, 9, 28].map(F101::from))
+            .unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(
+            (err.a, err.b, err.c),
+            (F101::from(9), F101::from(3), F101::from(28))
+        );
+        assert_eq!(
+            err.to_string(),
+            "constraint 1 (mul) is not satisfied: 9 * 3 != 28"
+        );
+
+        let err = r1cs
+            .is_satisfied(&[F101::from(36)], &[3, 9, 27].map(F101::from))
+            .unwrap_err();
+        assert_eq!(err.index, 2);
+    }
+
+    #[test]
+    fn test_matrix_mles() {
+        let (r1cs, _, _) = cubic::<F101>(3, 35);
+        let z = r1cs.assignment(&[F101::from(35)], &[3, 9, 27].map(F101::from));
+        let az = r1cs.a.mul_vector(&z);
+        let [a, _, _] = r1cs.matrix_mles();
+        assert_eq!(a.num_vars, 2 + 3);
+
+        // (Az)_x = sum_y A(x, y) z(y)
+        let z = vector_mle(&z);
+        for x in 0..4 {
+            let row = number_to_domain::<F101>(x, 2);
+            let sum: F101 = (0..8)
+                .map(|y| {
+                    let col = number_to_domain::<F101>(y, 3);
+                    let point = [row.clone(), col.clone()].concat();
+                    a.evaluate(&point).unwrap() * z.evaluate(&col).unwrap()
+                })
+                .sum();
+            assert_eq!(sum, az.get(x).copied().unwrap_or(F101::ZERO));
+        }
+    }
+
+    #[test]
+    fn test_map_columns() {
+        let (r1cs, _, _) = cubic::<F101>(3, 35);
+        // the witness first, then the constant and the public value
+        let moved = r1cs.a.map_columns(8, |j| if j < 2 { 4 + j } else { j - 2 });
+        let z = [3, 9, 27, 0, 1, 35, 0, 0].map(F101::from);
+        let original = r1cs.assignment(&[F101::from(35)], &[3, 9, 27].map(F101::from));
+        assert_eq!(moved.mul_vector(&z), r1cs.a.mul_vector(&original));
+    }
+}
@@ -0,0 +1,140 @@
+use super::SigmaProtocol;
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::{Field, UniformRand};
+use ark_std::rand::RngCore;
+
+/// Chaum–Pedersen proof that X = x G and Y = x H have the same discrete
+/// logarithm: Schnorr in both bases with the same nonce and response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChaumPedersen<C: CurveGroup> {
+    g: C,
+    h: C,
+}
+
+impl<C: CurveGroup> ChaumPedersen<C> {
+    pub fn new(g: C, h: C) -> Self {
+        ChaumPedersen { g, h }
+    }
+}
+
+impl<C: CurveGroup> SigmaProtocol<C> for ChaumPedersen<C> {
+    /// (X, Y)
+    type Statement = (C, C);
+    type Witness = C::ScalarField;
+    /// (k G, k H)
+    type Commitment = (C, C);
+    type Response = C::ScalarField;
+    type ProverState = C::ScalarField;
+
+    fn absorb_parameters(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"sigma_protocol", b"chaum_pedersen");
+        transcript.append_serializable(b"chaum_pedersen_g", &self.g);
+        transcript.append_serializable(b"chaum_pedersen_h", &self.h);
+    }
+
+    fn commit<R: RngCore>(
+        &self,
+        _statement: &(C, C),
+        _witness: &C::ScalarField,
+        rng: &mut R,
+    ) -> ((C, C), C::ScalarField) {
+        let k = C::ScalarField::rand(rng);
+        ((self.g * k, self.h * k), k)
+    }
+
+    fn respond(
+        &self,
+        witness: &C::ScalarField,
+        k: C::ScalarField,
+        challenge: C::ScalarField,
+    ) -> C::ScalarField {
+        k + challenge * witness
+    }
+
+    fn verify(
+        &self,
+        (x, y): &(C, C),
+        (a, b): &(C, C),
+        challenge: C::ScalarField,
+        response: &C::ScalarField,
+    ) -> bool {
+        self.g * response == *a + *x * challenge && self.h * response == *b + *y * challenge
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        (x, y): &(C, C),
+        challenge: C::ScalarField,
+        rng: &mut R,
+    ) -> ((C, C), C::ScalarField) {
+        let z = C::ScalarField::rand(rng);
+        let commitment = (self.g * z - *x * challenge, self.h * z - *y * challenge);
+        (commitment, z)
+    }
+
+    fn extract(
+        &self,
+        _statement: &(C, C),
+        (c_1, z_1): (C::ScalarField, &C::ScalarField),
+        (c_2, z_2): (C::ScalarField, &C::ScalarField),
+    ) -> Option<C::ScalarField> {
+        (c_1 - c_2).inverse().map(|d| (*z_1 - z_2) * d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::hash_to_curve;
+    use ark_ec::Group;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    fn setup() -> (
+        ChaumPedersen<G1Projective>,
+        (G1Projective, G1Projective),
+        Fr,
+    ) {
+        let g = G1Projective::generator();
+        let h: G1Projective = hash_to_curve::<G1Projective>(b"dleq", 0).into();
+        let x = Fr::rand(&mut test_rng());
+        (ChaumPedersen::new(g, h), (g * x, h * x), x)
+    }
+
+    #[test]
+    fn test_chaum_pedersen() {
+        let mut rng = test_rng();
+        let (dleq, statement, x) = setup();
+        let (commitment, state) = dleq.commit(&statement, &x, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = dleq.respond(&x, state, c);
+        assert!(dleq.verify(&statement, &commitment, c, &response));
+
+        let (commitment, response) = dleq.simulate(&statement, c, &mut rng);
+        assert!(dleq.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_different_logarithms() {
+        let mut rng = test_rng();
+        let (dleq, (x_g, y), x) = setup();
+        let statement = (x_g, y + dleq.h);
+        let (commitment, state) = dleq.commit(&statement, &x, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = dleq.respond(&x, state, c);
+        assert!(!dleq.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_extractor() {
+        let mut rng = test_rng();
+        let (dleq, statement, x) = setup();
+        let (commitment, k) = dleq.commit(&statement, &x, &mut rng);
+        let (c_1, c_2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let (z_1, z_2) = (dleq.respond(&x, k, c_1), dleq.respond(&x, k, c_2));
+        assert!(dleq.verify(&statement, &commitment, c_1, &z_1));
+        assert!(dleq.verify(&statement, &commitment, c_2, &z_2));
+        assert_eq!(dleq.extract(&statement, (c_1, &z_1), (c_2, &z_2)), Some(x));
+    }
+}
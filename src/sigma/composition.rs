@@ -0,0 +1,317 @@
+use super::SigmaProtocol;
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_std::rand::RngCore;
+
+/// Knowledge of witnesses for both statements, answering the same challenge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct And<P, Q>(pub P, pub Q);
+
+/// Knowledge of a witness for one of the statements. The response is
+/// (c_0, z_0, z_1), the challenge of the second branch being c - c_0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Or<P, Q>(pub P, pub Q);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrWitness<A, B> {
+    Left(A),
+    Right(B),
+}
+
+/// State of the real branch, and the challenge and response of the
+/// simulated one
+pub enum OrState<C: CurveGroup, P: SigmaProtocol<C>, Q: SigmaProtocol<C>> {
+    Left(P::ProverState, C::ScalarField, Q::Response),
+    Right(C::ScalarField, P::Response, Q::ProverState),
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>, Q: SigmaProtocol<C>> Clone for OrState<C, P, Q>
+where
+    P::ProverState: Clone,
+    Q::ProverState: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            OrState::Left(state, c, z) => OrState::Left(state.clone(), *c, z.clone()),
+            OrState::Right(c, z, state) => OrState::Right(*c, z.clone(), state.clone()),
+        }
+    }
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>, Q: SigmaProtocol<C>> SigmaProtocol<C> for And<P, Q> {
+    type Statement = (P::Statement, Q::Statement);
+    type Witness = (P::Witness, Q::Witness);
+    type Commitment = (P::Commitment, Q::Commitment);
+    type Response = (P::Response, Q::Response);
+    type ProverState = (P::ProverState, Q::ProverState);
+
+    fn absorb_parameters(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"sigma_protocol", b"and");
+        self.0.absorb_parameters(transcript);
+        self.1.absorb_parameters(transcript);
+    }
+
+    fn commit<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::ProverState) {
+        let (a_0, state_0) = self.0.commit(&statement.0, &witness.0, rng);
+        let (a_1, state_1) = self.1.commit(&statement.1, &witness.1, rng);
+        ((a_0, a_1), (state_0, state_1))
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::ProverState,
+        challenge: C::ScalarField,
+    ) -> Self::Response {
+        (
+            self.0.respond(&witness.0, state.0, challenge),
+            self.1.respond(&witness.1, state.1, challenge),
+        )
+    }
+
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: C::ScalarField,
+        response: &Self::Response,
+    ) -> bool {
+        self.0
+            .verify(&statement.0, &commitment.0, challenge, &response.0)
+            && self
+                .1
+                .verify(&statement.1, &commitment.1, challenge, &response.1)
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        challenge: C::ScalarField,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let (a_0, z_0) = self.0.simulate(&statement.0, challenge, rng);
+        let (a_1, z_1) = self.1.simulate(&statement.1, challenge, rng);
+        ((a_0, a_1), (z_0, z_1))
+    }
+
+    fn extract(
+        &self,
+        statement: &Self::Statement,
+        (c_1, z_1): (C::ScalarField, &Self::Response),
+        (c_2, z_2): (C::ScalarField, &Self::Response),
+    ) -> Option<Self::Witness> {
+        let w_0 = self.0.extract(&statement.0, (c_1, &z_1.0), (c_2, &z_2.0))?;
+        let w_1 = self.1.extract(&statement.1, (c_1, &z_1.1), (c_2, &z_2.1))?;
+        Some((w_0, w_1))
+    }
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>, Q: SigmaProtocol<C>> SigmaProtocol<C> for Or<P, Q> {
+    type Statement = (P::Statement, Q::Statement);
+    type Witness = OrWitness<P::Witness, Q::Witness>;
+    type Commitment = (P::Commitment, Q::Commitment);
+    type Response = (C::ScalarField, P::Response, Q::Response);
+    type ProverState = OrState<C, P, Q>;
+
+    fn absorb_parameters(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"sigma_protocol", b"or");
+        self.0.absorb_parameters(transcript);
+        self.1.absorb_parameters(transcript);
+    }
+
+    /// Commits in the branch of the witness and simulates the other one for a
+    /// random challenge
+    fn commit<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::ProverState) {
+        let simulated_challenge = C::ScalarField::rand(rng);
+        match witness {
+            OrWitness::Left(w) => {
+                let (a_0, state) = self.0.commit(&statement.0, w, rng);
+                let (a_1, z_1) = self.1.simulate(&statement.1, simulated_challenge, rng);
+                ((a_0, a_1), OrState::Left(state, simulated_challenge, z_1))
+            }
+            OrWitness::Right(w) => {
+                let (a_0, z_0) = self.0.simulate(&statement.0, simulated_challenge, rng);
+                let (a_1, state) = self.1.commit(&statement.1, w, rng);
+                ((a_0, a_1), OrState::Right(simulated_challenge, z_0, state))
+            }
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::ProverState,
+        challenge: C::ScalarField,
+    ) -> Self::Response {
+        match (witness, state) {
+            (OrWitness::Left(w), OrState::Left(state, c_1, z_1)) => {
+                let c_0 = challenge - c_1;
+                (c_0, self.0.respond(w, state, c_0), z_1)
+            }
+            (OrWitness::Right(w), OrState::Right(c_0, z_0, state)) => {
+                (c_0, z_0, self.1.respond(w, state, challenge - c_0))
+            }
+            _ => panic!("state of the other branch"),
+        }
+    }
+
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: C::ScalarField,
+        (c_0, z_0, z_1): &Self::Response,
+    ) -> bool {
+        self.0.verify(&statement.0, &commitment.0, *c_0, z_0)
+            && self
+                .1
+                .verify(&statement.1, &commitment.1, challenge - c_0, z_1)
+    }
+
+    fn simulate<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        challenge: C::ScalarField,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response) {
+        let c_0 = C::ScalarField::rand(rng);
+        let (a_0, z_0) = self.0.simulate(&statement.0, c_0, rng);
+        let (a_1, z_1) = self.1.simulate(&statement.1, challenge - c_0, rng);
+        ((a_0, a_1), (c_0, z_0, z_1))
+    }
+
+    /// The challenges differ in at least one branch, which gives its witness
+    fn extract(
+        &self,
+        statement: &Self::Statement,
+        (c_1, z_1): (C::ScalarField, &Self::Response),
+        (c_2, z_2): (C::ScalarField, &Self::Response),
+    ) -> Option<Self::Witness> {
+        if z_1.0 != z_2.0 {
+            let w = self
+                .0
+                .extract(&statement.0, (z_1.0, &z_1.1), (z_2.0, &z_2.1))?;
+            return Some(OrWitness::Left(w));
+        }
+        let w = self
+            .1
+            .extract(&statement.1, (c_1 - z_1.0, &z_1.2), (c_2 - z_2.0, &z_2.2))?;
+        Some(OrWitness::Right(w))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::hash_to_curve;
+    use crate::sigma::{ChaumPedersen, Schnorr};
+    use ark_ec::Group;
+    use ark_ff::Field;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    type SchnorrOr = Or<Schnorr<G1Projective>, Schnorr<G1Projective>>;
+
+    fn or_setup() -> (SchnorrOr, (G1Projective, G1Projective), Fr) {
+        let mut rng = test_rng();
+        let g = G1Projective::generator();
+        let (x, unknown) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        (
+            Or(Schnorr::default(), Schnorr::default()),
+            (g * unknown, g * x),
+            x,
+        )
+    }
+
+    // transcripts for two challenges with the same commitment, verified
+    fn rewind<P: SigmaProtocol<G1Projective>>(
+        protocol: &P,
+        statement: &P::Statement,
+        witness: &P::Witness,
+    ) -> [(Fr, P::Response); 2]
+    where
+        P::ProverState: Clone,
+    {
+        let mut rng = test_rng();
+        let (commitment, state) = protocol.commit(statement, witness, &mut rng);
+        [Fr::rand(&mut rng), Fr::rand(&mut rng)].map(|c| {
+            let response = protocol.respond(witness, state.clone(), c);
+            assert!(protocol.verify(statement, &commitment, c, &response));
+            (c, response)
+        })
+    }
+
+    #[test]
+    fn test_and() {
+        let mut rng = test_rng();
+        let g = G1Projective::generator();
+        let h: G1Projective = hash_to_curve::<G1Projective>(b"dleq", 0).into();
+        let and = And(Schnorr::default(), ChaumPedersen::new(g, h));
+        let (x, y) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let statement = (g * x, (g * y, h * y));
+
+        let [(c_1, z_1), (c_2, z_2)] = rewind(&and, &statement, &(x, y));
+        assert_eq!(
+            and.extract(&statement, (c_1, &z_1), (c_2, &z_2)),
+            Some((x, y))
+        );
+
+        let c = Fr::rand(&mut rng);
+        let (commitment, response) = and.simulate(&statement, c, &mut rng);
+        assert!(and.verify(&statement, &commitment, c, &response));
+
+        // a witness for only one of the statements
+        let (commitment, state) = and.commit(&statement, &(x, x), &mut rng);
+        let response = and.respond(&(x, x), state, c);
+        assert!(!and.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_or() {
+        let mut rng = test_rng();
+        let (or, statement, x) = or_setup();
+        let witness = OrWitness::Right(x);
+        let (commitment, state) = or.commit(&statement, &witness, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = or.respond(&witness, state, c);
+        assert!(or.verify(&statement, &commitment, c, &response));
+        assert!(!or.verify(&statement, &commitment, c + Fr::ONE, &response));
+
+        let (commitment, response) = or.simulate(&statement, c, &mut rng);
+        assert!(or.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_or_without_witness() {
+        let mut rng = test_rng();
+        let (or, statement, x) = or_setup();
+        let witness = OrWitness::Left(x);
+        let (commitment, state) = or.commit(&statement, &witness, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = or.respond(&witness, state, c);
+        assert!(!or.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_or_extractor() {
+        let (or, statement, x) = or_setup();
+        let [(c_1, z_1), (c_2, z_2)] = rewind(&or, &statement, &OrWitness::Right(x));
+        // the simulated branch keeps its challenge
+        assert_eq!(z_1.0, z_2.0);
+        assert_eq!(
+            or.extract(&statement, (c_1, &z_1), (c_2, &z_2)),
+            Some(OrWitness::Right(x))
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Σ-protocols (Thaler §12). The prover sends a commitment a, the verifier a
+//! random challenge c and the prover a response z. The protocols here are
+//!
+//! - special sound: from two accepting transcripts (a, c, z) and (a, c', z')
+//!   with c != c' a witness can be extracted, so a prover answering more than
+//!   one challenge knows one,
+//! - honest-verifier zero knowledge: accepting transcripts for a given
+//!   challenge can be simulated without the witness.
+//!
+//! `And` answers the same challenge in two protocols and `Or` proves that the
+//! prover knows a witness for one of two statements without revealing which
+//! (Cramer, Damgård, Schoenmakers 1994): it simulates the other branch for a
+//! challenge of its choice, and the two challenges must sum to c.
+//!
+//! The protocols run interactively over the channels of `Prover` and
+//! `Verifier`, or non-interactively with the challenge derived from the
+//! transcript (Fiat–Shamir). The transcript absorbs a label of the protocol
+//! and its public parameters before the statement, so that e.g. `And` and
+//! `Or` over the same statements get different challenges.
+mod chaum_pedersen;
+mod composition;
+mod protocol;
+mod schnorr;
+
+pub use chaum_pedersen::ChaumPedersen;
+pub use composition::{And, Or, OrState, OrWitness};
+pub use protocol::{Prover, ProverMessage, Verifier, VerifierMessage};
+pub use schnorr::Schnorr;
+
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+/// Three-move protocol for a relation between statements and witnesses, with
+/// challenges in the scalar field of the group
+pub trait SigmaProtocol<C: CurveGroup> {
+    type Statement: CanonicalSerialize;
+    type Witness;
+    type Commitment: Clone + CanonicalSerialize + CanonicalDeserialize;
+    type Response: Clone + CanonicalSerialize + CanonicalDeserialize;
+    /// What the prover keeps from the commitment to respond
+    type ProverState;
+
+    /// Absorbs a label of the protocol and its public parameters, before the
+    /// statement of a non-interactive proof
+    fn absorb_parameters(&self, transcript: &mut Transcript);
+
+    fn commit<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::ProverState);
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        state: Self::ProverState,
+        challenge: C::ScalarField,
+    ) -> Self::Response;
+
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: C::ScalarField,
+        response: &Self::Response,
+    ) -> bool;
+
+    /// Accepting transcript for the challenge, without the witness
+    fn simulate<R: RngCore>(
+        &self,
+        statement: &Self::Statement,
+        challenge: C::ScalarField,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response);
+
+    /// Witness from two accepting transcripts with the same commitment, given
+    /// as (challenge, response). None if the challenges are equal.
+    fn extract(
+        &self,
+        statement: &Self::Statement,
+        first: (C::ScalarField, &Self::Response),
+        second: (C::ScalarField, &Self::Response),
+    ) -> Option<Self::Witness>;
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct SigmaProof<C: CurveGroup, P: SigmaProtocol<C>> {
+    commitment: P::Commitment,
+    response: P::Response,
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>> SigmaProof<C, P> {
+    pub fn new(commitment: P::Commitment, response: P::Response) -> Self {
+        SigmaProof {
+            commitment,
+            response,
+        }
+    }
+
+    pub fn commitment(&self) -> &P::Commitment {
+        &self.commitment
+    }
+
+    pub fn response(&self) -> &P::Response {
+        &self.response
+    }
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>> Clone for SigmaProof<C, P> {
+    fn clone(&self) -> Self {
+        SigmaProof::new(self.commitment.clone(), self.response.clone())
+    }
+}
+
+fn challenge<C: CurveGroup, P: SigmaProtocol<C>>(
+    protocol: &P,
+    statement: &P::Statement,
+    commitment: &P::Commitment,
+    transcript: &mut Transcript,
+) -> C::ScalarField {
+    protocol.absorb_parameters(transcript);
+    transcript.append_serializable(b"sigma_statement", statement);
+    transcript.append_serializable(b"sigma_commitment", commitment);
+    transcript.challenge_field(b"sigma_challenge")
+}
+
+/// Runs the prover with the challenge drawn from the transcript
+pub fn prove_noninteractive<C: CurveGroup, P: SigmaProtocol<C>, R: RngCore>(
+    protocol: &P,
+    statement: &P::Statement,
+    witness: &P::Witness,
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> SigmaProof<C, P> {
+    let (commitment, state) = protocol.commit(statement, witness, rng);
+    let c = challenge::<C, P>(protocol, statement, &commitment, transcript);
+    let response = protocol.respond(witness, state, c);
+    SigmaProof::new(commitment, response)
+}
+
+pub fn verify_noninteractive<C: CurveGroup, P: SigmaProtocol<C>>(
+    protocol: &P,
+    statement: &P::Statement,
+    proof: &SigmaProof<C, P>,
+    transcript: &mut Transcript,
+) -> bool {
+    let c = challenge::<C, P>(protocol, statement, &proof.commitment, transcript);
+    protocol.verify(statement, &proof.commitment, c, &proof.response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::Group;
+    use ark_ff::{Field, UniformRand};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    #[test]
+    fn test_noninteractive() {
+        let mut rng = test_rng();
+        let schnorr = Schnorr::<G1Projective>::default();
+        let x = Fr::rand(&mut rng);
+        let statement = G1Projective::generator() * x;
+        let proof = prove_noninteractive(
+            &schnorr,
+            &statement,
+            &x,
+            &mut rng,
+            &mut Transcript::new(b"test"),
+        );
+        let mut transcript = Transcript::new(b"test");
+        assert!(verify_noninteractive(
+            &schnorr,
+            &statement,
+            &proof,
+            &mut transcript
+        ));
+
+        // the challenge depends on the statement
+        let other = statement + G1Projective::generator();
+        let mut transcript = Transcript::new(b"test");
+        assert!(!verify_noninteractive(
+            &schnorr,
+            &other,
+            &proof,
+            &mut transcript
+        ));
+    }
+
+    #[test]
+    fn test_noninteractive_or() {
+        let mut rng = test_rng();
+        let g = G1Projective::generator();
+        let or = Or(Schnorr::<G1Projective>::default(), Schnorr::default());
+        let statement = (g * Fr::rand(&mut rng), g * Fr::from(7));
+        let witness = OrWitness::Right(Fr::from(7));
+        let proof = prove_noninteractive(
+            &or,
+            &statement,
+            &witness,
+            &mut rng,
+            &mut Transcript::new(b"test"),
+        );
+        let mut transcript = Transcript::new(b"test");
+        assert!(verify_noninteractive(
+            &or,
+            &statement,
+            &proof,
+            &mut transcript
+        ));
+
+        let mut tampered = proof;
+        tampered.response.0 += Fr::ONE;
+        let mut transcript = Transcript::new(b"test");
+        assert!(!verify_noninteractive(
+            &or,
+            &statement,
+            &tampered,
+            &mut transcript
+        ));
+    }
+
+    #[test]
+    fn test_challenge_binds_protocol() {
+        // same statements and commitments, but other protocols or parameters
+        let mut rng = test_rng();
+        let g = G1Projective::generator();
+        let schnorr = Schnorr::<G1Projective>::default();
+        let statement = (g * Fr::rand(&mut rng), g * Fr::rand(&mut rng));
+        let commitment = (g * Fr::rand(&mut rng), g * Fr::rand(&mut rng));
+        let and = challenge::<G1Projective, _>(
+            &And(schnorr, schnorr),
+            &statement,
+            &commitment,
+            &mut Transcript::new(b"test"),
+        );
+        let or = challenge::<G1Projective, _>(
+            &Or(schnorr, schnorr),
+            &statement,
+            &commitment,
+            &mut Transcript::new(b"test"),
+        );
+        assert_ne!(and, or);
+
+        let other = Schnorr::new(g.double());
+        let c = |schnorr: &Schnorr<G1Projective>| {
+            challenge::<G1Projective, _>(
+                schnorr,
+                &statement.0,
+                &commitment.0,
+                &mut Transcript::new(b"test"),
+            )
+        };
+        assert_ne!(c(&schnorr), c(&other));
+        let dleq = ChaumPedersen::new(g, g.double());
+        let c = |dleq: &ChaumPedersen<G1Projective>| {
+            challenge::<G1Projective, _>(
+                dleq,
+                &statement,
+                &commitment,
+                &mut Transcript::new(b"test"),
+            )
+        };
+        assert_ne!(c(&dleq), c(&ChaumPedersen::new(g, g.double().double())));
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let mut rng = test_rng();
+        let g = G1Projective::generator();
+        let or = Or(Schnorr::<G1Projective>::default(), Schnorr::default());
+        let statement = (g * Fr::from(7), g * Fr::rand(&mut rng));
+        let proof = prove_noninteractive(
+            &or,
+            &statement,
+            &OrWitness::Left(Fr::from(7)),
+            &mut rng,
+            &mut Transcript::new(b"test"),
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded: SigmaProof<G1Projective, Or<_, _>> =
+            SigmaProof::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded.commitment(), proof.commitment());
+        assert_eq!(decoded.response(), proof.response());
+        assert!(verify_noninteractive(
+            &or,
+            &statement,
+            &decoded,
+            &mut Transcript::new(b"test")
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use super::SigmaProtocol;
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_std::rand::RngCore;
+use trpl::{self, Receiver, Sender};
+
+pub enum ProverMessage<C: CurveGroup, P: SigmaProtocol<C>> {
+    Statement(
+        Sender<VerifierMessage<C::ScalarField>>,
+        P::Statement,
+        P::Commitment,
+    ),
+    Response(P::Response),
+}
+
+pub enum VerifierMessage<F> {
+    Challenge(F),
+    Failure(String),
+    Success,
+}
+
+pub struct Prover<C: CurveGroup, P: SigmaProtocol<C>> {
+    tx: Sender<ProverMessage<C, P>>,
+    rx: Receiver<VerifierMessage<C::ScalarField>>,
+    protocol: P,
+    witness: P::Witness,
+    state: Option<P::ProverState>,
+    result: Option<Result<(), String>>,
+}
+
+pub struct Verifier<C: CurveGroup, P: SigmaProtocol<C>, R: RngCore> {
+    tx: Option<Sender<VerifierMessage<C::ScalarField>>>,
+    rx: Receiver<ProverMessage<C, P>>,
+    protocol: P,
+    rng: R,
+    /// statement, commitment and challenge of the running proof
+    state: Option<(P::Statement, P::Commitment, C::ScalarField)>,
+    result: Option<Result<(), String>>,
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>> Prover<C, P> {
+    /// Sends the statement and the commitment to the verifier
+    pub fn new<R: RngCore>(
+        tx: Sender<ProverMessage<C, P>>,
+        protocol: P,
+        statement: P::Statement,
+        witness: P::Witness,
+        rng: &mut R,
+    ) -> Self {
+        let (v_tx, p_rx) = trpl::channel();
+        let (commitment, state) = protocol.commit(&statement, &witness, rng);
+        tx.send(ProverMessage::Statement(v_tx, statement, commitment))
+            .expect("unable to communicate with verifier");
+        Prover {
+            tx,
+            rx: p_rx,
+            protocol,
+            witness,
+            state: Some(state),
+            result: None,
+        }
+    }
+
+    pub async fn prove(&mut self) {
+        if let Some(message) = self.rx.recv().await {
+            match message {
+                VerifierMessage::Challenge(challenge) => {
+                    let state = self.state.take().expect("challenge already answered");
+                    let response = self.protocol.respond(&self.witness, state, challenge);
+                    self.tx
+                        .send(ProverMessage::Response(response))
+                        .expect("Communication Error");
+                }
+                VerifierMessage::Success => self.result = Some(Ok(())),
+                VerifierMessage::Failure(err_message) => self.result = Some(Err(err_message)),
+            }
+        }
+    }
+
+    /// Outcome sent by the verifier, if any
+    pub fn result(&self) -> Option<&Result<(), String>> {
+        self.result.as_ref()
+    }
+}
+
+impl<C: CurveGroup, P: SigmaProtocol<C>, R: RngCore> Verifier<C, P, R> {
+    pub fn new(rx: Receiver<ProverMessage<C, P>>, protocol: P, rng: R) -> Self {
+        Verifier {
+            tx: None,
+            rx,
+            protocol,
+            rng,
+            state: None,
+            result: None,
+        }
+    }
+
+    pub async fn listen(&mut self) {
+        match self.rx.recv().await {
+            Some(ProverMessage::Statement(tx, statement, commitment)) => {
+                self.registration(tx, statement, commitment)
+            }
+            Some(ProverMessage::Response(response)) => self.verify(response),
+            None => {
+                self.state = None;
+                self.tx = None;
+            }
+        }
+    }
+
+    /// Outcome of the last proof, if it is over
+    pub fn result(&self) -> Option<&Result<(), String>> {
+        self.result.as_ref()
+    }
+
+    fn registration(
+        &mut self,
+        tx: Sender<VerifierMessage<C::ScalarField>>,
+        statement: P::Statement,
+        commitment: P::Commitment,
+    ) {
+        if self.tx.is_some() || self.state.is_some() {
+            tx.send(VerifierMessage::Failure(
+                "Other verification taking place".to_string(),
+            ))
+            .expect("Communication Failure");
+        } else {
+            let challenge = C::ScalarField::rand(&mut self.rng);
+            tx.send(VerifierMessage::Challenge(challenge))
+                .expect("Communication Failure");
+            self.tx = Some(tx);
+            self.state = Some((statement, commitment, challenge));
+        }
+    }
+
+    fn verify(&mut self, response: P::Response) {
+        let (Some(tx), Some((statement, commitment, challenge))) =
+            (self.tx.take(), self.state.take())
+        else {
+            return;
+        };
+        let result = if self
+            .protocol
+            .verify(&statement, &commitment, challenge, &response)
+        {
+            Ok(())
+        } else {
+            Err("Invalid response".to_string())
+        };
+        let message = match &result {
+            Ok(()) => VerifierMessage::Success,
+            Err(err) => VerifierMessage::Failure(err.clone()),
+        };
+        tx.send(message).expect("Communication Error");
+        self.result = Some(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sigma::{ChaumPedersen, Or, OrWitness, Schnorr};
+    use ark_ec::Group;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    type Outcome = Option<Result<(), String>>;
+
+    // runs the three moves and returns what the prover and the verifier got
+    fn run<P: SigmaProtocol<G1Projective> + Clone>(
+        protocol: P,
+        statement: P::Statement,
+        witness: P::Witness,
+    ) -> (Outcome, Outcome) {
+        let (tx, rx) = trpl::channel();
+        let mut verifier = Verifier::new(rx, protocol.clone(), test_rng());
+        let mut prover = Prover::new(tx, protocol, statement, witness, &mut test_rng());
+        trpl::run(async {
+            verifier.listen().await;
+            prover.prove().await;
+            verifier.listen().await;
+            prover.prove().await;
+        });
+        (prover.result().cloned(), verifier.result().cloned())
+    }
+
+    #[test]
+    fn test_interactive_schnorr() {
+        let x = Fr::from(42);
+        let statement = G1Projective::generator() * x;
+        let (prover, verifier) = run(Schnorr::default(), statement, x);
+        assert_eq!(prover, Some(Ok(())));
+        assert_eq!(verifier, Some(Ok(())));
+
+        let (prover, verifier) = run(Schnorr::default(), statement, x + Fr::from(1));
+        assert!(matches!(prover, Some(Err(_))));
+        assert!(matches!(verifier, Some(Err(_))));
+    }
+
+    #[test]
+    fn test_interactive_chaum_pedersen_or() {
+        let g = G1Projective::generator();
+        let h = g * Fr::from(5);
+        let dleq = ChaumPedersen::new(g, h);
+        let or = Or(dleq, dleq);
+        let x = Fr::from(3);
+        let statement = ((g * x, h * x), (g, h * Fr::from(2)));
+        let (_, verifier) = run(or, statement, OrWitness::Left(x));
+        assert_eq!(verifier, Some(Ok(())));
+    }
+}
@@ -0,0 +1,150 @@
+use super::SigmaProtocol;
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::{Field, UniformRand};
+use ark_std::rand::RngCore;
+
+/// Proof of knowledge of the discrete logarithm x of X = x G. The prover
+/// commits to A = k G and responds z = k + c x, which the verifier checks as
+/// z G = A + c X.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schnorr<C: CurveGroup> {
+    generator: C,
+}
+
+impl<C: CurveGroup> Schnorr<C> {
+    pub fn new(generator: C) -> Self {
+        Schnorr { generator }
+    }
+}
+
+impl<C: CurveGroup> Default for Schnorr<C> {
+    fn default() -> Self {
+        Self::new(C::generator())
+    }
+}
+
+impl<C: CurveGroup> SigmaProtocol<C> for Schnorr<C> {
+    type Statement = C;
+    type Witness = C::ScalarField;
+    type Commitment = C;
+    type Response = C::ScalarField;
+    type ProverState = C::ScalarField;
+
+    fn absorb_parameters(&self, transcript: &mut Transcript) {
+        transcript.append_message(b"sigma_protocol", b"schnorr");
+        transcript.append_serializable(b"schnorr_generator", &self.generator);
+    }
+
+    fn commit<R: RngCore>(
+        &self,
+        _statement: &C,
+        _witness: &C::ScalarField,
+        rng: &mut R,
+    ) -> (C, C::ScalarField) {
+        let k = C::ScalarField::rand(rng);
+        (self.generator * k, k)
+    }
+
+    fn respond(
+        &self,
+        witness: &C::ScalarField,
+        k: C::ScalarField,
+        challenge: C::ScalarField,
+    ) -> C::ScalarField {
+        k + challenge * witness
+    }
+
+    fn verify(
+        &self,
+        statement: &C,
+        commitment: &C,
+        challenge: C::ScalarField,
+        response: &C::ScalarField,
+    ) -> bool {
+        self.generator * response == *commitment + *statement * challenge
+    }
+
+    /// A = z G - c X for a random z
+    fn simulate<R: RngCore>(
+        &self,
+        statement: &C,
+        challenge: C::ScalarField,
+        rng: &mut R,
+    ) -> (C, C::ScalarField) {
+        let z = C::ScalarField::rand(rng);
+        (self.generator * z - *statement * challenge, z)
+    }
+
+    /// x = (z - z') / (c - c')
+    fn extract(
+        &self,
+        _statement: &C,
+        (c_1, z_1): (C::ScalarField, &C::ScalarField),
+        (c_2, z_2): (C::ScalarField, &C::ScalarField),
+    ) -> Option<C::ScalarField> {
+        (c_1 - c_2).inverse().map(|d| (*z_1 - z_2) * d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::Group;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Fr, G1Projective};
+
+    fn setup() -> (Schnorr<G1Projective>, G1Projective, Fr) {
+        let x = Fr::rand(&mut test_rng());
+        (Schnorr::default(), G1Projective::generator() * x, x)
+    }
+
+    #[test]
+    fn test_schnorr() {
+        let mut rng = test_rng();
+        let (schnorr, statement, x) = setup();
+        let (commitment, state) = schnorr.commit(&statement, &x, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = schnorr.respond(&x, state, c);
+        assert!(schnorr.verify(&statement, &commitment, c, &response));
+        assert!(!schnorr.verify(&statement, &commitment, c + Fr::ONE, &response));
+    }
+
+    #[test]
+    fn test_wrong_witness() {
+        let mut rng = test_rng();
+        let (schnorr, statement, x) = setup();
+        let wrong = x + Fr::ONE;
+        let (commitment, state) = schnorr.commit(&statement, &wrong, &mut rng);
+        let c = Fr::rand(&mut rng);
+        let response = schnorr.respond(&wrong, state, c);
+        assert!(!schnorr.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_simulator() {
+        let mut rng = test_rng();
+        let (schnorr, statement, _) = setup();
+        let c = Fr::rand(&mut rng);
+        let (commitment, response) = schnorr.simulate(&statement, c, &mut rng);
+        assert!(schnorr.verify(&statement, &commitment, c, &response));
+    }
+
+    #[test]
+    fn test_extractor() {
+        // rewinding the prover to answer a second challenge reveals x
+        let mut rng = test_rng();
+        let (schnorr, statement, x) = setup();
+        let (commitment, k) = schnorr.commit(&statement, &x, &mut rng);
+        let (c_1, c_2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let (z_1, z_2) = (schnorr.respond(&x, k, c_1), schnorr.respond(&x, k, c_2));
+        assert!(schnorr.verify(&statement, &commitment, c_1, &z_1));
+        assert!(schnorr.verify(&statement, &commitment, c_2, &z_2));
+
+        assert_eq!(
+            schnorr.extract(&statement, (c_1, &z_1), (c_2, &z_2)),
+            Some(x)
+        );
+        assert_eq!(schnorr.extract(&statement, (c_1, &z_1), (c_1, &z_1)), None);
+    }
+}
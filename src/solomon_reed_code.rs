@@ -1,4 +1,8 @@
-use ark_ff::Field;
+use crate::merkle::{MerkleHasher, MerkleTree, MultiProof, Sha256Hasher};
+use ark_ff::{FftField, Field};
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
 
 pub struct ReedSolomon<F>(Vec<F>);
 
@@ -23,22 +27,112 @@ impl<F: Field> ReedSolomon<F> {
 
         h
     }
+
+    pub fn message(&self) -> &[F] {
+        &self.0
+    }
+
+    /// Codeword of the message, the evaluations at the given points of the
+    /// polynomial with the message as coefficients
+    pub fn encode(&self, points: &[F]) -> Vec<F> {
+        points
+            .iter()
+            .map(|r| self.univariate_fingerprint(r))
+            .collect()
+    }
+}
+
+impl<F: FftField> ReedSolomon<F> {
+    /// Codeword of the message over a FFT domain. The rate of the code is the
+    /// length of the message divided by the size of the domain.
+    pub fn encode_over_domain<D: EvaluationDomain<F>>(&self, domain: &D) -> Vec<F> {
+        assert!(
+            self.0.len() <= domain.size(),
+            "message longer than the domain"
+        );
+        domain.fft(&self.0)
+    }
+}
+
+/// Reed-Solomon codeword committed with a Merkle tree, one symbol per leaf. The
+/// verifier reads the codeword only at the positions it asks for.
+pub struct CommittedCodeword<F: Field, H: MerkleHasher<F> = Sha256Hasher> {
+    codeword: Vec<F>,
+    tree: MerkleTree<F, H>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CodewordCommitment<D: CanonicalSerialize + CanonicalDeserialize> {
+    pub root: D,
+    pub length: usize,
+}
+
+impl<F: Field, H: MerkleHasher<F>> CommittedCodeword<F, H> {
+    pub fn new(hasher: H, codeword: Vec<F>) -> Self {
+        let tree = MerkleTree::from_elements(hasher, &codeword);
+        CommittedCodeword { codeword, tree }
+    }
+
+    pub fn commitment(&self) -> CodewordCommitment<H::Digest> {
+        CodewordCommitment {
+            root: self.tree.root(),
+            length: self.codeword.len(),
+        }
+    }
+
+    pub fn codeword(&self) -> &[F] {
+        &self.codeword
+    }
+
+    pub fn open(&self, positions: &[usize]) -> MultiProof<F, H::Digest> {
+        self.tree.open_batch(positions)
+    }
+}
+
+impl<F: FftField, H: MerkleHasher<F>> CommittedCodeword<F, H> {
+    pub fn encode<D: EvaluationDomain<F>>(hasher: H, message: &ReedSolomon<F>, domain: &D) -> Self {
+        Self::new(hasher, message.encode_over_domain(domain))
+    }
+}
+
+impl<D: CanonicalSerialize + CanonicalDeserialize + Clone + Eq> CodewordCommitment<D> {
+    /// Checks the opening and returns the symbols at the given positions, in
+    /// the same order
+    pub fn verify_openings<F: Field, H: MerkleHasher<F, Digest = D>>(
+        &self,
+        hasher: &H,
+        positions: &[usize],
+        proof: &MultiProof<F, D>,
+    ) -> Option<Vec<F>> {
+        if !MerkleTree::verify(hasher, &self.root, self.length, positions, proof) {
+            return None;
+        }
+        positions
+            .iter()
+            .map(|p| proof.leaf(*p).and_then(|leaf| leaf.first().copied()))
+            .collect()
+    }
+}
+
+/// Random positions of a codeword of the given length for the verifier to
+/// spot-check
+pub fn sample_positions<R: RngCore>(rng: &mut R, length: usize, count: usize) -> Vec<usize> {
+    (0..count)
+        .map(|_| (rng.next_u64() % length as u64) as usize)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use crate::test_fields::F17 as Fq;
+    use ark_ff::UniformRand;
+    use ark_poly::Radix2EvaluationDomain;
+    use ark_std::test_rng;
     use ark_test_curves::bls12_381::Fr as F;
 
     #[test]
     fn test_custom_field() {
-        #[derive(MontConfig)]
-        #[modulus = "17"]
-        #[generator = "3"]
-        pub struct FqConfig;
-        pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
         let a = Fq::from(9);
         let b = Fq::from(10);
 
@@ -50,12 +144,6 @@ mod tests {
 
     #[test]
     fn test_inconsistent_data() {
-        #[derive(MontConfig)]
-        #[modulus = "17"]
-        #[generator = "3"]
-        pub struct FqConfig;
-        pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
         let a = vec![Fq::from(9), Fq::from(10)];
         let rs_a = ReedSolomon::new(a);
         let rs_b = ReedSolomon::new(vec![Fq::from(9), Fq::from(11)]);
@@ -76,4 +164,56 @@ mod tests {
         let r = F::from(8);
         assert_eq!(rs.univariate_fingerprint(&r), F::from(89));
     }
+
+    #[test]
+    fn test_encode() {
+        let message = ReedSolomon::new(vec![F::from(9), F::from(10)]);
+        let codeword = message.encode(&[F::from(0), F::from(8)]);
+        assert_eq!(codeword, vec![F::from(9), F::from(89)]);
+    }
+
+    #[test]
+    fn test_encode_over_domain() {
+        let message = ReedSolomon::new((1..=4).map(F::from).collect());
+        let domain = Radix2EvaluationDomain::<F>::new(16).unwrap();
+        let points: Vec<F> = domain.elements().collect();
+        assert_eq!(message.encode_over_domain(&domain), message.encode(&points));
+    }
+
+    #[test]
+    fn test_committed_codeword_spot_checks() {
+        let mut rng = test_rng();
+        let message = ReedSolomon::new((0..8).map(|_| F::rand(&mut rng)).collect());
+        let domain = Radix2EvaluationDomain::<F>::new(32).unwrap();
+        let committed = CommittedCodeword::encode(Sha256Hasher, &message, &domain);
+        let commitment = committed.commitment();
+
+        let positions = sample_positions(&mut rng, commitment.length, 10);
+        let proof = committed.open(&positions);
+        let values = commitment
+            .verify_openings(&Sha256Hasher, &positions, &proof)
+            .unwrap();
+        let points: Vec<F> = positions.iter().map(|p| domain.element(*p)).collect();
+        assert_eq!(values, message.encode(&points));
+    }
+
+    #[test]
+    fn test_committed_codeword_wrong_opening() {
+        let mut rng = test_rng();
+        let message = ReedSolomon::new((0..8).map(|_| F::rand(&mut rng)).collect());
+        let domain = Radix2EvaluationDomain::<F>::new(32).unwrap();
+        let committed = CommittedCodeword::encode(Sha256Hasher, &message, &domain);
+        let commitment = committed.commitment();
+
+        let mut proof = committed.open(&[3, 17]);
+        proof.leaves[0][0] += F::ONE;
+        assert!(commitment
+            .verify_openings(&Sha256Hasher, &[3, 17], &proof)
+            .is_none());
+
+        let proof = committed.open(&[3, 17]);
+        assert!(commitment
+            .verify_openings(&Sha256Hasher, &[3, 18], &proof)
+            .is_none());
+    }
 }
@@ -0,0 +1,358 @@
+//! Spartan (Setty 2020, Thaler Chapter 14.2 / 16): a SNARK for R1CS from two
+//! sumchecks and a commitment to the multilinear extension of the witness.
+//!
+//! z is laid out as ((1, io, 0...), (w, 0...)), both halves of size 2^{s-1}, so
+//! that z(r) = (1 - r_{s-1}) io(r') + r_{s-1} w(r') with r' = (r_0, ..., r_{s-2}).
+//! The zero-check
+//!
+//!   0 = sum_x eq(τ, x) (Az(x) Bz(x) - Cz(x))
+//!
+//! reduces satisfiability to the claims Az(r_x), Bz(r_x), Cz(r_x), which are
+//! merged with random coefficients into the inner sumcheck
+//!
+//!   sum_y (α A(r_x, y) + β B(r_x, y) + γ C(r_x, y)) z(y)
+//!
+//! ending in a single evaluation of w, opened from its commitment. The
+//! verifier evaluates the multilinear extensions of the matrices by itself, in
+//! time linear in their number of non zero entries. With `IdealOracle` as the
+//! commitment this is the interactive protocol with oracle access to w.
+use crate::multilinear::{eq_evaluate, eq_table};
+use crate::oracle::Oracle;
+use crate::pcs::MultilinearPcs;
+use crate::r1cs::{SparseMatrix, R1CS};
+use crate::sumcheck::{
+    prove_noninteractive, verify_noninteractive, ProductPolynomial, SumOfProducts, VerifierState,
+};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+
+/// Preprocessed R1CS and commitment parameters
+pub struct Spartan<F: Field, P: MultilinearPcs<F>> {
+    r1cs: R1CS<F>,
+    /// hash of the instance, which the transcript starts from
+    digest: Vec<u8>,
+    /// A, B and C with the columns in the layout of z
+    matrices: [SparseMatrix<F>; 3],
+    pcs: P,
+    row_vars: usize,
+    col_vars: usize,
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SpartanProof<F: Field, P: MultilinearPcs<F>> {
+    witness_commitment: P::Commitment,
+    outer_round_polys: Vec<UnivariatePolynomial<F>>,
+    /// Az(r_x), Bz(r_x) and Cz(r_x)
+    claims: [F; 3],
+    inner_round_polys: Vec<UnivariatePolynomial<F>>,
+    witness_eval: F,
+    witness_proof: P::Proof,
+}
+
+// final check of the zero-check, eq(τ, r_x) (Az(r_x) Bz(r_x) - Cz(r_x)) with
+// the values claimed by the prover
+struct OuterOracle<'a, F: Field> {
+    tau: &'a [F],
+    claims: [F; 3],
+}
+
+impl<F: Field> Oracle<F> for OuterOracle<'_, F> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        let [a, b, c] = self.claims;
+        eq_evaluate(self.tau, point) * (a * b - c)
+    }
+}
+
+// final check of the inner sumcheck, with z(r_y) built from the public values
+// and the claimed evaluation of the witness
+struct InnerOracle<'a, F: Field, P: MultilinearPcs<F>> {
+    spartan: &'a Spartan<F, P>,
+    rx: &'a [F],
+    coefficients: [F; 3],
+    public: &'a [F],
+    witness_eval: F,
+}
+
+impl<F: Field, P: MultilinearPcs<F>> Oracle<F> for InnerOracle<'_, F, P> {
+    type Point = [F];
+
+    fn query(&self, ry: &[F]) -> F {
+        let (ry_low, ry_top) = ry.split_at(self.spartan.col_vars - 1);
+        let io = self.spartan.public_half(self.public);
+        let io_eval = eq_table(ry_low)
+            .iter()
+            .zip(&io)
+            .map(|(e, v)| *e * v)
+            .sum::<F>();
+        let z_eval = (F::ONE - ry_top[0]) * io_eval + ry_top[0] * self.witness_eval;
+
+        let (eq_rx, eq_ry) = (eq_table(self.rx), eq_table(ry));
+        let matrix_eval: F = self
+            .spartan
+            .matrices
+            .iter()
+            .zip(self.coefficients)
+            .map(|(m, coefficient)| {
+                coefficient
+                    * m.entries()
+                        .map(|(i, j, v)| v * eq_rx[i] * eq_ry[j])
+                        .sum::<F>()
+            })
+            .sum();
+        matrix_eval * z_eval
+    }
+}
+
+impl<F: Field, P: MultilinearPcs<F>> Spartan<F, P> {
+    pub fn new<R: RngCore>(r1cs: R1CS<F>, rng: &mut R) -> Self {
+        let public_len = 1 + r1cs.num_public();
+        let half = public_len.max(r1cs.num_witness()).next_power_of_two();
+        let col_vars = half.trailing_zeros() as usize + 1;
+        // a sumcheck needs at least one variable
+        let row_vars =
+            (r1cs.num_constraints().next_power_of_two().trailing_zeros() as usize).max(1);
+        let column = |j: usize| {
+            if j < public_len {
+                j
+            } else {
+                half + j - public_len
+            }
+        };
+        let matrices = [&r1cs.a, &r1cs.b, &r1cs.c].map(|m| m.map_columns(2 * half, column));
+        Spartan {
+            pcs: P::setup(col_vars - 1, rng),
+            digest: r1cs.digest(),
+            r1cs,
+            matrices,
+            row_vars,
+            col_vars,
+        }
+    }
+
+    pub fn r1cs(&self) -> &R1CS<F> {
+        &self.r1cs
+    }
+
+    pub fn prove(&self, public: &[F], witness: &[F]) -> SpartanProof<F, P> {
+        assert_eq!(
+            public.len(),
+            self.r1cs.num_public(),
+            "wrong number of public values"
+        );
+        assert_eq!(
+            witness.len(),
+            self.r1cs.num_witness(),
+            "wrong witness length"
+        );
+        let half = 1 << (self.col_vars - 1);
+        let mut w = witness.to_vec();
+        w.resize(half, F::ZERO);
+        let z = [self.public_half(public), w.clone()].concat();
+        let w = DenseMultilinearExtension::from_evaluations_vec(self.col_vars - 1, w);
+        let (witness_commitment, data) = self.pcs.commit(&w);
+        let mut transcript = self.new_transcript(public, &witness_commitment);
+
+        // zero-check
+        let tau = transcript.challenge_fields(b"tau", self.row_vars);
+        let mut tables = vec![eq_table(&tau)];
+        for m in self.matrices.iter() {
+            let mut mz = m.mul_vector(&z);
+            mz.resize(1 << self.row_vars, F::ZERO);
+            tables.push(mz);
+        }
+        let terms = vec![(F::ONE, vec![0, 1, 2]), (-F::ONE, vec![0, 3])];
+        let (outer_round_polys, outer) = prove_noninteractive(
+            SumOfProducts::new(self.row_vars, tables, terms),
+            &mut transcript,
+        );
+        let rx = outer.get_random_vars().to_vec();
+        let evals = outer.get_poly().final_evaluations();
+        let claims = [evals[1], evals[2], evals[3]];
+        transcript.append_serializable(b"claims", &claims[..]);
+
+        // inner sumcheck over α A(r_x, y) + β B(r_x, y) + γ C(r_x, y)
+        let coefficients = transcript.challenge_fields::<F>(b"coefficients", 3);
+        let eq_rx = eq_table(&rx);
+        let mut combined = vec![F::ZERO; 2 * half];
+        for (m, coefficient) in self.matrices.iter().zip(coefficients) {
+            for (i, j, v) in m.entries() {
+                combined[j] += coefficient * v * eq_rx[i];
+            }
+        }
+        let (inner_round_polys, inner) = prove_noninteractive(
+            ProductPolynomial::new(self.col_vars, vec![combined, z]),
+            &mut transcript,
+        );
+        let ry = inner.get_random_vars();
+
+        let ry_low = &ry[..self.col_vars - 1];
+        let (witness_eval, witness_proof) = self.pcs.open(&w, &data, ry_low, &mut transcript);
+        SpartanProof {
+            witness_commitment,
+            outer_round_polys,
+            claims,
+            inner_round_polys,
+            witness_eval,
+            witness_proof,
+        }
+    }
+
+    pub fn verify(&self, public: &[F], proof: &SpartanProof<F, P>) -> Result<(), String> {
+        if public.len() != self.r1cs.num_public() {
+            return Err("wrong number of public values".to_string());
+        }
+        let mut transcript = self.new_transcript(public, &proof.witness_commitment);
+
+        let tau = transcript.challenge_fields(b"tau", self.row_vars);
+        let oracle = OuterOracle {
+            tau: &tau,
+            claims: proof.claims,
+        };
+        let verifier = VerifierState::with_oracle(F::ZERO, self.row_vars, oracle);
+        let rx = verify_noninteractive(verifier, &proof.outer_round_polys, 3, &mut transcript)
+            .map_err(|err| format!("zero-check: {}", err))?;
+        transcript.append_serializable(b"claims", &proof.claims[..]);
+
+        let coefficients = transcript.challenge_fields::<F>(b"coefficients", 3);
+        let claim = proof
+            .claims
+            .iter()
+            .zip(coefficients.iter())
+            .map(|(c, k)| *c * k)
+            .sum();
+        let oracle = InnerOracle {
+            spartan: self,
+            rx: &rx,
+            coefficients: [coefficients[0], coefficients[1], coefficients[2]],
+            public,
+            witness_eval: proof.witness_eval,
+        };
+        let verifier = VerifierState::with_oracle(claim, self.col_vars, oracle);
+        let ry = verify_noninteractive(verifier, &proof.inner_round_polys, 2, &mut transcript)
+            .map_err(|err| format!("inner sumcheck: {}", err))?;
+
+        let ry_low = &ry[..self.col_vars - 1];
+        if !self.pcs.verify(
+            &proof.witness_commitment,
+            ry_low,
+            proof.witness_eval,
+            &proof.witness_proof,
+            &mut transcript,
+        ) {
+            return Err("invalid opening of the witness".to_string());
+        }
+        Ok(())
+    }
+
+    // (1, io) padded to the size of the witness half
+    fn public_half(&self, public: &[F]) -> Vec<F> {
+        let mut io = vec![F::ONE];
+        io.extend_from_slice(public);
+        io.resize(1 << (self.col_vars - 1), F::ZERO);
+        io
+    }
+
+    fn new_transcript(&self, public: &[F], commitment: &P::Commitment) -> Transcript {
+        let mut transcript = Transcript::new(b"spartan");
+        transcript.append_message(b"instance", &self.digest);
+        transcript.append_serializable(b"public", public);
+        transcript.append_serializable(b"witness_commitment", commitment);
+        transcript
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hyrax::Hyrax;
+    use crate::kzg::MultilinearSrs;
+    use crate::pcs::IdealOracle;
+    use crate::r1cs::fixtures::{cubic, repeated_squaring};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr, G1Projective};
+
+    fn check_cubic<P: MultilinearPcs<Fr>>() {
+        let (r1cs, public, witness) = cubic(3, 35);
+        let spartan = Spartan::<Fr, P>::new(r1cs, &mut test_rng());
+        let proof = spartan.prove(&public, &witness);
+        assert_eq!(spartan.verify(&public, &proof), Ok(()));
+        assert!(spartan.verify(&[Fr::from(36)], &proof).is_err());
+    }
+
+    #[test]
+    fn test_cubic() {
+        check_cubic::<IdealOracle<DenseMultilinearExtension<Fr>>>();
+        check_cubic::<Hyrax<G1Projective>>();
+        check_cubic::<MultilinearSrs<Bls12_381>>();
+    }
+
+    #[test]
+    fn test_wrong_witness() {
+        let (r1cs, public, mut witness) = cubic(3, 35);
+        witness[2] = Fr::from(28);
+        let spartan = Spartan::<Fr, Hyrax<G1Projective>>::new(r1cs, &mut test_rng());
+        let proof = spartan.prove(&public, &witness);
+        let err = spartan.verify(&public, &proof).unwrap_err();
+        assert!(err.starts_with("zero-check"), "{}", err);
+    }
+
+    #[test]
+    fn test_tampered_proof() {
+        let (r1cs, public, witness) = cubic(3, 35);
+        let spartan = Spartan::<Fr, Hyrax<G1Projective>>::new(r1cs, &mut test_rng());
+
+        let mut proof = spartan.prove(&public, &witness);
+        proof.claims[2] += Fr::ONE;
+        assert!(spartan.verify(&public, &proof).is_err());
+
+        let mut proof = spartan.prove(&public, &witness);
+        proof.witness_eval += Fr::ONE;
+        assert!(spartan.verify(&public, &proof).is_err());
+
+        let mut proof = spartan.prove(&public, &witness);
+        proof.inner_round_polys.pop();
+        assert!(spartan.verify(&public, &proof).is_err());
+    }
+
+    #[test]
+    fn test_proof_is_bound_to_instance() {
+        // A and B swapped: the same assignments satisfy it
+        let (r1cs, public, witness) = cubic(3, 35);
+        let swapped = R1CS::new(r1cs.b.clone(), r1cs.a.clone(), r1cs.c.clone(), 1);
+        assert!(swapped.is_satisfied(&public, &witness).is_ok());
+        assert_ne!(r1cs.digest(), swapped.digest());
+
+        let spartan = Spartan::<Fr, Hyrax<G1Projective>>::new(r1cs, &mut test_rng());
+        let other = Spartan::<Fr, Hyrax<G1Projective>>::new(swapped, &mut test_rng());
+        let proof = spartan.prove(&public, &witness);
+        assert!(other.verify(&public, &proof).is_err());
+    }
+
+    #[test]
+    fn test_proof_serialization() {
+        let (r1cs, public, witness) = cubic(3, 35);
+        let spartan = Spartan::<Fr, Hyrax<G1Projective>>::new(r1cs, &mut test_rng());
+        let proof = spartan.prove(&public, &witness);
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded =
+            SpartanProof::<Fr, Hyrax<G1Projective>>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(spartan.verify(&public, &decoded), Ok(()));
+    }
+
+    #[test]
+    fn test_larger_instance() {
+        let (r1cs, public, witness) = repeated_squaring::<Fr>();
+        assert!(r1cs.is_satisfied(&public, &witness).is_ok());
+
+        let spartan = Spartan::<Fr, Hyrax<G1Projective>>::new(r1cs, &mut test_rng());
+        let proof = spartan.prove(&public, &witness);
+        assert_eq!(spartan.verify(&public, &proof), Ok(()));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::oracle::Oracle;
+use crate::sumcheck::{ProverState, SumcheckPolynomial, VerifierState};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_poly::Polynomial;
+
+/// Runs the prover with the challenges drawn from the transcript. Returns the
+/// round polynomials and the final state, holding the challenges and the
+/// polynomial bound to them.
+pub fn prove_noninteractive<F: Field, P: SumcheckPolynomial<F>>(
+    poly: P,
+    transcript: &mut Transcript,
+) -> (Vec<UnivariatePolynomial<F>>, ProverState<F, P>) {
+    let num_vars = poly.num_vars();
+    let mut prover = ProverState::new(poly);
+    let mut round_polys = Vec::with_capacity(num_vars);
+    for _ in 0..num_vars {
+        let round_poly = prover.calculate_round_poly();
+        prover.update_random_vars(round_challenge(&round_poly, transcript));
+        round_polys.push(round_poly);
+    }
+    (round_polys, prover)
+}
+
+/// Checks the round polynomials of `prove_noninteractive`, each of degree at
+/// most max_degree, and returns the challenges
+pub fn verify_noninteractive<F: Field, O: Oracle<F, Point = [F]>>(
+    mut verifier: VerifierState<F, O>,
+    round_polys: &[UnivariatePolynomial<F>],
+    max_degree: usize,
+    transcript: &mut Transcript,
+) -> Result<Vec<F>, String> {
+    if round_polys.len() != verifier.get_total_rounds() {
+        return Err("invalid number of rounds".to_string());
+    }
+    for round_poly in round_polys {
+        if round_poly.degree() > max_degree {
+            return Err("round polynomial degree too high".to_string());
+        }
+        let r = round_challenge(round_poly, transcript);
+        verifier.check_round(round_poly.clone(), r)?;
+    }
+    Ok(verifier.get_random_vars().to_vec())
+}
+
+/// Absorbs the round polynomial and draws the challenge of the round
+pub fn round_challenge<F: Field>(
+    round_poly: &UnivariatePolynomial<F>,
+    transcript: &mut Transcript,
+) -> F {
+    transcript.append_serializable(b"round_poly", round_poly);
+    transcript.challenge_field(b"r")
+}
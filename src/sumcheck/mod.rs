@@ -1,7 +1,12 @@
+mod fiat_shamir;
+mod polynomial;
 mod protocol;
 mod prover;
 mod verifier;
+pub mod zk;
 
+pub use fiat_shamir::{prove_noninteractive, round_challenge, verify_noninteractive};
+pub use polynomial::{evaluate_terms, ProductPolynomial, SumOfProducts, SumcheckPolynomial};
 pub use protocol::{Prover, ProverMessage, Verifier, VerifierMessage};
 pub use prover::ProverState;
 pub use verifier::VerifierState;
@@ -0,0 +1,300 @@
+use crate::multilinear::number_to_domain;
+use crate::polynomial::{cast_mv_to_uv_polynomial, partial_evaluate_compact};
+use crate::univariate::lagrange_interpolate;
+use ark_ff::{Field, Zero};
+use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    polynomial::DenseMVPolynomial,
+    Polynomial,
+};
+
+/// Polynomial the sumcheck prover can run over. The variables are bound in
+/// order, so the first free variable is always the one of the actual round.
+pub trait SumcheckPolynomial<F: Field> {
+    fn num_vars(&self) -> usize;
+
+    /// Sum of the evaluations over the boolean hypercube
+    fn hypercube_sum(&self) -> F;
+
+    /// Univariate polynomial in the first free variable, after summing the
+    /// remaining ones over {0, 1}
+    fn round_poly(&self) -> UnivariatePolynomial<F>;
+
+    /// Binds the first free variable to r
+    fn fix_first_variable(&mut self, r: F);
+}
+
+impl<F: Field> SumcheckPolynomial<F> for SparsePolynomial<F, SparseTerm> {
+    fn num_vars(&self) -> usize {
+        DenseMVPolynomial::num_vars(self)
+    }
+
+    fn hypercube_sum(&self) -> F {
+        let num_vars = self.num_vars;
+        let mut result = F::ZERO;
+        for i in 0..(1 << num_vars) {
+            let binary = number_to_domain(i, num_vars);
+            result += self.evaluate(&binary);
+        }
+        result
+    }
+
+    fn round_poly(&self) -> UnivariatePolynomial<F> {
+        let mut round_poly = SparsePolynomial::<F, SparseTerm>::zero();
+        let remaining_vars = self.num_vars - 1;
+        for i in 0..(1 << remaining_vars) {
+            let binary: Vec<F> = number_to_domain(i, remaining_vars);
+            let values: Vec<(usize, F)> = std::iter::zip(1..=remaining_vars, binary).collect();
+            round_poly += &partial_evaluate_compact(self, &values);
+        }
+        cast_mv_to_uv_polynomial(round_poly)
+    }
+
+    fn fix_first_variable(&mut self, r: F) {
+        *self = partial_evaluate_compact(self, &[(0, r)]);
+    }
+}
+
+/// Product of multilinear polynomials, given by their evaluations over the
+/// boolean hypercube. Every round takes time linear in the size of the tables.
+pub struct ProductPolynomial<F: Field> {
+    num_vars: usize,
+    tables: Vec<Vec<F>>,
+}
+
+impl<F: Field> ProductPolynomial<F> {
+    pub fn new(num_vars: usize, tables: Vec<Vec<F>>) -> Self {
+        assert!(!tables.is_empty(), "product of no polynomials");
+        assert!(
+            tables.iter().all(|t| t.len() == 1 << num_vars),
+            "tables must have 2^num_vars evaluations"
+        );
+        ProductPolynomial { num_vars, tables }
+    }
+
+    /// Evaluations of the factors, once every variable is bound
+    pub fn final_evaluations(&self) -> Vec<F> {
+        assert_eq!(self.num_vars, 0, "there are free variables left");
+        self.tables.iter().map(|t| t[0]).collect()
+    }
+}
+
+impl<F: Field> SumcheckPolynomial<F> for ProductPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn hypercube_sum(&self) -> F {
+        (0..(1 << self.num_vars))
+            .map(|x| self.tables.iter().map(|t| t[x]).product::<F>())
+            .sum()
+    }
+
+    fn round_poly(&self) -> UnivariatePolynomial<F> {
+        // the round polynomial has degree equal to the number of factors, so it
+        // is interpolated from its evaluations at 0, 1, ..., degree
+        let degree = self.tables.len();
+        let half = 1 << (self.num_vars - 1);
+        let points: Vec<(F, F)> = (0..=degree)
+            .map(|c| {
+                let c = F::from(c as u64);
+                let eval = (0..half)
+                    .map(|x| {
+                        self.tables
+                            .iter()
+                            .map(|t| t[2 * x] + c * (t[2 * x + 1] - t[2 * x]))
+                            .product::<F>()
+                    })
+                    .sum();
+                (c, eval)
+            })
+            .collect();
+
+        let coeffs = lagrange_interpolate(&points).coeffs;
+        UnivariatePolynomial::from_coefficients_vec(coeffs.into_iter().enumerate().collect())
+    }
+
+    fn fix_first_variable(&mut self, r: F) {
+        fix_tables(&mut self.tables, r);
+        self.num_vars -= 1;
+    }
+}
+
+/// Linear combination of products of multilinear polynomials sharing the same
+/// tables, sum_k c_k prod_{i in S_k} t_i, e.g. eq * (a * b - c)
+pub struct SumOfProducts<F: Field> {
+    num_vars: usize,
+    tables: Vec<Vec<F>>,
+    /// coefficient and indexes of the tables of every product
+    terms: Vec<(F, Vec<usize>)>,
+}
+
+impl<F: Field> SumOfProducts<F> {
+    pub fn new(num_vars: usize, tables: Vec<Vec<F>>, terms: Vec<(F, Vec<usize>)>) -> Self {
+        assert!(
+            tables.iter().all(|t| t.len() == 1 << num_vars),
+            "tables must have 2^num_vars evaluations"
+        );
+        assert!(
+            terms.iter().flat_map(|(_, t)| t).all(|i| *i < tables.len()),
+            "term with a table out of range"
+        );
+        SumOfProducts {
+            num_vars,
+            tables,
+            terms,
+        }
+    }
+
+    /// Evaluations of the tables, once every variable is bound
+    pub fn final_evaluations(&self) -> Vec<F> {
+        assert_eq!(self.num_vars, 0, "there are free variables left");
+        self.tables.iter().map(|t| t[0]).collect()
+    }
+
+    fn evaluate_terms(&self, values: &[F]) -> F {
+        evaluate_terms(&self.terms, values)
+    }
+}
+
+/// sum_k c_k prod_{i in S_k} values_i, the terms of a `SumOfProducts` at the
+/// values of its tables
+pub fn evaluate_terms<F: Field>(terms: &[(F, Vec<usize>)], values: &[F]) -> F {
+    terms
+        .iter()
+        .map(|(c, factors)| *c * factors.iter().map(|i| values[*i]).product::<F>())
+        .sum()
+}
+
+impl<F: Field> SumcheckPolynomial<F> for SumOfProducts<F> {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn hypercube_sum(&self) -> F {
+        (0..(1 << self.num_vars))
+            .map(|x| {
+                let values: Vec<F> = self.tables.iter().map(|t| t[x]).collect();
+                self.evaluate_terms(&values)
+            })
+            .sum()
+    }
+
+    fn round_poly(&self) -> UnivariatePolynomial<F> {
+        let degree = self.terms.iter().map(|(_, t)| t.len()).max().unwrap_or(0);
+        let half = 1 << (self.num_vars - 1);
+        let points: Vec<(F, F)> = (0..=degree)
+            .map(|c| {
+                let c = F::from(c as u64);
+                let eval = (0..half)
+                    .map(|x| {
+                        let values: Vec<F> = self
+                            .tables
+                            .iter()
+                            .map(|t| t[2 * x] + c * (t[2 * x + 1] - t[2 * x]))
+                            .collect();
+                        self.evaluate_terms(&values)
+                    })
+                    .sum();
+                (c, eval)
+            })
+            .collect();
+
+        let coeffs = lagrange_interpolate(&points).coeffs;
+        UnivariatePolynomial::from_coefficients_vec(coeffs.into_iter().enumerate().collect())
+    }
+
+    fn fix_first_variable(&mut self, r: F) {
+        fix_tables(&mut self.tables, r);
+        self.num_vars -= 1;
+    }
+}
+
+// binds the lowest variable of every table to r
+fn fix_tables<F: Field>(tables: &mut [Vec<F>], r: F) {
+    for table in tables.iter_mut() {
+        let half = table.len() / 2;
+        for x in 0..half {
+            table[x] = table[2 * x] + r * (table[2 * x + 1] - table[2 * x]);
+        }
+        table.truncate(half);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_product_polynomial_rounds() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let tables: Vec<Vec<Fr>> = (0..3)
+            .map(|_| (0..(1 << num_vars)).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let mles: Vec<_> = tables
+            .iter()
+            .map(|t| DenseMultilinearExtension::from_evaluations_slice(num_vars, t))
+            .collect();
+        let mut poly = ProductPolynomial::new(num_vars, tables);
+
+        let mut claim = poly.hypercube_sum();
+        let mut rs = vec![];
+        for _ in 0..num_vars {
+            let round_poly = poly.round_poly();
+            assert_eq!(round_poly.degree(), 3);
+            assert_eq!(
+                round_poly.evaluate(&Fr::ZERO) + round_poly.evaluate(&Fr::ONE),
+                claim
+            );
+            let r = Fr::rand(&mut rng);
+            claim = round_poly.evaluate(&r);
+            poly.fix_first_variable(r);
+            rs.push(r);
+        }
+
+        let should: Fr = mles.iter().map(|mle| mle.evaluate(&rs).unwrap()).product();
+        assert_eq!(poly.final_evaluations().iter().product::<Fr>(), should);
+        assert_eq!(claim, should);
+    }
+
+    #[test]
+    fn test_sum_of_products_rounds() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let tables: Vec<Vec<Fr>> = (0..4)
+            .map(|_| (0..(1 << num_vars)).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        let mles: Vec<_> = tables
+            .iter()
+            .map(|t| DenseMultilinearExtension::from_evaluations_slice(num_vars, t))
+            .collect();
+        // t0 * (t1 * t2 - t3)
+        let terms = vec![(Fr::ONE, vec![0, 1, 2]), (-Fr::ONE, vec![0, 3])];
+        let mut poly = SumOfProducts::new(num_vars, tables, terms);
+
+        let mut claim = poly.hypercube_sum();
+        let mut rs = vec![];
+        for _ in 0..num_vars {
+            let round_poly = poly.round_poly();
+            assert_eq!(round_poly.degree(), 3);
+            assert_eq!(
+                round_poly.evaluate(&Fr::ZERO) + round_poly.evaluate(&Fr::ONE),
+                claim
+            );
+            let r = Fr::rand(&mut rng);
+            claim = round_poly.evaluate(&r);
+            poly.fix_first_variable(r);
+            rs.push(r);
+        }
+
+        let v: Vec<Fr> = mles.iter().map(|mle| mle.evaluate(&rs).unwrap()).collect();
+        assert_eq!(poly.final_evaluations(), v);
+        assert_eq!(claim, v[0] * (v[1] * v[2] - v[3]));
+    }
+}
@@ -1,22 +1,17 @@
-use crate::polynomial::{assign_value, cast_mv_to_uv_polynomial, reduced_to_univariate};
-use ark_ff::{Field, Zero};
+use crate::sumcheck::SumcheckPolynomial;
+use ark_ff::Field;
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm};
 use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
-use ark_poly::{
-    multivariate::{SparsePolynomial, SparseTerm},
-    polynomial::DenseMVPolynomial,
-    Polynomial,
-};
 
-pub struct ProverState<F: Field> {
-    poly: SparsePolynomial<F, SparseTerm>, // Use concrete type
+pub struct ProverState<F: Field, P: SumcheckPolynomial<F> = SparsePolynomial<F, SparseTerm>> {
+    poly: P,
     total_rounds: usize,
     actual_round: usize,
     rs: Vec<F>,
 }
 
-impl<F: Field> ProverState<F> {
-    pub fn new(poly: SparsePolynomial<F, SparseTerm>) -> Self {
-        // Accept concrete type
+impl<F: Field, P: SumcheckPolynomial<F>> ProverState<F, P> {
+    pub fn new(poly: P) -> Self {
         let total_rounds = poly.num_vars();
         ProverState {
             poly,
@@ -26,57 +21,40 @@ impl<F: Field> ProverState<F> {
         }
     }
 
-    // convert number into {0, 1}^domain
-    fn number_to_domain(number: usize, domain: usize) -> Vec<F> {
-        (0..domain)
-            .map(|j| {
-                if (number & (1 << j)) != 0 {
-                    F::ONE
-                } else {
-                    F::ZERO
-                }
-            })
-            .collect()
-    }
-
     pub fn calculate_sum(&self) -> F {
-        let mut result = F::ZERO;
-        for i in 0..(1 << self.total_rounds) {
-            let binary = ProverState::number_to_domain(i, self.total_rounds);
-            result += self.poly.evaluate(&binary);
-        }
-        result
+        self.poly.hypercube_sum()
     }
 
     pub fn calculate_round_poly(&self) -> UnivariatePolynomial<F> {
-        let mut round_poly = SparsePolynomial::<F, SparseTerm>::zero();
-        let remaining_rounds = self.total_rounds - self.actual_round - 1;
-        for i in 0..(1 << remaining_rounds) {
-            let binary: Vec<F> = ProverState::number_to_domain(i, remaining_rounds);
-            let values = std::iter::zip(1..=remaining_rounds, binary).collect();
-            round_poly += &reduced_to_univariate(&self.poly, values);
-        }
-        cast_mv_to_uv_polynomial(round_poly)
+        assert!(
+            self.actual_round < self.total_rounds,
+            "Invalid round number"
+        );
+        self.poly.round_poly()
     }
 
     pub fn update_random_vars(&mut self, r: F) {
-        self.poly = assign_value(self.poly.clone(), 0, r);
+        self.poly.fix_first_variable(r);
         self.rs.push(r);
         self.actual_round += 1;
     }
+
+    pub fn get_random_vars(&self) -> &[F] {
+        &self.rs
+    }
+
+    /// Polynomial with the random values of the past rounds assigned
+    pub fn get_poly(&self) -> &P {
+        &self.poly
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use crate::test_fields::F17;
     use ark_poly::multivariate::Term;
-
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    pub struct F17Config;
-    pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+    use ark_poly::{DenseMVPolynomial, Polynomial};
 
     /// examples and solutions taken from SumCheck example in
     /// Thaler's Chp. 4
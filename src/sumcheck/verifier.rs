@@ -1,3 +1,4 @@
+use crate::oracle::Oracle;
 use ark_ff::{Field, Zero};
 use ark_poly::univariate::SparsePolynomial as UnivariatePolynomial;
 use ark_poly::{
@@ -6,9 +7,9 @@ use ark_poly::{
 };
 use ark_std::test_rng;
 
-pub struct VerifierState<F: Field> {
+pub struct VerifierState<F: Field, O: Oracle<F, Point = [F]> = SparsePolynomial<F, SparseTerm>> {
     solution: F,
-    poly: SparsePolynomial<F, SparseTerm>,
+    poly: O,
     total_rounds: usize,
     actual_round: usize,
     running_poly: UnivariatePolynomial<F>,
@@ -18,13 +19,21 @@ pub struct VerifierState<F: Field> {
 impl<F: Field> VerifierState<F> {
     pub fn new(result: F, poly: SparsePolynomial<F, SparseTerm>) -> Self {
         let total_rounds = poly.num_vars;
+        VerifierState::with_oracle(result, total_rounds, poly)
+    }
+}
+
+impl<F: Field, O: Oracle<F, Point = [F]>> VerifierState<F, O> {
+    /// The verifier only queries the oracle once, at the random point of the
+    /// last round
+    pub fn with_oracle(result: F, num_vars: usize, oracle: O) -> Self {
         VerifierState {
             solution: result,
-            poly,
+            poly: oracle,
             running_poly: UnivariatePolynomial::<F>::zero(),
-            total_rounds,
+            total_rounds: num_vars,
             actual_round: 0,
-            rs: Vec::with_capacity(total_rounds),
+            rs: Vec::with_capacity(num_vars),
         }
     }
 
@@ -36,36 +45,53 @@ impl<F: Field> VerifierState<F> {
         self.actual_round
     }
 
+    pub fn get_random_vars(&self) -> &[F] {
+        &self.rs
+    }
+
     pub fn verify_round(&mut self, round_poly: UnivariatePolynomial<F>) -> F {
-        // TODO: Improve Error handling with result
-        assert!(
-            self.actual_round < self.total_rounds,
-            "Invalid round number"
-        );
+        let field = F::rand(&mut test_rng());
+        if let Err(err) = self.check_round(round_poly, field) {
+            panic!("{}", err);
+        }
+        field
+    }
+
+    /// Checks the round polynomial and binds the round variable to the given
+    /// challenge, e.g. one derived from a transcript
+    pub fn check_round(
+        &mut self,
+        round_poly: UnivariatePolynomial<F>,
+        challenge: F,
+    ) -> Result<(), String> {
+        if self.actual_round >= self.total_rounds {
+            return Err("Invalid round number".to_string());
+        }
 
         let round_value = round_poly.evaluate(&F::ZERO) + round_poly.evaluate(&F::ONE);
-        if self.actual_round == 0 {
-            assert_eq!(round_value, self.solution);
+        let expected = if self.actual_round == 0 {
+            self.solution
         } else {
-            assert_eq!(
-                round_value,
-                self.running_poly.evaluate(self.rs.last().unwrap())
-            );
+            self.running_poly.evaluate(self.rs.last().unwrap())
+        };
+        if round_value != expected {
+            return Err(format!(
+                "round {}: g(0) + g(1) does not match the previous claim",
+                self.actual_round
+            ));
         }
 
         self.actual_round += 1;
-        let field = F::rand(&mut test_rng());
-        self.rs.push(field);
+        self.rs.push(challenge);
         self.running_poly = round_poly;
 
-        if self.actual_round == self.total_rounds {
-            assert_eq!(
-                self.running_poly.evaluate(&field),
-                self.poly.evaluate(&self.rs)
-            );
+        if self.actual_round == self.total_rounds
+            && self.running_poly.evaluate(&challenge) != self.poly.query(&self.rs)
+        {
+            return Err("final evaluation does not match the oracle".to_string());
         }
 
-        field
+        Ok(())
     }
 }
 
@@ -73,15 +99,9 @@ impl<F: Field> VerifierState<F> {
 mod tests {
     use super::*;
     use crate::sumcheck::ProverState;
-    use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+    use crate::test_fields::F17;
     use ark_poly::{multivariate::Term, DenseMVPolynomial};
 
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    pub struct F17Config;
-    pub type F17 = Fp64<MontBackend<F17Config, 1>>;
-
     /// examples and solutions taken from SumCheck example in
     /// Thaler's Chp. 4
     fn setup() -> SparsePolynomial<F17, SparseTerm> {
@@ -0,0 +1,669 @@
+//! Zero-knowledge sumcheck with a masking polynomial (Chiesa, Forbes, Spooner
+//! 2017; Xie et al. 2019 "Libra").
+//!
+//! The round polynomials of the plain sumcheck are partial sums of g, which
+//! the verifier could not compute by itself. To hide them, the prover samples
+//! a random p(x) = sum_i p_i(x_i), with p_i of the degree of g in x_i, commits
+//! to every p_i and sends P = sum_{x in {0,1}^n} p(x). After a random ρ from the
+//! verifier, both run the sumcheck of
+//!
+//!   sum_x g(x) + ρ p(x) = H + ρ P.
+//!
+//! In round i, the mask adds ρ 2^(n-i-1) p_i(X) to the round polynomial, so
+//! it is uniformly random apart from the consistency with the previous
+//! round. At the end the verifier evaluates g(r) itself and gets
+//! p(r) = sum_i p_i(r_i) from openings of the commitments. The transcript
+//! depends on g only through H and g(r), which is what the simulator in the
+//! tests relies on. The protocol is honest-verifier zero knowledge when the
+//! commitments are hiding and their openings reveal nothing but p_i(r_i);
+//! the schemes of this crate are not, e.g. `IdealOracle` sends p itself.
+use crate::oracle::Oracle;
+use crate::pcs::UnivariatePcs;
+use crate::sumcheck::{prove_noninteractive, round_challenge, SumcheckPolynomial, VerifierState};
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_poly::multivariate::{SparsePolynomial, SparseTerm, Term};
+use ark_poly::univariate::{DensePolynomial, SparsePolynomial as UnivariatePolynomial};
+use ark_poly::{DenseMVPolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::RngCore;
+
+/// Sum of univariate polynomials p(x) = sum_i p_i(x_i)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaskingPolynomial<F: Field> {
+    univariates: Vec<DensePolynomial<F>>,
+}
+
+impl<F: Field> MaskingPolynomial<F> {
+    pub fn new(univariates: Vec<DensePolynomial<F>>) -> Self {
+        MaskingPolynomial { univariates }
+    }
+
+    /// Random mask with p_i of degree degrees[i]
+    pub fn rand<R: RngCore>(degrees: &[usize], rng: &mut R) -> Self {
+        let univariates = degrees
+            .iter()
+            .map(|d| {
+                DensePolynomial::from_coefficients_vec((0..=*d).map(|_| F::rand(rng)).collect())
+            })
+            .collect();
+        MaskingPolynomial { univariates }
+    }
+
+    pub fn univariates(&self) -> &[DensePolynomial<F>] {
+        &self.univariates
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.univariates.len()
+    }
+
+    /// sum_{x in {0,1}^n} p(x) = 2^(n-1) sum_i (p_i(0) + p_i(1))
+    pub fn hypercube_sum(&self) -> F {
+        let Some(n) = self.num_vars().checked_sub(1) else {
+            return F::ZERO;
+        };
+        let sum: F = self
+            .univariates
+            .iter()
+            .map(|p| p.evaluate(&F::ZERO) + p.evaluate(&F::ONE))
+            .sum();
+        sum * F::from(2u64).pow([n as u64])
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars(),
+            "point of a different dimension"
+        );
+        self.univariates
+            .iter()
+            .zip(point)
+            .map(|(p, x)| p.evaluate(x))
+            .sum()
+    }
+
+    /// Terms of c * p as a multivariate polynomial
+    fn scaled_terms(&self, c: F) -> Vec<(F, SparseTerm)> {
+        self.univariates
+            .iter()
+            .enumerate()
+            .flat_map(|(i, p)| {
+                p.coeffs
+                    .iter()
+                    .enumerate()
+                    .map(move |(power, coeff)| (c * coeff, SparseTerm::new(vec![(i, power)])))
+            })
+            .collect()
+    }
+}
+
+/// Degree of g in every variable
+pub fn variable_degrees<F: Field>(g: &SparsePolynomial<F, SparseTerm>) -> Vec<usize> {
+    let mut degrees = vec![0; g.num_vars];
+    for (_, term) in &g.terms {
+        for (var, power) in term.iter() {
+            degrees[*var] = degrees[*var].max(*power);
+        }
+    }
+    degrees
+}
+
+/// g + ρ p
+pub fn mask<F: Field>(
+    g: &SparsePolynomial<F, SparseTerm>,
+    p: &MaskingPolynomial<F>,
+    rho: F,
+) -> SparsePolynomial<F, SparseTerm> {
+    assert_eq!(g.num_vars, p.num_vars(), "mask of a different dimension");
+    let mut terms = g.terms.clone();
+    terms.extend(p.scaled_terms(rho));
+    SparsePolynomial::from_coefficients_vec(g.num_vars, terms)
+}
+
+/// g + ρ p for the sumcheck prover, the sums of the mask over the remaining
+/// variables computed from its univariates
+pub struct MaskedPolynomial<F: Field, P: SumcheckPolynomial<F>> {
+    g: P,
+    mask: MaskingPolynomial<F>,
+    rho: F,
+    /// sum of p_j(r_j) over the bound variables
+    bound: F,
+    round: usize,
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> MaskedPolynomial<F, P> {
+    pub fn new(g: P, mask: MaskingPolynomial<F>, rho: F) -> Self {
+        assert_eq!(
+            g.num_vars(),
+            mask.num_vars(),
+            "mask of a different dimension"
+        );
+        MaskedPolynomial {
+            g,
+            mask,
+            rho,
+            bound: F::ZERO,
+            round: 0,
+        }
+    }
+
+    // sum of p_j(0) + p_j(1) over the variables after the actual one
+    fn later_sums(&self) -> F {
+        self.mask.univariates[self.round + 1..]
+            .iter()
+            .map(|p| p.evaluate(&F::ZERO) + p.evaluate(&F::ONE))
+            .sum()
+    }
+}
+
+impl<F: Field, P: SumcheckPolynomial<F>> SumcheckPolynomial<F> for MaskedPolynomial<F, P> {
+    fn num_vars(&self) -> usize {
+        self.g.num_vars()
+    }
+
+    fn hypercube_sum(&self) -> F {
+        let free = self.num_vars();
+        if free == 0 {
+            return self.g.hypercube_sum() + self.rho * self.bound;
+        }
+        let actual = &self.mask.univariates[self.round];
+        let sums = actual.evaluate(&F::ZERO) + actual.evaluate(&F::ONE) + self.later_sums();
+        let two = F::from(2u64);
+        let mask_sum = two.pow([free as u64]) * self.bound + two.pow([free as u64 - 1]) * sums;
+        self.g.hypercube_sum() + self.rho * mask_sum
+    }
+
+    // with m free variables, the mask adds
+    // ρ (2^(m-1) (bound + p_i(X)) + 2^(m-2) sum_{j > i} (p_j(0) + p_j(1)))
+    fn round_poly(&self) -> UnivariatePolynomial<F> {
+        let free = self.num_vars() as u64;
+        let two = F::from(2u64);
+        let mut coeffs = self.mask.univariates[self.round].coeffs.clone();
+        if coeffs.is_empty() {
+            coeffs.push(F::ZERO);
+        }
+        coeffs[0] += self.bound;
+        let mut coeffs: Vec<F> = coeffs
+            .iter()
+            .map(|c| *c * self.rho * two.pow([free - 1]))
+            .collect();
+        if free > 1 {
+            coeffs[0] += self.rho * two.pow([free - 2]) * self.later_sums();
+        }
+        for (power, c) in self.g.round_poly().iter() {
+            if *power >= coeffs.len() {
+                coeffs.resize(power + 1, F::ZERO);
+            }
+            coeffs[*power] += c;
+        }
+        UnivariatePolynomial::from_coefficients_vec(coeffs.into_iter().enumerate().collect())
+    }
+
+    fn fix_first_variable(&mut self, r: F) {
+        self.g.fix_first_variable(r);
+        self.bound += self.mask.univariates[self.round].evaluate(&r);
+        self.round += 1;
+    }
+}
+
+// final check of the masked sumcheck, g(r) + ρ p(r) with p(r) opened by the
+// prover
+struct MaskedOracle<'a, F: Field, O: Oracle<F, Point = [F]>> {
+    g: &'a O,
+    rho: F,
+    mask_eval: F,
+}
+
+impl<F: Field, O: Oracle<F, Point = [F]>> Oracle<F> for MaskedOracle<'_, F, O> {
+    type Point = [F];
+
+    fn query(&self, point: &[F]) -> F {
+        self.g.query(point) + self.rho * self.mask_eval
+    }
+}
+
+/// Sumcheck hiding the partial sums of g, with the mask committed through a
+/// univariate scheme
+pub struct ZkSumcheck<F: Field, P: UnivariatePcs<F>> {
+    pcs: P,
+    _field: std::marker::PhantomData<F>,
+}
+
+pub struct ZkSumcheckProof<F: Field, P: UnivariatePcs<F>> {
+    mask_commitments: Vec<P::Commitment>,
+    /// sum of the mask over the hypercube
+    mask_sum: F,
+    round_polys: Vec<UnivariatePolynomial<F>>,
+    /// p_i(r_i)
+    mask_evals: Vec<F>,
+    openings: Vec<P::Proof>,
+}
+
+impl<F: Field, P: UnivariatePcs<F>> ZkSumcheck<F, P> {
+    /// For polynomials of degree at most max_degree in every variable
+    pub fn new<R: RngCore>(max_degree: usize, rng: &mut R) -> Self {
+        ZkSumcheck {
+            pcs: P::setup(max_degree + 1, rng),
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the sum of g over the hypercube, the proof and the random point
+    /// the sumcheck ends at
+    pub fn prove<R: RngCore>(
+        &self,
+        g: &SparsePolynomial<F, SparseTerm>,
+        rng: &mut R,
+        transcript: &mut Transcript,
+    ) -> (F, ZkSumcheckProof<F, P>, Vec<F>) {
+        self.prove_masked(g.clone(), &variable_degrees(g), rng, transcript)
+    }
+
+    /// Same as `prove` for any sumcheck polynomial, with a mask of the given
+    /// degree in every variable, at least the degree of g
+    pub fn prove_masked<Q: SumcheckPolynomial<F>, R: RngCore>(
+        &self,
+        g: Q,
+        degrees: &[usize],
+        rng: &mut R,
+        transcript: &mut Transcript,
+    ) -> (F, ZkSumcheckProof<F, P>, Vec<F>) {
+        let sum = g.hypercube_sum();
+        let p = MaskingPolynomial::rand(degrees, rng);
+        let (mask_commitments, mask_data): (Vec<_>, Vec<_>) =
+            p.univariates.iter().map(|p_i| self.pcs.commit(p_i)).unzip();
+        let mask_sum = p.hypercube_sum();
+        transcript.append_field(b"zk_sum", &sum);
+        transcript.append_serializable(b"zk_mask_commitments", &mask_commitments);
+        transcript.append_field(b"zk_mask_sum", &mask_sum);
+        let rho = transcript.challenge_field::<F>(b"zk_rho");
+
+        let (round_polys, prover) =
+            prove_noninteractive(MaskedPolynomial::new(g, p.clone(), rho), transcript);
+        let r = prover.get_random_vars().to_vec();
+        let mask_evals: Vec<F> = p
+            .univariates
+            .iter()
+            .zip(&r)
+            .map(|(p_i, r_i)| p_i.evaluate(r_i))
+            .collect();
+        transcript.append_serializable(b"zk_mask_evals", &mask_evals);
+        let openings = p
+            .univariates
+            .iter()
+            .zip(&mask_data)
+            .zip(&r)
+            .map(|((p_i, data), r_i)| self.pcs.open(p_i, data, r_i, transcript).1)
+            .collect();
+
+        let proof = ZkSumcheckProof {
+            mask_commitments,
+            mask_sum,
+            round_polys,
+            mask_evals,
+            openings,
+        };
+        (sum, proof, r)
+    }
+
+    pub fn pcs(&self) -> &P {
+        &self.pcs
+    }
+
+    /// Checks that g sums to the claim, with query access to g and its degree
+    /// in every variable. Returns the random point the sumcheck ends at.
+    pub fn verify<O: Oracle<F, Point = [F]>>(
+        &self,
+        g: &O,
+        degrees: &[usize],
+        claim: F,
+        proof: &ZkSumcheckProof<F, P>,
+        transcript: &mut Transcript,
+    ) -> Result<Vec<F>, String> {
+        let (rho, r) = self.challenges(degrees.len(), claim, proof, transcript)?;
+        check_rounds(g, degrees, claim, proof, rho, &r)?;
+        Ok(r)
+    }
+
+    /// Replays the transcript of a proof in n variables and checks the
+    /// openings of the mask. Returns ρ and the challenges of the rounds.
+    pub fn challenges(
+        &self,
+        n: usize,
+        claim: F,
+        proof: &ZkSumcheckProof<F, P>,
+        transcript: &mut Transcript,
+    ) -> Result<(F, Vec<F>), String> {
+        if proof.mask_commitments.len() != n
+            || proof.mask_evals.len() != n
+            || proof.openings.len() != n
+            || proof.round_polys.len() != n
+        {
+            return Err("malformed proof".to_string());
+        }
+        transcript.append_field(b"zk_sum", &claim);
+        transcript.append_serializable(b"zk_mask_commitments", &proof.mask_commitments);
+        transcript.append_field(b"zk_mask_sum", &proof.mask_sum);
+        let rho = transcript.challenge_field::<F>(b"zk_rho");
+        let r: Vec<F> = proof
+            .round_polys
+            .iter()
+            .map(|round_poly| round_challenge(round_poly, transcript))
+            .collect();
+        transcript.append_serializable(b"zk_mask_evals", &proof.mask_evals);
+        for (i, r_i) in r.iter().enumerate() {
+            let valid = self.pcs.verify(
+                &proof.mask_commitments[i],
+                r_i,
+                proof.mask_evals[i],
+                &proof.openings[i],
+                transcript,
+            );
+            if !valid {
+                return Err(format!("invalid opening of p_{}", i));
+            }
+        }
+        Ok((rho, r))
+    }
+}
+
+/// Checks the round polynomials of the proof against the challenges ρ and r,
+/// the last one against g(r) + ρ p(r) with the p_i(r_i) claimed in the proof.
+/// The openings of the mask are not checked.
+pub fn check_rounds<F: Field, P: UnivariatePcs<F>, O: Oracle<F, Point = [F]>>(
+    g: &O,
+    degrees: &[usize],
+    claim: F,
+    proof: &ZkSumcheckProof<F, P>,
+    rho: F,
+    r: &[F],
+) -> Result<(), String> {
+    let n = degrees.len();
+    if proof.round_polys.len() != n || proof.mask_evals.len() != n || r.len() != n {
+        return Err("malformed proof".to_string());
+    }
+    if let Some(i) = (0..n).find(|i| proof.round_polys[*i].degree() > degrees[*i]) {
+        return Err(format!("round {}: degree too high", i));
+    }
+    let oracle = MaskedOracle {
+        g,
+        rho,
+        mask_eval: proof.mask_evals.iter().sum(),
+    };
+    let mut verifier = VerifierState::with_oracle(claim + rho * proof.mask_sum, n, oracle);
+    for (round_poly, r_i) in proof.round_polys.iter().zip(r) {
+        verifier.check_round(round_poly.clone(), *r_i)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::kzg::Srs;
+    use crate::pcs::{IdealOracle, PolynomialCommitment};
+    use crate::sumcheck::ProverState;
+    use crate::test_fields::F17;
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+    use ark_test_curves::bls12_381::{Bls12_381, Fr};
+
+    type Ideal = IdealOracle<DensePolynomial<Fr>>;
+
+    // 2 x_0^3 + x_0 x_2 + x_1 x_2 + 5
+    fn sample_poly() -> SparsePolynomial<Fr, SparseTerm> {
+        SparsePolynomial::from_coefficients_vec(
+            3,
+            vec![
+                (Fr::from(2), SparseTerm::new(vec![(0, 3)])),
+                (Fr::from(1), SparseTerm::new(vec![(0, 1), (2, 1)])),
+                (Fr::from(1), SparseTerm::new(vec![(1, 1), (2, 1)])),
+                (Fr::from(5), SparseTerm::new(vec![])),
+            ],
+        )
+    }
+
+    // messages of the prover for the given mask and challenges: the sum of the
+    // mask, the round polynomials and p(r)
+    fn messages<F: Field>(
+        g: &SparsePolynomial<F, SparseTerm>,
+        p: &MaskingPolynomial<F>,
+        rho: F,
+        r: &[F],
+    ) -> (F, Vec<UnivariatePolynomial<F>>, F) {
+        let mut prover = ProverState::new(mask(g, p, rho));
+        let round_polys = r
+            .iter()
+            .map(|r_i| {
+                let round_poly = prover.calculate_round_poly();
+                prover.update_random_vars(*r_i);
+                round_poly
+            })
+            .collect();
+        (p.hypercube_sum(), round_polys, p.evaluate(r))
+    }
+
+    // g'(x) = c + (g(r) - c) prod_i (2 x_i - 1) / (2 r_i - 1), which sums to the
+    // claim with c = claim / 2^n and agrees with g at r
+    fn simulated_poly<F: Field>(claim: F, g_at_r: F, r: &[F]) -> SparsePolynomial<F, SparseTerm> {
+        let n = r.len();
+        let c = claim / F::from(2u64).pow([n as u64]);
+        let scale = r
+            .iter()
+            .fold(g_at_r - c, |acc, r_i| acc / (r_i.double() - F::ONE));
+        // prod_i (2 x_i - 1) expanded over the subsets of the variables
+        let terms = (0..1usize << n)
+            .map(|subset| {
+                let vars: Vec<(usize, usize)> = (0..n)
+                    .filter(|i| subset >> i & 1 == 1)
+                    .map(|i| (i, 1))
+                    .collect();
+                let sign = if (n - vars.len()).is_multiple_of(2) {
+                    F::ONE
+                } else {
+                    -F::ONE
+                };
+                let coeff = sign * F::from(2u64).pow([vars.len() as u64]) * scale;
+                (coeff, SparseTerm::new(vars))
+            })
+            .chain(std::iter::once((c, SparseTerm::new(vec![]))))
+            .collect();
+        SparsePolynomial::from_coefficients_vec(n, terms)
+    }
+
+    // Honest-verifier simulator: knows the challenges in advance, the claim and
+    // g(r) from one query to the oracle, but not g. It runs the prover on the
+    // simulated polynomial with a fresh mask.
+    fn simulate<F: Field, R: RngCore>(
+        claim: F,
+        g_at_r: F,
+        degrees: &[usize],
+        rho: F,
+        r: &[F],
+        rng: &mut R,
+    ) -> (F, Vec<UnivariatePolynomial<F>>, F) {
+        let g_sim = simulated_poly(claim, g_at_r, r);
+        messages(&g_sim, &MaskingPolynomial::rand(degrees, rng), rho, r)
+    }
+
+    /// The simulator above as a proof, with the mask committed and opened at r,
+    /// for the protocols built on the sumcheck
+    pub(crate) fn simulate_proof<F: Field, P: UnivariatePcs<F>, R: RngCore>(
+        pcs: &P,
+        claim: F,
+        g_at_r: F,
+        degrees: &[usize],
+        (rho, r): (F, &[F]),
+        rng: &mut R,
+    ) -> ZkSumcheckProof<F, P> {
+        let p = MaskingPolynomial::rand(degrees, rng);
+        let (mask_sum, round_polys, _) = messages(&simulated_poly(claim, g_at_r, r), &p, rho, r);
+        let mut transcript = Transcript::new(b"simulator");
+        let (mask_commitments, (mask_evals, openings)): (Vec<_>, (Vec<_>, Vec<_>)) = p
+            .univariates()
+            .iter()
+            .zip(r)
+            .map(|(p_i, r_i)| {
+                let (commitment, data) = pcs.commit(p_i);
+                (commitment, pcs.open(p_i, &data, r_i, &mut transcript))
+            })
+            .unzip();
+        ZkSumcheckProof {
+            mask_commitments,
+            mask_sum,
+            round_polys,
+            mask_evals,
+            openings,
+        }
+    }
+
+    #[test]
+    fn test_masking_polynomial() {
+        let mut rng = test_rng();
+        let p = MaskingPolynomial::<Fr>::rand(&[3, 1, 2], &mut rng);
+        let sparse = mask(
+            &SparsePolynomial::from_coefficients_vec(3, vec![]),
+            &p,
+            Fr::ONE,
+        );
+        assert_eq!(p.hypercube_sum(), sparse.hypercube_sum());
+        let point: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(p.evaluate(&point), sparse.evaluate(&point.to_vec()));
+        assert_eq!(variable_degrees(&sample_poly()), vec![3, 1, 1]);
+    }
+
+    #[test]
+    fn test_masked_polynomial() {
+        // the prover's view of g + ρ p agrees with its expansion in every round
+        let mut rng = test_rng();
+        let g = sample_poly();
+        let p = MaskingPolynomial::<Fr>::rand(&[3, 1, 2], &mut rng);
+        let rho = Fr::rand(&mut rng);
+        let mut masked = MaskedPolynomial::new(g.clone(), p.clone(), rho);
+        let mut expanded = mask(&g, &p, rho);
+        for _ in 0..3 {
+            assert_eq!(masked.hypercube_sum(), expanded.hypercube_sum());
+            assert_eq!(masked.round_poly(), expanded.round_poly());
+            let r = Fr::rand(&mut rng);
+            masked.fix_first_variable(r);
+            expanded.fix_first_variable(r);
+        }
+        assert_eq!(masked.hypercube_sum(), expanded.hypercube_sum());
+    }
+
+    #[test]
+    fn test_zk_sumcheck() {
+        let mut rng = test_rng();
+        let g = sample_poly();
+        let zk = ZkSumcheck::<Fr, Srs<Bls12_381>>::new(3, &mut rng);
+        let (sum, proof, r) = zk.prove(&g, &mut rng, &mut Transcript::new(b"test"));
+        assert_eq!(sum, g.hypercube_sum());
+        let degrees = variable_degrees(&g);
+        let result = zk.verify(&g, &degrees, sum, &proof, &mut Transcript::new(b"test"));
+        assert_eq!(result, Ok(r));
+    }
+
+    #[test]
+    fn test_wrong_sum() {
+        let mut rng = test_rng();
+        let g = sample_poly();
+        let zk = ZkSumcheck::<Fr, Ideal>::new(3, &mut rng);
+        let degrees = variable_degrees(&g);
+        let (sum, proof, _) = zk.prove(&g, &mut rng, &mut Transcript::new(b"test"));
+        let wrong = sum + Fr::ONE;
+        let result = zk.verify(&g, &degrees, wrong, &proof, &mut Transcript::new(b"test"));
+        assert!(result.is_err());
+
+        let mut tampered = proof;
+        tampered.mask_evals[0] += Fr::ONE;
+        let result = zk.verify(&g, &degrees, sum, &tampered, &mut Transcript::new(b"test"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_simulator_accepted() {
+        let mut rng = test_rng();
+        let g = sample_poly();
+        let degrees = variable_degrees(&g);
+        let claim = g.hypercube_sum();
+        let rho = Fr::rand(&mut rng);
+        let r: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        let g_at_r = g.query(&r);
+        let (mask_sum, round_polys, mask_eval) =
+            simulate(claim, g_at_r, &degrees, rho, &r, &mut rng);
+        let oracle = MaskedOracle {
+            g: &g,
+            rho,
+            mask_eval,
+        };
+        let mut verifier = VerifierState::with_oracle(claim + rho * mask_sum, 3, oracle);
+        for (round_poly, r_i) in round_polys.into_iter().zip(&r) {
+            assert!(round_poly.degree() <= 3);
+            assert_eq!(verifier.check_round(round_poly, *r_i), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_simulated_proof() {
+        let mut rng = test_rng();
+        let g = sample_poly();
+        let degrees = variable_degrees(&g);
+        let claim = g.hypercube_sum();
+        let rho = Fr::rand(&mut rng);
+        let r: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        let pcs = Ideal::setup(4, &mut rng);
+        let proof = simulate_proof(&pcs, claim, g.query(&r), &degrees, (rho, &r), &mut rng);
+        assert_eq!(check_rounds(&g, &degrees, claim, &proof, rho, &r), Ok(()));
+    }
+
+    #[test]
+    fn test_simulator_distribution() {
+        // over F17, every mask of a multilinear g in 2 variables gives the same
+        // distribution of messages as the simulator. Fixing the constant of p_1
+        // to 0 still covers every p, whose constant is p_0(0) + p_1(0).
+        let g = SparsePolynomial::from_coefficients_vec(
+            2,
+            vec![
+                (F17::from(3), SparseTerm::new(vec![(0, 1), (1, 1)])),
+                (F17::from(5), SparseTerm::new(vec![(1, 1)])),
+                (F17::from(1), SparseTerm::new(vec![])),
+            ],
+        );
+        let (rho, r) = (F17::from(2), [F17::from(3), F17::from(5)]);
+        let claim = g.hypercube_sum();
+        let masks = (0..17u64 * 17 * 17).map(|i| {
+            let f = |j: u32| F17::from(i / 17u64.pow(j) % 17);
+            MaskingPolynomial::new(vec![
+                DensePolynomial::from_coefficients_vec(vec![f(0), f(1)]),
+                DensePolynomial::from_coefficients_vec(vec![F17::ZERO, f(2)]),
+            ])
+        });
+        let flatten =
+            |(mask_sum, round_polys, mask_eval): (F17, Vec<UnivariatePolynomial<F17>>, F17)| {
+                let mut values = vec![mask_sum, mask_eval];
+                for poly in round_polys {
+                    values.push(poly.evaluate(&F17::ZERO));
+                    values.push(poly.evaluate(&F17::ONE));
+                }
+                values
+            };
+        let g_at_r = g.query(&r);
+        let simulated = simulated_poly(claim, g_at_r, &r);
+        assert_eq!(simulated.hypercube_sum(), claim);
+        assert_eq!(simulated.query(&r), g_at_r);
+
+        let mut real: Vec<_> = masks
+            .clone()
+            .map(|p| flatten(messages(&g, &p, rho, &r)))
+            .collect();
+        let mut ideal: Vec<_> = masks
+            .map(|p| flatten(messages(&simulated, &p, rho, &r)))
+            .collect();
+        real.sort();
+        ideal.sort();
+        assert!(real == ideal);
+    }
+}
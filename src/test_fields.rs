@@ -0,0 +1,23 @@
+//! Small prime fields for the tests, where values can be checked by hand.
+// `#[derive(MontConfig)]` puts its impls inside a function
+#![allow(non_local_definitions)]
+use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+
+#[derive(MontConfig)]
+#[modulus = "17"]
+#[generator = "3"]
+pub struct F17Config;
+pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "101"]
+#[generator = "2"]
+pub struct F101Config;
+pub type F101 = Fp64<MontBackend<F101Config, 1>>;
+
+/// Same field as in the grand product notebook, 120.next_prime()
+#[derive(MontConfig)]
+#[modulus = "127"]
+#[generator = "3"]
+pub struct F127Config;
+pub type F127 = Fp64<MontBackend<F127Config, 1>>;
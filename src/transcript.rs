@@ -0,0 +1,118 @@
+use ark_ff::Field;
+use ark_serialize::CanonicalSerialize;
+use sha2::{Digest, Sha256};
+
+/// Fiat-Shamir transcript. Every message is absorbed into a running SHA-256
+/// state and the verifier challenges are derived from it, so prover and
+/// verifier obtain the same challenges as long as they absorb the same messages.
+#[derive(Clone)]
+pub struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript { state: [0; 32] };
+        transcript.append_message(b"domain-separator", label);
+        transcript
+    }
+
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.state);
+        hasher.update((label.len() as u64).to_le_bytes());
+        hasher.update(label);
+        hasher.update((message.len() as u64).to_le_bytes());
+        hasher.update(message);
+        self.state = hasher.finalize().into();
+    }
+
+    pub fn append_serializable<T: CanonicalSerialize + ?Sized>(&mut self, label: &[u8], item: &T) {
+        let mut bytes = Vec::new();
+        item.serialize_compressed(&mut bytes)
+            .expect("serialization into a vector does not fail");
+        self.append_message(label, &bytes);
+    }
+
+    pub fn append_field<F: Field>(&mut self, label: &[u8], element: &F) {
+        self.append_serializable(label, element);
+    }
+
+    /// Squeezes n pseudorandom bytes, updating the state
+    pub fn challenge_bytes(&mut self, label: &[u8], n: usize) -> Vec<u8> {
+        self.append_message(b"challenge", label);
+        let mut bytes = Vec::with_capacity(n);
+        let mut counter = 0u64;
+        while bytes.len() < n {
+            let mut hasher = Sha256::new();
+            hasher.update(self.state);
+            hasher.update(counter.to_le_bytes());
+            bytes.extend_from_slice(&hasher.finalize());
+            counter += 1;
+        }
+        bytes.truncate(n);
+        self.append_message(b"squeezed", &bytes);
+        bytes
+    }
+
+    pub fn challenge_field<F: Field>(&mut self, label: &[u8]) -> F {
+        // twice the size of the field, rejection sampling rarely needs a retry
+        let size = 2 * F::ZERO.uncompressed_size();
+        loop {
+            if let Some(element) = F::from_random_bytes(&self.challenge_bytes(label, size)) {
+                return element;
+            }
+        }
+    }
+
+    pub fn challenge_fields<F: Field>(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge_field(label)).collect()
+    }
+
+    /// Uniform index in [0, bound)
+    pub fn challenge_index(&mut self, label: &[u8], bound: usize) -> usize {
+        assert!(bound > 0, "empty range");
+        let bytes = self.challenge_bytes(label, 16);
+        let value = u128::from_le_bytes(bytes.try_into().unwrap());
+        (value % bound as u128) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    #[test]
+    fn test_same_messages_same_challenges() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_field(b"x", &Fr::from(5));
+        t2.append_field(b"x", &Fr::from(5));
+        assert_eq!(
+            t1.challenge_field::<Fr>(b"r"),
+            t2.challenge_field::<Fr>(b"r")
+        );
+        assert_eq!(t1.challenge_index(b"i", 100), t2.challenge_index(b"i", 100));
+    }
+
+    #[test]
+    fn test_different_messages_different_challenges() {
+        let mut t1 = Transcript::new(b"test");
+        let mut t2 = Transcript::new(b"test");
+        t1.append_field(b"x", &Fr::from(5));
+        t2.append_field(b"x", &Fr::from(6));
+        assert_ne!(
+            t1.challenge_field::<Fr>(b"r"),
+            t2.challenge_field::<Fr>(b"r")
+        );
+    }
+
+    #[test]
+    fn test_consecutive_challenges_differ() {
+        let mut t = Transcript::new(b"test");
+        let r1: Fr = t.challenge_field(b"r");
+        let r2: Fr = t.challenge_field(b"r");
+        assert_ne!(r1, r2);
+    }
+}
@@ -0,0 +1,275 @@
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial, SparsePolynomial};
+use ark_poly::{DenseUVPolynomial, EvaluationDomain, Polynomial};
+
+/// Returns the lagrange interpolation of the given points (x_i, y_i).
+/// The x_i must be pairwise distinct.
+pub fn lagrange_interpolate<F: Field>(points: &[(F, F)]) -> DensePolynomial<F> {
+    let xs: Vec<F> = points.iter().map(|(x, _)| *x).collect();
+    let weights = barycentric_weights(&xs);
+    let z = vanishing_polynomial(&xs);
+
+    // L(x) = sum_i y_i * w_i * z(x) / (x - x_i)
+    let mut result = DensePolynomial::zero();
+    for ((x_i, y_i), w_i) in points.iter().zip(weights) {
+        let (basis, _) = divide_by_linear(&z, *x_i);
+        result += (*y_i * w_i, &basis);
+    }
+    result
+}
+
+/// Interpolates the evaluations of a polynomial over a FFT domain
+pub fn interpolate_over_domain<F: FftField, D: EvaluationDomain<F>>(
+    domain: &D,
+    evals: &[F],
+) -> DensePolynomial<F> {
+    assert!(
+        evals.len() <= domain.size(),
+        "more evaluations than elements in the domain"
+    );
+    DensePolynomial::from_coefficients_vec(domain.ifft(evals))
+}
+
+/// Calculates Z(x) = prod_i (x - a_i)
+pub fn vanishing_polynomial<F: Field>(points: &[F]) -> DensePolynomial<F> {
+    points.iter().fold(
+        DensePolynomial::from_coefficients_vec(vec![F::ONE]),
+        |z, a| z.naive_mul(&DensePolynomial::from_coefficients_vec(vec![-*a, F::ONE])),
+    )
+}
+
+/// Vanishing polynomial of a FFT domain, Z_H(x) = x^n - 1
+pub fn domain_vanishing_polynomial<F: FftField, D: EvaluationDomain<F>>(
+    domain: &D,
+) -> SparsePolynomial<F> {
+    domain.vanishing_polynomial()
+}
+
+/// Divides the given polynomials and returns (quotient, remainder)
+pub fn quo_rem<'a, F: Field>(
+    dividend: impl Into<DenseOrSparsePolynomial<'a, F>>,
+    divisor: impl Into<DenseOrSparsePolynomial<'a, F>>,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    let divisor = divisor.into();
+    assert!(!divisor.is_zero(), "division by the zero polynomial");
+    dividend
+        .into()
+        .divide_with_q_and_r(&divisor)
+        .expect("divisor is not zero")
+}
+
+/// Divides the polynomial by the vanishing polynomial of the given points
+pub fn divide_by_vanishing<F: Field>(
+    poly: &DensePolynomial<F>,
+    points: &[F],
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    quo_rem(poly, vanishing_polynomial(points))
+}
+
+/// Divides the polynomial by the vanishing polynomial of a FFT domain. Since
+/// Z_H(x) = x^n - 1 is sparse, this runs in linear time.
+pub fn divide_by_domain_vanishing<F: FftField, D: EvaluationDomain<F>>(
+    poly: &DensePolynomial<F>,
+    domain: &D,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    poly.divide_by_vanishing_poly(*domain)
+        .expect("vanishing polynomial is not zero")
+}
+
+/// Synthetic division by (x - a). Returns the quotient and f(a)
+pub fn divide_by_linear<F: Field>(poly: &DensePolynomial<F>, a: F) -> (DensePolynomial<F>, F) {
+    if poly.coeffs.is_empty() {
+        return (DensePolynomial::zero(), F::ZERO);
+    }
+    let mut quotient = vec![F::ZERO; poly.coeffs.len() - 1];
+    let mut carry = F::ZERO;
+    for (i, coeff) in poly.coeffs.iter().enumerate().rev() {
+        carry = carry * a + coeff;
+        if i > 0 {
+            quotient[i - 1] = carry;
+        }
+    }
+    (DensePolynomial::from_coefficients_vec(quotient), carry)
+}
+
+/// Evaluates the polynomial at every given point
+pub fn evaluate_many<F: Field>(poly: &DensePolynomial<F>, points: &[F]) -> Vec<F> {
+    points.iter().map(|x| poly.evaluate(x)).collect()
+}
+
+/// Returns f(cX), scaling the i-th coefficient by c^i. With c = ω this is the
+/// polynomial whose evaluations over a FFT domain are those of f shifted by one
+pub fn scale_argument<F: Field>(poly: &DensePolynomial<F>, c: F) -> DensePolynomial<F> {
+    let mut power = F::ONE;
+    let coeffs = poly
+        .coeffs
+        .iter()
+        .map(|coeff| {
+            let scaled = *coeff * power;
+            power *= c;
+            scaled
+        })
+        .collect();
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// Calculates the barycentric weights w_i = 1 / prod_{j != i} (x_i - x_j)
+pub fn barycentric_weights<F: Field>(points: &[F]) -> Vec<F> {
+    let mut weights: Vec<F> = points
+        .iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i != *j)
+                .fold(F::ONE, |acc, (_, x_j)| acc * (*x_i - x_j))
+        })
+        .collect();
+    ark_ff::batch_inversion(&mut weights);
+    assert!(
+        weights.iter().all(|w| !w.is_zero()),
+        "interpolation points must be distinct"
+    );
+    weights
+}
+
+/// Evaluates at x the polynomial interpolating (points_i, evals_i), without
+/// calculating its coefficients. Weights are the result of `barycentric_weights`
+pub fn barycentric_evaluate<F: Field>(points: &[F], evals: &[F], weights: &[F], x: F) -> F {
+    assert_eq!(points.len(), evals.len());
+    assert_eq!(points.len(), weights.len());
+    if let Some(i) = points.iter().position(|x_i| *x_i == x) {
+        return evals[i];
+    }
+
+    // f(x) = l(x) * sum_i w_i * y_i / (x - x_i), with l(x) = prod_i (x - x_i)
+    let mut diffs: Vec<F> = points.iter().map(|x_i| x - x_i).collect();
+    let l = diffs.iter().product::<F>();
+    ark_ff::batch_inversion(&mut diffs);
+    let sum = diffs
+        .iter()
+        .zip(weights.iter().zip(evals))
+        .fold(F::ZERO, |acc, (d, (w, y))| acc + *d * w * y);
+    l * sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fields::F17;
+    use ark_poly::Radix2EvaluationDomain;
+    use ark_test_curves::bls12_381::Fr;
+
+    // 2x^3 + x + 1
+    fn setup() -> DensePolynomial<F17> {
+        DensePolynomial::from_coefficients_vec(vec![
+            F17::from(1),
+            F17::from(1),
+            F17::from(0),
+            F17::from(2),
+        ])
+    }
+
+    #[test]
+    fn test_lagrange_interpolate() {
+        let poly = setup();
+        let points: Vec<(F17, F17)> = (0..4)
+            .map(|i| (F17::from(i), poly.evaluate(&F17::from(i))))
+            .collect();
+        assert_eq!(lagrange_interpolate(&points), poly);
+    }
+
+    #[test]
+    fn test_interpolate_over_domain() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(4).unwrap();
+        let poly = DensePolynomial::from_coefficients_vec(vec![
+            Fr::from(3),
+            Fr::from(0),
+            Fr::from(7),
+            Fr::from(1),
+        ]);
+        let evals = evaluate_many(&poly, &domain.elements().collect::<Vec<_>>());
+        assert_eq!(interpolate_over_domain(&domain, &evals), poly);
+    }
+
+    #[test]
+    fn test_quo_rem() {
+        let poly = setup();
+        let divisor = vanishing_polynomial(&[F17::from(2), F17::from(5)]);
+        let (q, r) = quo_rem(&poly, &divisor);
+        assert!(r.degree() < divisor.degree());
+        assert_eq!(&q.naive_mul(&divisor) + &r, poly);
+    }
+
+    #[test]
+    fn test_divide_by_vanishing() {
+        let points = [F17::from(1), F17::from(3), F17::from(4)];
+        let z = vanishing_polynomial(&points);
+        assert!(points.iter().all(|p| z.evaluate(p).is_zero()));
+
+        let poly = z.naive_mul(&setup());
+        let (q, r) = divide_by_vanishing(&poly, &points);
+        assert!(r.is_zero());
+        assert_eq!(q, setup());
+    }
+
+    #[test]
+    fn test_divide_by_domain_vanishing() {
+        let domain = Radix2EvaluationDomain::<Fr>::new(4).unwrap();
+        let z: DensePolynomial<Fr> = domain_vanishing_polynomial(&domain).into();
+        let f = DensePolynomial::from_coefficients_vec(vec![Fr::from(5), Fr::from(2)]);
+        let (q, r) = divide_by_domain_vanishing(&(&z * &f), &domain);
+        assert!(r.is_zero());
+        assert_eq!(q, f);
+    }
+
+    #[test]
+    fn test_divide_by_linear() {
+        let poly = setup();
+        let (q, eval) = divide_by_linear(&poly, F17::from(3));
+        assert_eq!(eval, poly.evaluate(&F17::from(3)));
+        let (should, _) = quo_rem(&poly, vanishing_polynomial(&[F17::from(3)]));
+        assert_eq!(q, should);
+    }
+
+    #[test]
+    fn test_scale_argument() {
+        let poly = setup();
+        let scaled = scale_argument(&poly, F17::from(5));
+        for x in 0..17 {
+            let x = F17::from(x);
+            assert_eq!(scaled.evaluate(&x), poly.evaluate(&(x * F17::from(5))));
+        }
+    }
+
+    #[test]
+    fn test_barycentric_evaluate() {
+        let poly = setup();
+        let points: Vec<F17> = [2, 6, 9, 11].into_iter().map(F17::from).collect();
+        let evals = evaluate_many(&poly, &points);
+        let weights = barycentric_weights(&points);
+        for x in 0..17 {
+            let x = F17::from(x);
+            assert_eq!(
+                barycentric_evaluate(&points, &evals, &weights, x),
+                poly.evaluate(&x)
+            );
+        }
+    }
+
+    #[test]
+    fn test_sparse_interoperability() {
+        let sparse = SparsePolynomial::from_coefficients_vec(vec![
+            (3, F17::from(2)),
+            (1, F17::from(1)),
+            (0, F17::from(1)),
+        ]);
+        let (q, r) = quo_rem(&sparse, vanishing_polynomial(&[F17::from(1)]));
+        assert_eq!(r.evaluate(&F17::ZERO), sparse.evaluate(&F17::ONE));
+        assert_eq!(
+            &q.naive_mul(&vanishing_polynomial(&[F17::ONE])) + &r,
+            setup()
+        );
+    }
+}